@@ -0,0 +1,64 @@
+//! Register bank storage benchmarks: sparse `HashMap` vs dense backend
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use voltage_modbus::{ModbusRegisterBank, StorageBackend};
+
+const BACKENDS: [StorageBackend; 2] = [StorageBackend::Sparse, StorageBackend::Dense];
+
+fn populated(backend: StorageBackend) -> ModbusRegisterBank {
+    let bank = ModbusRegisterBank::with_backend(backend);
+    let values: Vec<u16> = (0..125).collect();
+    for start in (0..10_000).step_by(125) {
+        bank.write_10(start, &values).unwrap();
+        bank.write_0f(start, &[true; 125]).unwrap();
+    }
+    bank
+}
+
+fn read_holding_registers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_125_registers");
+    for backend in BACKENDS {
+        let bank = populated(backend);
+        group.bench_with_input(BenchmarkId::new("vec", format!("{:?}", backend)), &bank, |b, bank| {
+            b.iter(|| black_box(bank.read_03(black_box(1000), 125).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("into", format!("{:?}", backend)), &bank, |b, bank| {
+            let mut buffer = [0u16; 125];
+            b.iter(|| {
+                bank.read_holding_registers_into(black_box(1000), &mut buffer).unwrap();
+                black_box(&buffer);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn read_coils(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_2000_coils");
+    for backend in BACKENDS {
+        let bank = populated(backend);
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", backend)), &bank, |b, bank| {
+            let mut buffer = [false; 2000];
+            b.iter(|| {
+                bank.read_coils_into(black_box(1000), &mut buffer).unwrap();
+                black_box(&buffer);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn write_holding_registers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("write_123_registers");
+    let values: Vec<u16> = (0..123).collect();
+    for backend in BACKENDS {
+        let bank = populated(backend);
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{:?}", backend)), &bank, |b, bank| {
+            b.iter(|| bank.write_10(black_box(2000), &values).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read_holding_registers, read_coils, write_holding_registers);
+criterion_main!(benches);
@@ -0,0 +1,261 @@
+//! # Address Notation
+//!
+//! Device documentation rarely uses the 0-based protocol addresses sent on the
+//! wire. This module converts the common notations into a [`DataAddress`]
+//! (table plus 0-based offset) and back:
+//!
+//! | Notation | Example | Table | Offset |
+//! |----------|---------|-------|--------|
+//! | Modicon, 5 digits | `40001` | holding register | 0 |
+//! | Modicon, 6 digits | `400101` | holding register | 100 |
+//! | Modicon, 5 digits | `30010` | input register | 9 |
+//! | IEC 61131 | `%MW100` | holding register | 100 |
+//! | IEC 61131 | `%IX2.3` | discrete input | 19 |
+//!
+//! The leading Modicon digit selects the table (`0` coils, `1` discrete inputs,
+//! `3` input registers, `4` holding registers) and the remaining digits are a
+//! 1-based register number. IEC names are 0-based; `%M`/`%MX`/`%Q`/`%QX` are
+//! coils, `%I`/`%IX` discrete inputs, `%IW` input registers and `%MW`/`%QW`
+//! holding registers. Bit names accept `byte.bit` offsets.
+//!
+//! [`AddressingMode::OneBased`] lets clients and register banks take 1-based
+//! register numbers directly.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::address::{AddressNotation, DataAddress};
+//! use voltage_modbus::RegisterTable;
+//!
+//! let address: DataAddress = "400101".parse().unwrap();
+//! assert_eq!(address, DataAddress::new(RegisterTable::HoldingRegister, 100));
+//! assert_eq!(address.to_string(), "40101");
+//! assert_eq!(address.format(AddressNotation::Iec).unwrap(), "%MW100");
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusAddress, RegisterTable};
+
+/// How addresses passed to clients and register banks are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressingMode {
+    /// Addresses are protocol addresses (register 1 is address 0)
+    #[default]
+    ZeroBased,
+    /// Addresses are 1-based register numbers (register 1 is address 1)
+    OneBased,
+}
+
+impl AddressingMode {
+    /// Convert an address in this mode to a protocol address
+    pub fn to_wire(self, address: u16) -> ModbusResult<ModbusAddress> {
+        match self {
+            AddressingMode::ZeroBased => Ok(address),
+            AddressingMode::OneBased => address
+                .checked_sub(1)
+                .ok_or_else(|| ModbusError::invalid_data("Address 0 does not exist in 1-based addressing")),
+        }
+    }
+
+    /// Convert a protocol address to an address in this mode
+    pub fn from_wire(self, address: ModbusAddress) -> ModbusResult<u16> {
+        match self {
+            AddressingMode::ZeroBased => Ok(address),
+            AddressingMode::OneBased => address
+                .checked_add(1)
+                .ok_or_else(|| ModbusError::invalid_data(format!("Address {} cannot be expressed in 1-based addressing", address))),
+        }
+    }
+}
+
+/// Address notations understood by [`DataAddress`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressNotation {
+    /// Table digit and 4-digit register number, e.g. `40001` (offsets up to 9998)
+    Modicon5,
+    /// Table digit and 5-digit register number, e.g. `400001`
+    Modicon6,
+    /// IEC 61131 name, e.g. `%MW0`
+    Iec,
+}
+
+/// A Modbus data table and 0-based offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataAddress {
+    /// Data table
+    pub table: RegisterTable,
+    /// 0-based protocol address within the table
+    pub address: ModbusAddress,
+}
+
+impl DataAddress {
+    /// Create an address from a table and 0-based offset
+    pub const fn new(table: RegisterTable, address: ModbusAddress) -> Self {
+        Self { table, address }
+    }
+
+    /// Parse Modicon (`40001`, `400001`) or IEC (`%MW0`) notation
+    pub fn parse(text: &str) -> ModbusResult<Self> {
+        let text = text.trim();
+        let parsed = match text.strip_prefix('%') {
+            Some(name) => Self::parse_iec(name),
+            None => Self::parse_modicon(text),
+        };
+        parsed.ok_or_else(|| ModbusError::invalid_data(format!("Invalid Modbus address '{}'", text)))
+    }
+
+    /// 1-based register number within the table
+    pub fn number(&self) -> u32 {
+        self.address as u32 + 1
+    }
+
+    /// Format in the given notation
+    ///
+    /// # Returns
+    ///
+    /// The formatted address, or an error for offsets above 9998 in 5-digit notation
+    pub fn format(&self, notation: AddressNotation) -> ModbusResult<String> {
+        let digit = Self::table_digit(self.table);
+        match notation {
+            AddressNotation::Modicon5 if self.number() > 9999 => Err(ModbusError::invalid_data(format!(
+                "Offset {} does not fit 5-digit Modicon notation",
+                self.address
+            ))),
+            AddressNotation::Modicon5 => Ok(format!("{}{:04}", digit, self.number())),
+            AddressNotation::Modicon6 => Ok(format!("{}{:05}", digit, self.number())),
+            AddressNotation::Iec => Ok(self.to_iec()),
+        }
+    }
+
+    /// Format in Modicon notation, using 6 digits only when 5 are not enough
+    pub fn to_modicon(&self) -> String {
+        let digit = Self::table_digit(self.table);
+        if self.number() > 9999 {
+            format!("{}{:05}", digit, self.number())
+        } else {
+            format!("{}{:04}", digit, self.number())
+        }
+    }
+
+    /// Format as an IEC 61131 name
+    pub fn to_iec(&self) -> String {
+        let prefix = match self.table {
+            RegisterTable::Coil => "%M",
+            RegisterTable::DiscreteInput => "%I",
+            RegisterTable::InputRegister => "%IW",
+            RegisterTable::HoldingRegister => "%MW",
+        };
+        format!("{}{}", prefix, self.address)
+    }
+
+    fn table_digit(table: RegisterTable) -> char {
+        match table {
+            RegisterTable::Coil => '0',
+            RegisterTable::DiscreteInput => '1',
+            RegisterTable::InputRegister => '3',
+            RegisterTable::HoldingRegister => '4',
+        }
+    }
+
+    fn parse_modicon(text: &str) -> Option<Self> {
+        if !(text.len() == 5 || text.len() == 6) || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let table = match text.as_bytes()[0] {
+            b'0' => RegisterTable::Coil,
+            b'1' => RegisterTable::DiscreteInput,
+            b'3' => RegisterTable::InputRegister,
+            b'4' => RegisterTable::HoldingRegister,
+            _ => return None,
+        };
+        let number: u32 = text[1..].parse().ok()?;
+        let address = u16::try_from(number.checked_sub(1)?).ok()?;
+        Some(Self::new(table, address))
+    }
+
+    fn parse_iec(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let (prefix, offset) = name.split_at(split);
+        let table = match prefix {
+            "M" | "MX" | "Q" | "QX" => RegisterTable::Coil,
+            "I" | "IX" => RegisterTable::DiscreteInput,
+            "IW" => RegisterTable::InputRegister,
+            "MW" | "QW" => RegisterTable::HoldingRegister,
+            _ => return None,
+        };
+        let address = match offset.split_once('.') {
+            Some((byte, bit)) if table.is_bit_table() => {
+                let (byte, bit): (u32, u32) = (byte.parse().ok()?, bit.parse().ok()?);
+                if bit > 7 {
+                    return None;
+                }
+                u16::try_from(byte * 8 + bit).ok()?
+            }
+            Some(_) => return None,
+            None => offset.parse().ok()?,
+        };
+        Some(Self::new(table, address))
+    }
+}
+
+impl FromStr for DataAddress {
+    type Err = ModbusError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl fmt::Display for DataAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_modicon())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notations() {
+        let parse = |text: &str| DataAddress::parse(text).unwrap();
+        assert_eq!(parse("40001"), DataAddress::new(RegisterTable::HoldingRegister, 0));
+        assert_eq!(parse("400101"), DataAddress::new(RegisterTable::HoldingRegister, 100));
+        assert_eq!(parse("30010"), DataAddress::new(RegisterTable::InputRegister, 9));
+        assert_eq!(parse("00001"), DataAddress::new(RegisterTable::Coil, 0));
+        assert_eq!(parse("465536"), DataAddress::new(RegisterTable::HoldingRegister, 65535));
+        assert_eq!(parse("%MW100"), DataAddress::new(RegisterTable::HoldingRegister, 100));
+        assert_eq!(parse("%iw5"), DataAddress::new(RegisterTable::InputRegister, 5));
+        assert_eq!(parse("%IX2.3"), DataAddress::new(RegisterTable::DiscreteInput, 19));
+        assert_eq!(parse("%M7"), DataAddress::new(RegisterTable::Coil, 7));
+
+        for invalid in ["40000", "465537", "20001", "4001", "4000001", "%MW", "%XW1", "%MW1.2", "%IX1.8", "abc"] {
+            assert!(DataAddress::parse(invalid).is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[test]
+    fn test_format_and_addressing_mode() {
+        let address = DataAddress::new(RegisterTable::InputRegister, 9);
+        assert_eq!(address.to_string(), "30010");
+        assert_eq!(address.format(AddressNotation::Modicon6).unwrap(), "300010");
+        assert_eq!(address.format(AddressNotation::Iec).unwrap(), "%IW9");
+
+        let high = DataAddress::new(RegisterTable::HoldingRegister, 20000);
+        assert_eq!(high.to_string(), "420001");
+        assert!(high.format(AddressNotation::Modicon5).is_err());
+        assert_eq!(high.to_string().parse::<DataAddress>().unwrap(), high);
+
+        assert_eq!(AddressingMode::OneBased.to_wire(1).unwrap(), 0);
+        assert!(AddressingMode::OneBased.to_wire(0).is_err());
+        assert!(AddressingMode::OneBased.from_wire(65535).is_err());
+        assert_eq!(AddressingMode::ZeroBased.to_wire(0).unwrap(), 0);
+    }
+}
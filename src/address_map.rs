@@ -0,0 +1,437 @@
+//! # Address Maps
+//!
+//! A register bank without an address map accepts every address and reads
+//! unset points as zero, so a master with a typo'd address never notices.
+//! An [`AddressMap`] declares what the simulated device actually implements:
+//!
+//! - **blocks**: valid address ranges per table, each `read`, `write` or
+//!   `read_write` (default)
+//! - **constraints**: allowed values of individual registers, as a `min`/`max`
+//!   range (optionally `signed`) and/or a list of allowed `values`
+//!
+//! Once a map is attached with
+//! [`ModbusRegisterBank::with_address_map`](crate::register_bank::ModbusRegisterBank::with_address_map),
+//! addresses outside every block fail with [`ModbusError::InvalidAddress`] and
+//! values violating a constraint with [`ModbusError::InvalidData`]; servers
+//! answer them with exceptions 0x02 (Illegal Data Address) and 0x03 (Illegal
+//! Data Value). Block access modes only restrict Modbus requests, so the
+//! application can still update read-only ranges.
+//!
+//! ## Example Map (YAML)
+//!
+//! ```yaml
+//! blocks:
+//!   - table: holding
+//!     start: 0
+//!     end: 99
+//!   - table: holding
+//!     start: 100
+//!     end: 109
+//!     access: read
+//!   - table: input
+//!     start: 0
+//!     end: 49
+//! constraints:
+//!   - address: 10
+//!     min: 0
+//!     max: 1000
+//!   - address: 11
+//!     values: [0, 1, 2]
+//! ```
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::{Access, AddressMap, ModbusRegisterBank, RegisterTable, ValueConstraint};
+//!
+//! let map = AddressMap::new()
+//!     .block(RegisterTable::HoldingRegister, 0..=99, Access::ReadWrite)
+//!     .constraint(ValueConstraint::range(10, 0, 1000))
+//!     .constraint(ValueConstraint::one_of(11, vec![0, 1, 2]));
+//! let bank = ModbusRegisterBank::new().with_address_map(map).unwrap();
+//!
+//! bank.write_06(10, 500).unwrap();
+//! assert!(bank.write_06(10, 5000).is_err());
+//! assert!(bank.read_03(100, 1).is_err());
+//! ```
+
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::tags::Access;
+
+fn default_access() -> Access {
+    Access::ReadWrite
+}
+
+fn default_constraint_table() -> RegisterTable {
+    RegisterTable::HoldingRegister
+}
+
+fn default_count() -> u16 {
+    1
+}
+
+/// A range of valid addresses in one table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressBlock {
+    /// Data table
+    pub table: RegisterTable,
+    /// First protocol address
+    pub start: u16,
+    /// Last protocol address (inclusive)
+    pub end: u16,
+    /// What Modbus requests may do with the block
+    #[serde(default = "default_access")]
+    pub access: Access,
+}
+
+impl AddressBlock {
+    /// Create a block from an inclusive address range
+    pub fn new(table: RegisterTable, range: RangeInclusive<u16>, access: Access) -> Self {
+        Self { table, start: *range.start(), end: *range.end(), access }
+    }
+
+    /// Check whether the block holds an address of a table
+    pub fn contains(&self, table: RegisterTable, address: u16) -> bool {
+        self.table == table && (self.start..=self.end).contains(&address)
+    }
+}
+
+/// Allowed values of one or more consecutive registers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueConstraint {
+    /// Register table, holding registers by default
+    #[serde(default = "default_constraint_table")]
+    pub table: RegisterTable,
+    /// First protocol address
+    pub address: u16,
+    /// Number of registers sharing the constraint
+    #[serde(default = "default_count")]
+    pub count: u16,
+    /// Smallest allowed value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i32>,
+    /// Largest allowed value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i32>,
+    /// Compare `min`/`max` against the value as `i16`
+    #[serde(default)]
+    pub signed: bool,
+    /// Allowed values; empty allows any value within `min`/`max`
+    #[serde(default, alias = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<u16>,
+}
+
+impl ValueConstraint {
+    /// Allow holding register values from `min` to `max`
+    pub fn range(address: u16, min: i32, max: i32) -> Self {
+        Self {
+            table: RegisterTable::HoldingRegister,
+            address,
+            count: 1,
+            min: Some(min),
+            max: Some(max),
+            signed: false,
+            values: Vec::new(),
+        }
+    }
+
+    /// Allow only the listed holding register values
+    pub fn one_of(address: u16, values: Vec<u16>) -> Self {
+        Self { min: None, max: None, values, ..Self::range(address, 0, 0) }
+    }
+
+    /// Apply the constraint to `count` consecutive registers
+    pub fn with_count(mut self, count: u16) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Compare `min`/`max` against the value as `i16`
+    pub fn signed(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    /// Apply the constraint to another register table
+    pub fn in_table(mut self, table: RegisterTable) -> Self {
+        self.table = table;
+        self
+    }
+
+    /// Check whether the constraint covers an address of a table
+    pub fn covers(&self, table: RegisterTable, address: u16) -> bool {
+        self.table == table && address >= self.address && ((address - self.address) as u32) < self.count as u32
+    }
+
+    /// Check whether a value is allowed
+    pub fn allows(&self, value: u16) -> bool {
+        let number = if self.signed { value as i16 as i32 } else { value as i32 };
+        self.min.is_none_or(|min| number >= min)
+            && self.max.is_none_or(|max| number <= max)
+            && (self.values.is_empty() || self.values.contains(&value))
+    }
+
+    fn validate(&self) -> ModbusResult<()> {
+        if self.table.is_bit_table() {
+            return Err(ModbusError::configuration(format!("Value constraint at {} targets the {} table", self.address, self.table)));
+        }
+        if self.count == 0 || self.address as u32 + self.count as u32 > 0x10000 {
+            return Err(ModbusError::configuration(format!("Value constraint at {} covers an invalid range", self.address)));
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(ModbusError::configuration(format!("Value constraint at {} has min {} above max {}", self.address, min, max)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Valid addresses, access modes and value constraints of a register bank
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AddressMap {
+    /// Valid address ranges; addresses outside every block are invalid
+    #[serde(default)]
+    pub blocks: Vec<AddressBlock>,
+    /// Register value constraints
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<ValueConstraint>,
+}
+
+impl AddressMap {
+    /// Create a map without any valid address
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a map with read/write blocks starting at address 0
+    ///
+    /// # Arguments
+    ///
+    /// * `coils`, `discrete_inputs`, `holding_registers`, `input_registers` -
+    ///   number of valid addresses per table; 0 leaves the table empty
+    pub fn with_sizes(coils: usize, discrete_inputs: usize, holding_registers: usize, input_registers: usize) -> Self {
+        [
+            (RegisterTable::Coil, coils),
+            (RegisterTable::DiscreteInput, discrete_inputs),
+            (RegisterTable::HoldingRegister, holding_registers),
+            (RegisterTable::InputRegister, input_registers),
+        ]
+        .into_iter()
+        .filter(|&(_, size)| size > 0)
+        .fold(Self::new(), |map, (table, size)| {
+            map.block(table, 0..=(size.min(0x10000) - 1) as u16, Access::ReadWrite)
+        })
+    }
+
+    /// Add a block of valid addresses
+    pub fn block(mut self, table: RegisterTable, range: RangeInclusive<u16>, access: Access) -> Self {
+        self.blocks.push(AddressBlock::new(table, range, access));
+        self
+    }
+
+    /// Add a value constraint
+    pub fn constraint(mut self, constraint: ValueConstraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Parse an address map from YAML
+    pub fn from_yaml_str(yaml: &str) -> ModbusResult<Self> {
+        let map: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| ModbusError::configuration(format!("Invalid address map: {}", e)))?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    /// Parse an address map from JSON
+    pub fn from_json_str(json: &str) -> ModbusResult<Self> {
+        let map: Self = serde_json::from_str(json)
+            .map_err(|e| ModbusError::configuration(format!("Invalid address map: {}", e)))?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    /// Load an address map from a `.yaml`/`.yml` or `.json` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ModbusError::configuration(format!("Cannot read {}: {}", path.display(), e)))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&content),
+            _ => Self::from_yaml_str(&content),
+        }
+    }
+
+    /// Serialize the address map to YAML
+    pub fn to_yaml_string(&self) -> ModbusResult<String> {
+        serde_yaml::to_string(self).map_err(|e| ModbusError::internal(format!("Cannot serialize address map: {}", e)))
+    }
+
+    /// Check blocks and constraints for consistency
+    ///
+    /// Blocks must not be empty or overlap within a table.
+    pub fn validate(&self) -> ModbusResult<()> {
+        for (i, block) in self.blocks.iter().enumerate() {
+            if block.start > block.end {
+                return Err(ModbusError::configuration(format!("{} block {}..={} is empty", block.table, block.start, block.end)));
+            }
+            let overlaps = self.blocks[..i]
+                .iter()
+                .any(|other| other.table == block.table && other.start <= block.end && block.start <= other.end);
+            if overlaps {
+                return Err(ModbusError::configuration(format!("{} block {}..={} overlaps another block", block.table, block.start, block.end)));
+            }
+        }
+        self.constraints.iter().try_for_each(ValueConstraint::validate)
+    }
+
+    /// Block holding an address, if it is valid
+    pub fn find(&self, table: RegisterTable, address: u16) -> Option<&AddressBlock> {
+        self.blocks.iter().find(|block| block.contains(table, address))
+    }
+
+    /// Check whether an address is valid
+    pub fn is_valid(&self, table: RegisterTable, address: u16) -> bool {
+        self.find(table, address).is_some()
+    }
+
+    /// Check a read of `quantity` addresses
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - whether the read comes from a Modbus request, which must
+    ///   not touch write-only blocks
+    pub fn check_read(&self, table: RegisterTable, address: u16, quantity: u16, remote: bool) -> ModbusResult<()> {
+        self.check_blocks(table, address, quantity, |access| !remote || access.can_read())
+    }
+
+    /// Check a write of `values` starting at `address`
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - whether the write comes from a Modbus request, which must
+    ///   not touch read-only blocks
+    pub fn check_write(&self, table: RegisterTable, address: u16, values: &PointValue, remote: bool) -> ModbusResult<()> {
+        let quantity = match values {
+            PointValue::Bits(bits) => bits.len(),
+            PointValue::Registers(registers) => registers.len(),
+        };
+        let quantity = u16::try_from(quantity).map_err(|_| ModbusError::invalid_address(address, u16::MAX))?;
+        self.check_blocks(table, address, quantity, |access| !remote || access.can_write())?;
+
+        if let PointValue::Registers(registers) = values {
+            for (i, &value) in registers.iter().enumerate() {
+                let register = address + i as u16;
+                let rejected = self
+                    .constraints
+                    .iter()
+                    .any(|constraint| constraint.covers(table, register) && !constraint.allows(value));
+                if rejected {
+                    return Err(ModbusError::invalid_data(format!("Value {} not allowed at {} {}", value, table, register)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a write of `quantity` addresses only touches writable blocks
+    /// 
+    /// Used for Enron 32-bit registers, whose values are not checked against
+    /// the 16-bit constraints.
+    pub(crate) fn check_write_access(&self, table: RegisterTable, address: u16, quantity: u16, remote: bool) -> ModbusResult<()> {
+        self.check_blocks(table, address, quantity, |access| !remote || access.can_write())
+    }
+
+    /// Check that every address lies in a block whose access passes `allowed`
+    fn check_blocks(&self, table: RegisterTable, address: u16, quantity: u16, allowed: impl Fn(Access) -> bool) -> ModbusResult<()> {
+        if quantity == 0 {
+            return Ok(());
+        }
+        let last = address
+            .checked_add(quantity - 1)
+            .ok_or_else(|| ModbusError::invalid_address(address, quantity))?;
+
+        // Walk the blocks covering the request; adjacent blocks may be spanned
+        let mut next = address;
+        loop {
+            let block = self
+                .find(table, next)
+                .filter(|block| allowed(block.access))
+                .ok_or_else(|| ModbusError::invalid_address(address, quantity))?;
+            if block.end >= last {
+                return Ok(());
+            }
+            next = block.end + 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_and_access() {
+        let map = AddressMap::new()
+            .block(RegisterTable::HoldingRegister, 0..=9, Access::ReadWrite)
+            .block(RegisterTable::HoldingRegister, 10..=19, Access::Read)
+            .block(RegisterTable::HoldingRegister, 30..=39, Access::Write);
+        map.validate().unwrap();
+
+        assert!(map.check_read(RegisterTable::HoldingRegister, 5, 15, true).is_ok());
+        assert!(map.check_read(RegisterTable::HoldingRegister, 15, 10, true).is_err());
+        assert!(map.check_read(RegisterTable::HoldingRegister, 30, 1, true).is_err());
+        assert!(map.check_read(RegisterTable::HoldingRegister, 30, 1, false).is_ok());
+        assert!(map.check_read(RegisterTable::InputRegister, 0, 1, false).is_err());
+
+        let values = PointValue::Registers(vec![1, 2]);
+        assert!(map.check_write(RegisterTable::HoldingRegister, 8, &values, true).is_ok());
+        assert!(map.check_write(RegisterTable::HoldingRegister, 9, &values, true).is_err());
+        assert!(map.check_write(RegisterTable::HoldingRegister, 9, &values, false).is_ok());
+        assert!(matches!(
+            map.check_write(RegisterTable::HoldingRegister, 19, &values, false),
+            Err(ModbusError::InvalidAddress { start: 19, count: 2 })
+        ));
+
+        let overlapping = map.clone().block(RegisterTable::HoldingRegister, 35..=45, Access::ReadWrite);
+        assert!(overlapping.validate().is_err());
+    }
+
+    #[test]
+    fn test_constraints_from_yaml() {
+        let map = AddressMap::from_yaml_str(
+            r#"
+blocks:
+  - { table: holding, start: 0, end: 99 }
+  - { table: coil, start: 0, end: 7, access: rw }
+constraints:
+  - { address: 10, min: 0, max: 1000 }
+  - { address: 11, values: [0, 1, 2] }
+  - { address: 20, count: 4, min: -50, max: 50, signed: true }
+"#,
+        )
+        .unwrap();
+
+        let write = |address: u16, value: u16| map.check_write(RegisterTable::HoldingRegister, address, &PointValue::Registers(vec![value]), true);
+        assert!(write(10, 1000).is_ok());
+        assert!(matches!(write(10, 1001), Err(ModbusError::InvalidData { .. })));
+        assert!(write(11, 2).is_ok());
+        assert!(write(11, 3).is_err());
+        assert!(write(23, (-50i16) as u16).is_ok());
+        assert!(write(23, (-51i16) as u16).is_err());
+        assert!(write(24, (-51i16) as u16).is_ok());
+
+        let yaml = map.to_yaml_string().unwrap();
+        assert_eq!(AddressMap::from_yaml_str(&yaml).unwrap(), map);
+        assert!(AddressMap::from_yaml_str("constraints: [{ table: coil, address: 0, max: 1 }]").is_err());
+    }
+}
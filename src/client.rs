@@ -14,11 +14,82 @@ use std::net::SocketAddr;
 use std::str::FromStr;
 use std::time::Duration;
 use async_trait::async_trait;
+use tokio::sync::{broadcast, watch};
 
 use crate::error::{ModbusError, ModbusResult};
-use crate::protocol::{ModbusRequest, ModbusResponse, ModbusFunction, SlaveId};
-use crate::transport::{ModbusTransport, TcpTransport, RtuTransport, TransportStats};
+use crate::codec::{self, ModbusRegisters, RegisterOrder, RegisterValue};
+use crate::protocol::{ModbusRequest, ModbusResponse, ModbusFunction, RegisterTable, SlaveId};
+use crate::transport::{ModbusTransport, TcpTransport, RtuTransport, TransportStats, ConnectionState, ReconnectConfig, FailoverTransport, FailoverConfig};
 use crate::logging::CallbackLogger;
+use crate::retry::RetryPolicy;
+use crate::health::{HealthTracker, HealthConfig, HealthEvent, CircuitState};
+use crate::address::{AddressingMode, DataAddress};
+use crate::planner::PointValue;
+use crate::enron::{self, ArchiveRecord, EnronConfig, EnronEvent, ENRON_EVENT_REGISTER, ENRON_MAX_READ_VALUES, ENRON_MAX_WRITE_VALUES};
+
+/// Per-device request size limits for the chunked client methods
+/// 
+/// The defaults are the protocol maximums. Many devices accept less, e.g.
+/// 60 or 100 registers per request; use [`ChunkLimits::registers`] for those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLimits {
+    /// Maximum coils/discrete inputs per read (0x01, 0x02)
+    pub max_read_bits: u16,
+    /// Maximum registers per read (0x03, 0x04)
+    pub max_read_registers: u16,
+    /// Maximum coils per write (0x0F)
+    pub max_write_bits: u16,
+    /// Maximum registers per write (0x10)
+    pub max_write_registers: u16,
+}
+
+impl ChunkLimits {
+    /// Limit reads and writes to `max` registers, keeping the bit limits at the protocol maximums
+    pub fn registers(max: u16) -> Self {
+        Self {
+            max_read_registers: max.min(MAX_READ_REGISTERS),
+            max_write_registers: max.min(MAX_WRITE_REGISTERS),
+            ..Self::default()
+        }
+    }
+
+    /// Split `quantity` items starting at `address` into `(start, count)` chunks of at most `max`
+    pub fn split(address: u16, quantity: usize, max: u16) -> ModbusResult<Vec<(u16, u16)>> {
+        if quantity == 0 || max == 0 || address as usize + quantity > 0x10000 {
+            return Err(ModbusError::invalid_address(address, quantity.min(u16::MAX as usize) as u16));
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = address as usize;
+        let end = start + quantity;
+        while start < end {
+            let count = (end - start).min(max as usize);
+            chunks.push((start as u16, count as u16));
+            start += count;
+        }
+        Ok(chunks)
+    }
+}
+
+impl Default for ChunkLimits {
+    fn default() -> Self {
+        Self {
+            max_read_bits: MAX_READ_BITS,
+            max_read_registers: MAX_READ_REGISTERS,
+            max_write_bits: MAX_WRITE_BITS,
+            max_write_registers: MAX_WRITE_REGISTERS,
+        }
+    }
+}
+
+/// Protocol maximum coils per read request
+const MAX_READ_BITS: u16 = crate::MAX_COILS_PER_REQUEST;
+/// Protocol maximum registers per read request
+const MAX_READ_REGISTERS: u16 = crate::MAX_REGISTERS_PER_REQUEST;
+/// Protocol maximum coils per write request
+const MAX_WRITE_BITS: u16 = 1968;
+/// Protocol maximum registers per write request
+const MAX_WRITE_REGISTERS: u16 = 123;
 
 /// Trait defining the interface for Modbus client operations
 /// 
@@ -59,6 +130,11 @@ pub trait ModbusClient: Send + Sync {
     /// Get transport statistics
     fn get_stats(&self) -> TransportStats;
 
+    /// How the addresses passed to this client are counted
+    fn addressing_mode(&self) -> AddressingMode {
+        AddressingMode::ZeroBased
+    }
+
     // Legacy function names for compatibility
     async fn write_single_coil(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
         self.write_05(slave_id, address, value).await
@@ -75,6 +151,298 @@ pub trait ModbusClient: Send + Sync {
     async fn write_multiple_registers(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
         self.write_10(slave_id, address, values).await
     }
+
+    // Chunked variants for ranges larger than a single request
+
+    /// Read any number of coils (0x01), split into requests within `limits`
+    /// 
+    /// A failed chunk aborts the read with [`ModbusError::ChunkFailed`].
+    async fn read_01_chunked(&mut self, slave_id: SlaveId, address: u16, quantity: usize, limits: &ChunkLimits) -> ModbusResult<Vec<bool>> {
+        let mut values = Vec::with_capacity(quantity);
+        for (start, count) in ChunkLimits::split(address, quantity, limits.max_read_bits)? {
+            let chunk = self.read_01(slave_id, start, count).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+            values.extend(chunk);
+        }
+        Ok(values)
+    }
+
+    /// Read any number of discrete inputs (0x02), split into requests within `limits`
+    async fn read_02_chunked(&mut self, slave_id: SlaveId, address: u16, quantity: usize, limits: &ChunkLimits) -> ModbusResult<Vec<bool>> {
+        let mut values = Vec::with_capacity(quantity);
+        for (start, count) in ChunkLimits::split(address, quantity, limits.max_read_bits)? {
+            let chunk = self.read_02(slave_id, start, count).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+            values.extend(chunk);
+        }
+        Ok(values)
+    }
+
+    /// Read any number of holding registers (0x03), split into requests within `limits`
+    async fn read_03_chunked(&mut self, slave_id: SlaveId, address: u16, quantity: usize, limits: &ChunkLimits) -> ModbusResult<Vec<u16>> {
+        let mut values = Vec::with_capacity(quantity);
+        for (start, count) in ChunkLimits::split(address, quantity, limits.max_read_registers)? {
+            let chunk = self.read_03(slave_id, start, count).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+            values.extend(chunk);
+        }
+        Ok(values)
+    }
+
+    /// Read any number of input registers (0x04), split into requests within `limits`
+    async fn read_04_chunked(&mut self, slave_id: SlaveId, address: u16, quantity: usize, limits: &ChunkLimits) -> ModbusResult<Vec<u16>> {
+        let mut values = Vec::with_capacity(quantity);
+        for (start, count) in ChunkLimits::split(address, quantity, limits.max_read_registers)? {
+            let chunk = self.read_04(slave_id, start, count).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+            values.extend(chunk);
+        }
+        Ok(values)
+    }
+
+    /// Write any number of coils (0x0F), split into requests within `limits`
+    /// 
+    /// Chunks are written in address order; chunks before a failed one stay written.
+    async fn write_0f_chunked(&mut self, slave_id: SlaveId, address: u16, values: &[bool], limits: &ChunkLimits) -> ModbusResult<()> {
+        for (start, count) in ChunkLimits::split(address, values.len(), limits.max_write_bits)? {
+            let offset = (start - address) as usize;
+            self.write_0f(slave_id, start, &values[offset..offset + count as usize]).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+        }
+        Ok(())
+    }
+
+    /// Write any number of registers (0x10), split into requests within `limits`
+    /// 
+    /// Chunks are written in address order; chunks before a failed one stay written.
+    async fn write_10_chunked(&mut self, slave_id: SlaveId, address: u16, values: &[u16], limits: &ChunkLimits) -> ModbusResult<()> {
+        for (start, count) in ChunkLimits::split(address, values.len(), limits.max_write_registers)? {
+            let offset = (start - address) as usize;
+            self.write_10(slave_id, start, &values[offset..offset + count as usize]).await
+                .map_err(|e| ModbusError::chunk_failed(start, count, e))?;
+        }
+        Ok(())
+    }
+
+    // Typed values spanning one or more registers, see [`crate::codec`]
+
+    /// Read a typed value from holding registers (0x03)
+    /// 
+    /// # Arguments
+    /// 
+    /// * `slave_id` - Slave device ID
+    /// * `address` - Address of the first register
+    /// * `order` - Word and byte order used by the device
+    async fn read_value<V: RegisterValue>(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<V>
+    where
+        Self: Sized,
+    {
+        let registers = self.read_03(slave_id, address, V::REGISTERS).await?;
+        V::from_registers(&registers, order)
+    }
+
+    /// Read a typed value from input registers (0x04)
+    async fn read_input_value<V: RegisterValue>(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<V>
+    where
+        Self: Sized,
+    {
+        let registers = self.read_04(slave_id, address, V::REGISTERS).await?;
+        V::from_registers(&registers, order)
+    }
+
+    /// Write a typed value to holding registers (0x10)
+    async fn write_value<V: RegisterValue>(&mut self, slave_id: SlaveId, address: u16, value: V, order: RegisterOrder) -> ModbusResult<()>
+    where
+        Self: Sized,
+    {
+        self.write_10(slave_id, address, &value.to_registers(order)).await
+    }
+
+    /// Read a whole register block (0x03) into a struct
+    /// 
+    /// See [`ModbusRegisters`], usually derived with the `derive` feature.
+    async fn read_block<B: ModbusRegisters>(&mut self, slave_id: SlaveId, address: u16) -> ModbusResult<B>
+    where
+        Self: Sized,
+    {
+        let registers = self.read_03(slave_id, address, B::REGISTER_COUNT).await?;
+        B::from_registers(&registers)
+    }
+
+    /// Read a whole input register block (0x04) into a struct
+    async fn read_input_block<B: ModbusRegisters>(&mut self, slave_id: SlaveId, address: u16) -> ModbusResult<B>
+    where
+        Self: Sized,
+    {
+        let registers = self.read_04(slave_id, address, B::REGISTER_COUNT).await?;
+        B::from_registers(&registers)
+    }
+
+    /// Write a struct back as a whole register block (0x10)
+    async fn write_block<B: ModbusRegisters + Sync>(&mut self, slave_id: SlaveId, address: u16, block: &B) -> ModbusResult<()>
+    where
+        Self: Sized,
+    {
+        let registers = block.to_registers()?;
+        self.write_10(slave_id, address, &registers).await
+    }
+
+    /// Read a 32-bit unsigned integer from holding registers
+    async fn read_u32(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<u32> {
+        let registers = self.read_03(slave_id, address, 2).await?;
+        u32::from_registers(&registers, order)
+    }
+
+    /// Read a 32-bit signed integer from holding registers
+    async fn read_i32(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<i32> {
+        let registers = self.read_03(slave_id, address, 2).await?;
+        i32::from_registers(&registers, order)
+    }
+
+    /// Read a 32-bit float from holding registers
+    async fn read_f32(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<f32> {
+        let registers = self.read_03(slave_id, address, 2).await?;
+        f32::from_registers(&registers, order)
+    }
+
+    /// Read a 64-bit float from holding registers
+    async fn read_f64(&mut self, slave_id: SlaveId, address: u16, order: RegisterOrder) -> ModbusResult<f64> {
+        let registers = self.read_03(slave_id, address, 4).await?;
+        f64::from_registers(&registers, order)
+    }
+
+    /// Write a 32-bit unsigned integer to holding registers
+    async fn write_u32(&mut self, slave_id: SlaveId, address: u16, value: u32, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(slave_id, address, &value.to_registers(order)).await
+    }
+
+    /// Write a 32-bit signed integer to holding registers
+    async fn write_i32(&mut self, slave_id: SlaveId, address: u16, value: i32, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(slave_id, address, &value.to_registers(order)).await
+    }
+
+    /// Write a 32-bit float to holding registers
+    async fn write_f32(&mut self, slave_id: SlaveId, address: u16, value: f32, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(slave_id, address, &value.to_registers(order)).await
+    }
+
+    /// Write a 64-bit float to holding registers
+    async fn write_f64(&mut self, slave_id: SlaveId, address: u16, value: f64, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(slave_id, address, &value.to_registers(order)).await
+    }
+
+    /// Read a fixed-length string of `registers` holding registers
+    /// 
+    /// Trailing NUL and space padding is removed.
+    async fn read_string(&mut self, slave_id: SlaveId, address: u16, registers: u16, order: RegisterOrder) -> ModbusResult<String> {
+        let data = self.read_03(slave_id, address, registers).await?;
+        codec::decode_string(&data, order)
+    }
+
+    /// Write a string into exactly `registers` holding registers, padded with NUL
+    async fn write_string(&mut self, slave_id: SlaveId, address: u16, value: &str, registers: u16, order: RegisterOrder) -> ModbusResult<()> {
+        let data = codec::encode_string(value, registers, order)?;
+        self.write_10(slave_id, address, &data).await
+    }
+
+    /// Read a BCD number of `registers` holding registers (four digits each)
+    async fn read_bcd(&mut self, slave_id: SlaveId, address: u16, registers: u16, order: RegisterOrder) -> ModbusResult<u64> {
+        let data = self.read_03(slave_id, address, registers).await?;
+        codec::decode_bcd(&data, order)
+    }
+
+    /// Write a BCD number into `registers` holding registers (four digits each)
+    async fn write_bcd(&mut self, slave_id: SlaveId, address: u16, value: u64, registers: u16, order: RegisterOrder) -> ModbusResult<()> {
+        let data = codec::encode_bcd(value, registers, order)?;
+        self.write_10(slave_id, address, &data).await
+    }
+
+    /// Read `quantity` Enron 32-bit registers
+    /// 
+    /// The client must be in Enron mode so the quantity counts 32-bit values.
+    async fn read_enron_u32(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u32>> {
+        let registers = self.read_03(slave_id, self.addressing_mode().from_wire(address)?, quantity).await?;
+        if registers.len() != quantity as usize * 2 {
+            return Err(ModbusError::invalid_data(format!(
+                "Expected {} 32-bit values, got {} registers; is Enron mode enabled?",
+                quantity,
+                registers.len()
+            )));
+        }
+        enron::registers_to_u32(&registers)
+    }
+
+    /// Read `quantity` Enron 32-bit float registers
+    async fn read_enron_f32(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<f32>> {
+        let values = self.read_enron_u32(slave_id, address, quantity).await?;
+        Ok(values.into_iter().map(f32::from_bits).collect())
+    }
+
+    /// Write Enron 32-bit registers
+    async fn write_enron_u32(&mut self, slave_id: SlaveId, address: u16, values: &[u32]) -> ModbusResult<()> {
+        let address = self.addressing_mode().from_wire(address)?;
+        self.write_10(slave_id, address, &enron::u32_to_registers(values)).await
+    }
+
+    /// Write Enron 32-bit float registers
+    async fn write_enron_f32(&mut self, slave_id: SlaveId, address: u16, values: &[f32]) -> ModbusResult<()> {
+        let values: Vec<u32> = values.iter().map(|value| value.to_bits()).collect();
+        self.write_enron_u32(slave_id, address, &values).await
+    }
+
+    /// Read up to 12 pending events from the Enron event log
+    /// 
+    /// Events stay pending until acknowledged with
+    /// [`acknowledge_enron_events`](Self::acknowledge_enron_events).
+    async fn read_enron_events(&mut self, slave_id: SlaveId) -> ModbusResult<Vec<EnronEvent>> {
+        let address = self.addressing_mode().from_wire(ENRON_EVENT_REGISTER)?;
+        let registers = self.read_03(slave_id, address, 1).await?;
+        EnronEvent::decode_all(&registers)
+    }
+
+    /// Acknowledge the events returned by the last event log read
+    async fn acknowledge_enron_events(&mut self, slave_id: SlaveId) -> ModbusResult<()> {
+        let address = self.addressing_mode().from_wire(ENRON_EVENT_REGISTER)?;
+        self.write_05(slave_id, address, true).await
+    }
+
+    /// Read record `index` of an Enron archive
+    /// 
+    /// # Arguments
+    /// 
+    /// * `slave_id` - Slave device ID
+    /// * `register` - Archive register, e.g. [`ENRON_HOURLY_ARCHIVE`](crate::enron::ENRON_HOURLY_ARCHIVE)
+    /// * `index` - Record index, sent as the request quantity
+    async fn read_enron_archive(&mut self, slave_id: SlaveId, register: u16, index: u16) -> ModbusResult<ArchiveRecord> {
+        let registers = self.read_03(slave_id, self.addressing_mode().from_wire(register)?, index).await?;
+        let values = enron::registers_to_u32(&registers)?.into_iter().map(f32::from_bits).collect();
+        Ok(ArchiveRecord { register, index, values })
+    }
+
+    /// Read from any table at a parsed address
+    /// 
+    /// The table and offset come from the address, e.g. `"30010".parse()`,
+    /// independently of the client's addressing mode.
+    async fn read_at(&mut self, slave_id: SlaveId, address: DataAddress, quantity: u16) -> ModbusResult<PointValue> {
+        let offset = self.addressing_mode().from_wire(address.address)?;
+        Ok(match address.table {
+            RegisterTable::Coil => PointValue::Bits(self.read_01(slave_id, offset, quantity).await?),
+            RegisterTable::DiscreteInput => PointValue::Bits(self.read_02(slave_id, offset, quantity).await?),
+            RegisterTable::HoldingRegister => PointValue::Registers(self.read_03(slave_id, offset, quantity).await?),
+            RegisterTable::InputRegister => PointValue::Registers(self.read_04(slave_id, offset, quantity).await?),
+        })
+    }
+
+    /// Write coils or holding registers at a parsed address
+    async fn write_at(&mut self, slave_id: SlaveId, address: DataAddress, value: &PointValue) -> ModbusResult<()> {
+        let offset = self.addressing_mode().from_wire(address.address)?;
+        match (address.table, value) {
+            (RegisterTable::Coil, PointValue::Bits(bits)) => self.write_0f(slave_id, offset, bits).await,
+            (RegisterTable::HoldingRegister, PointValue::Registers(registers)) => self.write_10(slave_id, offset, registers).await,
+            (table, _) if !table.is_writable() => Err(ModbusError::invalid_data(format!("The {} table is read-only", table))),
+            (table, _) => Err(ModbusError::invalid_data(format!("Data does not match the {} table", table))),
+        }
+    }
 }
 
 /// Generic Modbus client that works with any transport
@@ -85,6 +453,11 @@ pub trait ModbusClient: Send + Sync {
 pub struct GenericModbusClient<T: ModbusTransport> {
     transport: T,
     logger: Option<CallbackLogger>,
+    retry_policy: RetryPolicy,
+    retries: u64,
+    health: Option<HealthTracker>,
+    enron: Option<EnronConfig>,
+    addressing: AddressingMode,
 }
 
 impl<T: ModbusTransport> GenericModbusClient<T> {
@@ -93,6 +466,11 @@ impl<T: ModbusTransport> GenericModbusClient<T> {
         Self { 
             transport,
             logger: None,
+            retry_policy: RetryPolicy::default(),
+            retries: 0,
+            health: None,
+            enron: None,
+            addressing: AddressingMode::ZeroBased,
         }
     }
 
@@ -101,7 +479,114 @@ impl<T: ModbusTransport> GenericModbusClient<T> {
         Self {
             transport,
             logger: Some(logger),
+            retry_policy: RetryPolicy::default(),
+            retries: 0,
+            health: None,
+            enron: None,
+            addressing: AddressingMode::ZeroBased,
+        }
+    }
+
+    /// Use the given retry policy for all requests
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Replace the retry policy
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = policy;
+    }
+
+    /// Get the active retry policy
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Track per-slave health and fail fast for offline slaves
+    /// 
+    /// Offline slaves are only probed when the caller runs
+    /// [`probe_offline_slaves`](Self::probe_offline_slaves), so call it
+    /// periodically, e.g. once per poll cycle.
+    pub fn with_health_tracking(mut self, config: HealthConfig) -> Self {
+        self.enable_health_tracking(config);
+        self
+    }
+
+    /// Enable per-slave health tracking, replacing any existing tracker
+    /// 
+    /// As with [`with_health_tracking`](Self::with_health_tracking), offline
+    /// slaves are only probed by [`probe_offline_slaves`](Self::probe_offline_slaves).
+    pub fn enable_health_tracking(&mut self, config: HealthConfig) {
+        self.health = Some(HealthTracker::new(config));
+    }
+
+    /// Get the health tracker, if enabled
+    pub fn health(&self) -> Option<&HealthTracker> {
+        self.health.as_ref()
+    }
+
+    /// Subscribe to slave online/offline events, if health tracking is enabled
+    pub fn subscribe_health(&self) -> Option<broadcast::Receiver<HealthEvent>> {
+        self.health.as_ref().map(HealthTracker::subscribe)
+    }
+
+    /// Use the given addressing mode for all addresses passed to the client
+    pub fn with_addressing(mut self, mode: AddressingMode) -> Self {
+        self.addressing = mode;
+        self
+    }
+
+    /// Replace the addressing mode
+    pub fn set_addressing(&mut self, mode: AddressingMode) {
+        self.addressing = mode;
+    }
+
+    /// Talk to the device in Enron mode
+    pub fn with_enron(mut self, config: EnronConfig) -> Self {
+        self.set_enron(Some(config));
+        self
+    }
+
+    /// Enable or disable Enron mode
+    /// 
+    /// In Enron mode, reads and writes in the configured 32-bit ranges count
+    /// 32-bit values, and reads of the event log and archive registers are
+    /// passed through unchecked. The transport is configured to match.
+    pub fn set_enron(&mut self, config: Option<EnronConfig>) {
+        self.transport.set_enron_mode(config.clone());
+        self.enron = config;
+    }
+
+    /// Get the Enron configuration, if enabled
+    pub fn enron(&self) -> Option<&EnronConfig> {
+        self.enron.as_ref()
+    }
+
+    /// Send a probe request to every offline slave whose open period has elapsed
+    /// 
+    /// Call this periodically so offline slaves are detected as online again
+    /// even when the application stops polling them.
+    /// 
+    /// # Returns
+    /// 
+    /// Slaves that answered their probe and are back online
+    pub async fn probe_offline_slaves(&mut self) -> Vec<SlaveId> {
+        let probes: Vec<ModbusRequest> = match self.health {
+            Some(ref health) => health.due_probes().into_iter().map(|slave_id| health.probe_request(slave_id)).collect(),
+            None => return Vec::new(),
+        };
+
+        let mut online = Vec::new();
+        for probe in probes {
+            let slave_id = probe.slave_id;
+            // Exception responses also close the breaker, so check the state rather than the result
+            let _ = self.execute_request(probe).await;
+            if self.health.as_ref().is_some_and(|health| health.state(slave_id) == CircuitState::Closed) {
+                online.push(slave_id);
+            }
         }
+        online
     }
     
     /// Get a reference to the underlying transport
@@ -115,7 +600,58 @@ impl<T: ModbusTransport> GenericModbusClient<T> {
     }
     
     /// Execute a raw request
+    /// 
+    /// Failed attempts are retried according to the client's [`RetryPolicy`].
+    /// With health tracking enabled, requests to offline slaves fail fast with
+    /// [`ModbusError::DeviceNotResponding`].
     pub async fn execute_request(&mut self, request: ModbusRequest) -> ModbusResult<ModbusResponse> {
+        // Broadcast requests are never answered, so they say nothing about slave health
+        let tracked = request.slave_id != 0;
+        if tracked {
+            if let Some(ref mut health) = self.health {
+                health.check(request.slave_id)?;
+            }
+        }
+
+        let result = self.execute_with_retry(&request).await;
+
+        if tracked {
+            if let Some(ref mut health) = self.health {
+                health.record(request.slave_id, &result);
+            }
+        }
+
+        result
+    }
+
+    /// Execute a request, retrying failed attempts according to the retry policy
+    async fn execute_with_retry(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+        let is_write = request.function.is_write_function();
+        let mut attempt = 1;
+
+        loop {
+            match self.send_request(request).await {
+                Ok(response) => return Ok(response),
+                Err(error) if self.retry_policy.should_retry(&error, attempt, is_write) => {
+                    let delay = self.retry_policy.backoff(attempt);
+                    if let Some(ref logger) = self.logger {
+                        logger.warn(&format!(
+                            "Request to slave {} failed (attempt {}/{}): {}, retrying in {:?}",
+                            request.slave_id, attempt, self.retry_policy.max_attempts, error, delay
+                        ));
+                    }
+
+                    self.retries += 1;
+                    attempt += 1;
+                    tokio::time::sleep(delay).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Send a single request attempt through the transport
+    async fn send_request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
         // Log request if logger is available
         if let Some(ref logger) = self.logger {
             logger.log_request(
@@ -127,7 +663,7 @@ impl<T: ModbusTransport> GenericModbusClient<T> {
             );
         }
 
-        let response = self.transport.request(&request).await?;
+        let response = self.transport.request(request).await?;
 
         // Log response if logger is available
         if let Some(ref logger) = self.logger {
@@ -145,6 +681,7 @@ impl<T: ModbusTransport> GenericModbusClient<T> {
 #[async_trait::async_trait]
 impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
     async fn read_01(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        let address = self.addressing.to_wire(address)?;
         if quantity == 0 || quantity > 2000 {
             return Err(ModbusError::InvalidDataValue);
         }
@@ -158,19 +695,13 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
         };
         
         let response = self.execute_request(request).await?;
-        Ok(response.data.chunks(8)
-           .flat_map(|byte_data| {
-               if let Some(&byte) = byte_data.first() {
-                   (0..8).map(move |i| (byte & (1 << i)) != 0).collect::<Vec<bool>>()
-               } else {
-                   vec![]
-               }
-           })
-           .take(quantity as usize)
-           .collect())
+        let mut bits = response.parse_bits()?;
+        bits.truncate(quantity as usize);
+        Ok(bits)
     }
     
     async fn read_02(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        let address = self.addressing.to_wire(address)?;
         if quantity == 0 || quantity > 2000 {
             return Err(ModbusError::InvalidDataValue);
         }
@@ -184,20 +715,29 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
         };
         
         let response = self.execute_request(request).await?;
-        Ok(response.data.chunks(8)
-           .flat_map(|byte_data| {
-               if let Some(&byte) = byte_data.first() {
-                   (0..8).map(move |i| (byte & (1 << i)) != 0).collect::<Vec<bool>>()
-               } else {
-                   vec![]
-               }
-           })
-           .take(quantity as usize)
-           .collect())
+        let mut bits = response.parse_bits()?;
+        bits.truncate(quantity as usize);
+        Ok(bits)
     }
     
     async fn read_03(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
-        if quantity == 0 || quantity > 125 {
+        let address = self.addressing.to_wire(address)?;
+        // Enron 32-bit reads return two registers per requested value
+        let mut expected = None;
+        if let Some(ref enron) = self.enron {
+            if enron.is_record_register(address) {
+                let request = ModbusRequest::new_read(slave_id, ModbusFunction::ReadHoldingRegisters, address, quantity);
+                return self.execute_request(request).await?.parse_registers();
+            }
+            if enron.long_range(address, quantity)? {
+                if quantity == 0 || quantity > ENRON_MAX_READ_VALUES {
+                    return Err(ModbusError::invalid_data(format!("Invalid 32-bit register quantity: {}", quantity)));
+                }
+                expected = Some(quantity as usize * 2);
+            }
+        }
+
+        if expected.is_none() && (quantity == 0 || quantity > 125) {
             return Err(ModbusError::InvalidDataValue);
         }
         
@@ -210,17 +750,17 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
         };
         
         let response = self.execute_request(request).await?;
-        Ok(response.data.chunks(2).filter_map(|chunk| {
-            if chunk.len() >= 2 {
-                Some(u16::from_be_bytes([chunk[0], chunk[1]]))
-            } else {
-                // Handle odd-length data by padding with zero
-                Some(u16::from_be_bytes([chunk[0], 0]))
+        let registers = response.parse_registers()?;
+        if let Some(expected) = expected {
+            if registers.len() != expected {
+                return Err(ModbusError::frame(format!("Expected {} registers for {} 32-bit values, got {}", expected, quantity, registers.len())));
             }
-        }).collect())
+        }
+        Ok(registers)
     }
     
     async fn read_04(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        let address = self.addressing.to_wire(address)?;
         if quantity == 0 || quantity > 125 {
             return Err(ModbusError::InvalidDataValue);
         }
@@ -234,10 +774,11 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
         };
         
         let response = self.execute_request(request).await?;
-        Ok(response.data.chunks(2).map(|chunk| u16::from_be_bytes([chunk[0], chunk[1]])).collect())
+        response.parse_registers()
     }
     
     async fn write_05(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
+        let address = self.addressing.to_wire(address)?;
         let mut data = vec![];
         data.extend_from_slice(&if value { [0xFF, 0x00] } else { [0x00, 0x00] });
         
@@ -254,6 +795,7 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
     }
     
     async fn write_06(&mut self, slave_id: SlaveId, address: u16, value: u16) -> ModbusResult<()> {
+        let address = self.addressing.to_wire(address)?;
         let request = ModbusRequest {
             slave_id,
             function: ModbusFunction::WriteSingleRegister,
@@ -267,12 +809,13 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
     }
     
     async fn write_0f(&mut self, slave_id: SlaveId, address: u16, values: &[bool]) -> ModbusResult<()> {
+        let address = self.addressing.to_wire(address)?;
         if values.is_empty() || values.len() > 1968 {
             return Err(ModbusError::InvalidDataValue);
         }
         
-        let byte_count = (values.len() + 7) / 8;
-        let mut data = vec![byte_count as u8];
+        // The transport prepends the byte count when framing the request
+        let mut data = Vec::with_capacity((values.len() + 7) / 8);
         
         for chunk in values.chunks(8) {
             let mut byte = 0u8;
@@ -297,11 +840,25 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
     }
     
     async fn write_10(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
-        if values.is_empty() || values.len() > 123 {
+        let address = self.addressing.to_wire(address)?;
+        let mut quantity = values.len() as u16;
+        let long = match self.enron {
+            Some(ref enron) => enron.long_range(address, quantity / 2)?,
+            None => false,
+        };
+
+        if long {
+            // Enron 32-bit writes count values, each sent as a register pair
+            if values.is_empty() || !values.len().is_multiple_of(2) || values.len() > ENRON_MAX_WRITE_VALUES as usize * 2 {
+                return Err(ModbusError::invalid_data(format!("Invalid 32-bit register write of {} registers", values.len())));
+            }
+            quantity /= 2;
+        } else if values.is_empty() || values.len() > 123 {
             return Err(ModbusError::InvalidDataValue);
         }
         
-        let mut data = vec![values.len() as u8 * 2];
+        // The transport prepends the byte count when framing the request
+        let mut data = Vec::with_capacity(values.len() * 2);
         for &value in values {
             data.extend_from_slice(&value.to_be_bytes());
         }
@@ -310,7 +867,7 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
             slave_id,
             function: ModbusFunction::WriteMultipleRegisters,
             address,
-            quantity: values.len() as u16,
+            quantity,
             data,
         };
         
@@ -318,6 +875,10 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
         Ok(())
     }
     
+    fn addressing_mode(&self) -> AddressingMode {
+        self.addressing
+    }
+
     fn is_connected(&self) -> bool {
         self.transport.is_connected()
     }
@@ -327,7 +888,9 @@ impl<T: ModbusTransport + Send + Sync> ModbusClient for GenericModbusClient<T> {
     }
     
     fn get_stats(&self) -> TransportStats {
-        self.transport.get_stats()
+        let mut stats = self.transport.get_stats();
+        stats.retries += self.retries;
+        stats
     }
 }
 
@@ -361,6 +924,20 @@ impl ModbusTcpClient {
         Self::new(addr, timeout).await
     }
 
+    /// Create a new TCP client with custom reconnection behaviour
+    pub async fn with_reconnect(addr: SocketAddr, timeout: Duration, reconnect: ReconnectConfig) -> ModbusResult<Self> {
+        let transport = TcpTransport::with_reconnect(addr, timeout, reconnect).await?;
+        Ok(Self::from_transport(transport))
+    }
+
+    /// Create a new TCP client without connecting
+    /// 
+    /// The connection is established by the first request or by
+    /// [`ensure_connected`](Self::ensure_connected).
+    pub fn disconnected(addr: SocketAddr, timeout: Duration, reconnect: ReconnectConfig) -> Self {
+        Self::from_transport(TcpTransport::disconnected(addr, timeout, reconnect))
+    }
+
     /// Create a new TCP client from transport
     pub fn from_transport(transport: TcpTransport) -> Self {
         Self {
@@ -377,6 +954,64 @@ impl ModbusTcpClient {
     pub fn set_packet_logging(&mut self, enabled: bool) {
         self.inner.transport_mut().set_packet_logging(enabled);
     }
+
+    /// Set the retry policy used for all requests
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.inner.set_retry_policy(policy);
+    }
+
+    /// Enable per-slave health tracking
+    /// 
+    /// Call [`probe_offline_slaves`](Self::probe_offline_slaves) periodically
+    /// so offline slaves are detected as online again.
+    pub fn enable_health_tracking(&mut self, config: HealthConfig) {
+        self.inner.enable_health_tracking(config);
+    }
+
+    /// Get the health tracker, if enabled
+    pub fn health(&self) -> Option<&HealthTracker> {
+        self.inner.health()
+    }
+
+    /// Subscribe to slave online/offline events, if health tracking is enabled
+    pub fn subscribe_health(&self) -> Option<broadcast::Receiver<HealthEvent>> {
+        self.inner.subscribe_health()
+    }
+
+    /// Probe offline slaves whose open period has elapsed
+    pub async fn probe_offline_slaves(&mut self) -> Vec<SlaveId> {
+        self.inner.probe_offline_slaves().await
+    }
+
+    /// Set the addressing mode for all addresses passed to the client
+    pub fn set_addressing(&mut self, mode: AddressingMode) {
+        self.inner.set_addressing(mode);
+    }
+
+    /// Enable or disable Enron mode
+    pub fn set_enron(&mut self, config: Option<EnronConfig>) {
+        self.inner.set_enron(config);
+    }
+
+    /// Set the reconnection behaviour of the underlying transport
+    pub fn set_reconnect_config(&mut self, reconnect: ReconnectConfig) {
+        self.inner.transport_mut().set_reconnect_config(reconnect);
+    }
+
+    /// Get the current connection state
+    pub fn connection_state(&self) -> ConnectionState {
+        self.inner.transport().connection_state()
+    }
+
+    /// Subscribe to connection state changes
+    pub fn subscribe_state(&self) -> watch::Receiver<ConnectionState> {
+        self.inner.transport().subscribe_state()
+    }
+
+    /// Connect, waiting out backoff delays between failed attempts
+    pub async fn ensure_connected(&mut self) -> ModbusResult<()> {
+        self.inner.transport_mut().ensure_connected().await
+    }
     
     /// Execute a raw request
     pub async fn execute_request(&mut self, request: ModbusRequest) -> ModbusResult<ModbusResponse> {
@@ -418,6 +1053,158 @@ impl ModbusClient for ModbusTcpClient {
         self.inner.write_10(slave_id, address, values).await
     }
     
+    fn addressing_mode(&self) -> AddressingMode {
+        self.inner.addressing_mode()
+    }
+    
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+    
+    async fn close(&mut self) -> ModbusResult<()> {
+        self.inner.close().await
+    }
+    
+    fn get_stats(&self) -> TransportStats {
+        self.inner.get_stats()
+    }
+}
+
+/// Modbus TCP client for redundant servers exposing the same register map
+/// 
+/// Wraps a [`FailoverTransport`]: requests go to the active endpoint and move
+/// to the next one when it keeps failing. Application code talks to it through
+/// the regular [`ModbusClient`] trait.
+pub struct ModbusFailoverClient {
+    inner: GenericModbusClient<FailoverTransport>,
+}
+
+impl ModbusFailoverClient {
+    /// Create a new failover client without connecting
+    /// 
+    /// # Arguments
+    /// 
+    /// * `endpoints` - Server addresses in order of preference, primary first
+    /// * `timeout` - Request timeout for every endpoint
+    /// * `config` - Failover and reconnection behaviour
+    pub fn new(endpoints: &[SocketAddr], timeout: Duration, config: FailoverConfig) -> ModbusResult<Self> {
+        let transport = FailoverTransport::new(endpoints, timeout, config)?;
+        Ok(Self::from_transport(transport))
+    }
+
+    /// Create a new failover client from address strings
+    pub fn from_addresses(endpoints: &[&str], timeout: Duration, config: FailoverConfig) -> ModbusResult<Self> {
+        let endpoints = endpoints
+            .iter()
+            .map(|addr| addr.parse().map_err(|e| ModbusError::configuration(format!("Invalid address {}: {}", addr, e))))
+            .collect::<ModbusResult<Vec<SocketAddr>>>()?;
+        Self::new(&endpoints, timeout, config)
+    }
+
+    /// Create a new failover client from transport
+    pub fn from_transport(transport: FailoverTransport) -> Self {
+        Self {
+            inner: GenericModbusClient::new(transport),
+        }
+    }
+
+    /// Address of the endpoint requests are currently sent to
+    pub fn active_endpoint(&self) -> SocketAddr {
+        self.inner.transport().active_endpoint()
+    }
+
+    /// Address of the endpoint that served the last successful request
+    pub fn last_endpoint(&self) -> Option<SocketAddr> {
+        self.inner.transport().last_endpoint()
+    }
+
+    /// Number of endpoint switches so far
+    pub fn failover_count(&self) -> u64 {
+        self.inner.transport().failover_count()
+    }
+
+    /// Set the retry policy used for all requests
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.inner.set_retry_policy(policy);
+    }
+
+    /// Enable per-slave health tracking
+    /// 
+    /// Call [`probe_offline_slaves`](Self::probe_offline_slaves) periodically
+    /// so offline slaves are detected as online again.
+    pub fn enable_health_tracking(&mut self, config: HealthConfig) {
+        self.inner.enable_health_tracking(config);
+    }
+
+    /// Get the health tracker, if enabled
+    pub fn health(&self) -> Option<&HealthTracker> {
+        self.inner.health()
+    }
+
+    /// Subscribe to slave online/offline events, if health tracking is enabled
+    pub fn subscribe_health(&self) -> Option<broadcast::Receiver<HealthEvent>> {
+        self.inner.subscribe_health()
+    }
+
+    /// Probe offline slaves whose open period has elapsed
+    pub async fn probe_offline_slaves(&mut self) -> Vec<SlaveId> {
+        self.inner.probe_offline_slaves().await
+    }
+
+    /// Set the addressing mode for all addresses passed to the client
+    pub fn set_addressing(&mut self, mode: AddressingMode) {
+        self.inner.set_addressing(mode);
+    }
+
+    /// Enable or disable Enron mode
+    pub fn set_enron(&mut self, config: Option<EnronConfig>) {
+        self.inner.set_enron(config);
+    }
+
+    /// Execute a raw request
+    pub async fn execute_request(&mut self, request: ModbusRequest) -> ModbusResult<ModbusResponse> {
+        self.inner.execute_request(request).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusClient for ModbusFailoverClient {
+    async fn read_01(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.inner.read_01(slave_id, address, quantity).await
+    }
+    
+    async fn read_02(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.inner.read_02(slave_id, address, quantity).await
+    }
+    
+    async fn read_03(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.inner.read_03(slave_id, address, quantity).await
+    }
+    
+    async fn read_04(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.inner.read_04(slave_id, address, quantity).await
+    }
+    
+    async fn write_05(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
+        self.inner.write_05(slave_id, address, value).await
+    }
+    
+    async fn write_06(&mut self, slave_id: SlaveId, address: u16, value: u16) -> ModbusResult<()> {
+        self.inner.write_06(slave_id, address, value).await
+    }
+    
+    async fn write_0f(&mut self, slave_id: SlaveId, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.inner.write_0f(slave_id, address, values).await
+    }
+    
+    async fn write_10(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.inner.write_10(slave_id, address, values).await
+    }
+    
+    fn addressing_mode(&self) -> AddressingMode {
+        self.inner.addressing_mode()
+    }
+    
     fn is_connected(&self) -> bool {
         self.inner.is_connected()
     }
@@ -492,6 +1279,44 @@ impl ModbusRtuClient {
     pub fn set_packet_logging(&mut self, enabled: bool) {
         self.inner.transport_mut().set_packet_logging(enabled);
     }
+
+    /// Set the retry policy used for all requests
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.inner.set_retry_policy(policy);
+    }
+
+    /// Enable per-slave health tracking
+    /// 
+    /// Call [`probe_offline_slaves`](Self::probe_offline_slaves) periodically
+    /// so offline slaves are detected as online again.
+    pub fn enable_health_tracking(&mut self, config: HealthConfig) {
+        self.inner.enable_health_tracking(config);
+    }
+
+    /// Get the health tracker, if enabled
+    pub fn health(&self) -> Option<&HealthTracker> {
+        self.inner.health()
+    }
+
+    /// Subscribe to slave online/offline events, if health tracking is enabled
+    pub fn subscribe_health(&self) -> Option<broadcast::Receiver<HealthEvent>> {
+        self.inner.subscribe_health()
+    }
+
+    /// Probe offline slaves whose open period has elapsed
+    pub async fn probe_offline_slaves(&mut self) -> Vec<SlaveId> {
+        self.inner.probe_offline_slaves().await
+    }
+
+    /// Set the addressing mode for all addresses passed to the client
+    pub fn set_addressing(&mut self, mode: AddressingMode) {
+        self.inner.set_addressing(mode);
+    }
+
+    /// Enable or disable Enron mode
+    pub fn set_enron(&mut self, config: Option<EnronConfig>) {
+        self.inner.set_enron(config);
+    }
     
     /// Execute a raw request
     pub async fn execute_request(&mut self, request: ModbusRequest) -> ModbusResult<ModbusResponse> {
@@ -533,6 +1358,10 @@ impl ModbusClient for ModbusRtuClient {
         self.inner.write_10(slave_id, address, values).await
     }
     
+    fn addressing_mode(&self) -> AddressingMode {
+        self.inner.addressing_mode()
+    }
+    
     fn is_connected(&self) -> bool {
         self.inner.is_connected()
     }
@@ -551,6 +1380,9 @@ pub mod utils {
     use super::*;
     
     /// Read multiple register types in a single operation
+    /// 
+    /// Issues one request per tuple. Use [`crate::planner::ReadPlanner`] to
+    /// coalesce adjacent or nearby ranges into fewer requests.
     pub async fn read_mixed_registers<T: ModbusClient>(
         client: &mut T,
         slave_id: SlaveId,
@@ -658,6 +1490,179 @@ mod tests {
         }
     }
     
+    #[tokio::test]
+    async fn test_retry_policy() {
+        use crate::retry::RetryPolicy;
+        use crate::test_utils::ScriptedTransport;
+
+        let transport = ScriptedTransport::new();
+        transport.push(Err(ModbusError::timeout("read response", 100)));
+        transport.push(Err(ModbusError::exception(0x03, 0x06)));
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x1234, 0x5678]);
+
+        let policy = RetryPolicy::new(3).with_backoff(Duration::from_millis(1), Duration::from_millis(5));
+        let mut client = GenericModbusClient::new(transport.clone()).with_retry_policy(policy);
+
+        let values = client.read_03(1, 0, 2).await.unwrap();
+        assert_eq!(values, vec![0x1234, 0x5678]);
+        assert_eq!(transport.request_count(), 3);
+        assert_eq!(client.get_stats().retries, 2);
+
+        // Writes are not retried unless enabled
+        transport.push(Err(ModbusError::timeout("read response", 100)));
+        assert!(client.write_06(1, 0, 1).await.is_err());
+        assert_eq!(transport.request_count(), 4);
+        assert_eq!(client.get_stats().retries, 2);
+
+        // Non-retryable errors fail immediately
+        transport.push(Err(ModbusError::exception(0x03, 0x02)));
+        assert!(client.read_03(1, 0, 2).await.is_err());
+        assert_eq!(transport.request_count(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_reads_and_writes() {
+        use crate::test_utils::ScriptedTransport;
+
+        let transport = ScriptedTransport::new();
+        let expected: Vec<u16> = (0..250).collect();
+        for chunk in expected.chunks(100) {
+            transport.push_registers(ModbusFunction::ReadHoldingRegisters, chunk);
+        }
+        let mut client = GenericModbusClient::new(transport.clone());
+        let limits = ChunkLimits::registers(100);
+
+        let values = client.read_03_chunked(1, 1000, 250, &limits).await.unwrap();
+        assert_eq!(values, expected);
+        let requests = transport.requests.lock().unwrap().clone();
+        let ranges: Vec<(u16, u16)> = requests.iter().map(|r| (r.address, r.quantity)).collect();
+        assert_eq!(ranges, vec![(1000, 100), (1100, 100), (1200, 50)]);
+
+        // Second write chunk fails: the error names its range
+        transport.push(Ok(ModbusResponse::new_success(1, ModbusFunction::WriteMultipleRegisters, vec![])));
+        transport.push(Err(ModbusError::exception(0x10, 0x02)));
+        let err = client.write_10_chunked(1, 0, &expected, &limits).await.unwrap_err();
+        match err {
+            ModbusError::ChunkFailed { start, count, source } => {
+                assert_eq!((start, count), (100, 100));
+                assert!(matches!(*source, ModbusError::Exception { code: 0x02, .. }));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(transport.request_count(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_typed_value_helpers() {
+        use crate::test_utils::ScriptedTransport;
+
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x0000, 0x4060]);
+        transport.push_registers(ModbusFunction::ReadInputRegisters, &[0xFFFF, 0xFFFF, 0xFFFF, 0xFFFE]);
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x4142, 0x4300]);
+        transport.push(Ok(ModbusResponse::new_success(1, ModbusFunction::WriteMultipleRegisters, vec![])));
+        let mut client = GenericModbusClient::new(transport.clone());
+
+        assert_eq!(client.read_f32(1, 0, RegisterOrder::CDAB).await.unwrap(), 3.5);
+        assert_eq!(client.read_input_value::<i64>(1, 10, RegisterOrder::ABCD).await.unwrap(), -2);
+        assert_eq!(client.read_string(1, 20, 2, RegisterOrder::ABCD).await.unwrap(), "ABC");
+        client.write_value(1, 30, 0x11223344u32, RegisterOrder::BADC).await.unwrap();
+
+        let requests = transport.requests.lock().unwrap().clone();
+        assert_eq!(requests[1].quantity, 4);
+        assert_eq!(requests[3].data, vec![0x22, 0x11, 0x44, 0x33]);
+    }
+
+    #[tokio::test]
+    async fn test_enron_mode() {
+        use crate::test_utils::ScriptedTransport;
+        use crate::enron::ENRON_HOURLY_ARCHIVE;
+
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x3FC0, 0x0000, 0xC000, 0x0000]);
+        transport.push(Ok(ModbusResponse::new_success(1, ModbusFunction::WriteMultipleRegisters, vec![])));
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x4110, 0x0000]);
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x0000, 0x0001]);
+        let mut client = GenericModbusClient::new(transport.clone()).with_enron(EnronConfig::default());
+
+        assert_eq!(client.read_enron_f32(1, 7001, 2).await.unwrap(), vec![1.5, -2.0]);
+        client.write_enron_u32(1, 5001, &[1, 2]).await.unwrap();
+        let record = client.read_enron_archive(1, ENRON_HOURLY_ARCHIVE, 840).await.unwrap();
+        assert_eq!(record.values, vec![9.0]);
+        // A short 32-bit response is rejected
+        assert!(client.read_enron_u32(1, 5001, 2).await.is_err());
+
+        let requests = transport.requests.lock().unwrap().clone();
+        assert_eq!(requests[0].quantity, 2);
+        assert_eq!((requests[1].quantity, requests[1].data.len()), (2, 8));
+        assert_eq!(requests[2].quantity, 840);
+
+        // Outside Enron mode the 32-bit ranges are ordinary registers
+        client.set_enron(None);
+        assert!(client.read_03(1, ENRON_HOURLY_ARCHIVE, 840).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_one_based_addressing() {
+        use crate::test_utils::ScriptedTransport;
+
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[1]);
+        transport.push_registers(ModbusFunction::ReadInputRegisters, &[2]);
+        let mut client = GenericModbusClient::new(transport.clone()).with_addressing(AddressingMode::OneBased);
+
+        assert_eq!(client.read_03(1, 1, 1).await.unwrap(), vec![1]);
+        let value = client.read_at(1, "30010".parse().unwrap(), 1).await.unwrap();
+        assert_eq!(value, PointValue::Registers(vec![2]));
+        assert!(client.read_03(1, 0, 1).await.is_err());
+        assert!(client.write_at(1, "30001".parse().unwrap(), &PointValue::Registers(vec![1])).await.is_err());
+
+        let requests = transport.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        assert_eq!((requests[0].function, requests[0].address), (ModbusFunction::ReadHoldingRegisters, 0));
+        assert_eq!((requests[1].function, requests[1].address), (ModbusFunction::ReadInputRegisters, 9));
+    }
+
+    #[test]
+    fn test_chunk_split() {
+        assert_eq!(ChunkLimits::split(0, 250, 125).unwrap(), vec![(0, 125), (125, 125)]);
+        assert_eq!(ChunkLimits::split(65530, 6, 4).unwrap(), vec![(65530, 4), (65534, 2)]);
+        assert!(ChunkLimits::split(65530, 7, 4).is_err());
+        assert!(ChunkLimits::split(0, 0, 4).is_err());
+        assert_eq!(ChunkLimits::registers(200).max_read_registers, 125);
+    }
+
+    #[tokio::test]
+    async fn test_health_tracking_fails_fast() {
+        use crate::health::{CircuitState, HealthConfig, HealthEvent};
+        use crate::test_utils::ScriptedTransport;
+
+        let transport = ScriptedTransport::new();
+        let mut client = GenericModbusClient::new(transport.clone()).with_health_tracking(HealthConfig {
+            failure_threshold: 2,
+            open_duration: Duration::from_millis(20),
+            ..Default::default()
+        });
+        let mut events = client.subscribe_health().unwrap();
+
+        // Script is empty, so every request times out
+        assert!(client.read_03(3, 0, 1).await.is_err());
+        assert!(client.read_03(3, 0, 1).await.is_err());
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Offline { slave_id: 3 });
+
+        // Offline slave fails fast without touching the transport
+        let err = client.read_03(3, 0, 1).await.unwrap_err();
+        assert!(matches!(err, ModbusError::DeviceNotResponding { slave_id: 3 }));
+        assert_eq!(transport.request_count(), 2);
+
+        // Probe after the open period brings it back
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0]);
+        assert_eq!(client.probe_offline_slaves().await, vec![3]);
+        assert_eq!(client.health().unwrap().state(3), CircuitState::Closed);
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Online { slave_id: 3 });
+    }
+
     #[tokio::test]
     async fn test_tcp_client_creation() {
         use std::time::Duration;
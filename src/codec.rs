@@ -0,0 +1,333 @@
+//! # Typed Value Codec
+//!
+//! Modbus registers are 16 bits wide; everything else (32/64-bit integers,
+//! floats, BCD numbers, strings) is spread over several registers, and devices
+//! disagree about the order of the words and of the bytes within each word.
+//!
+//! This module converts between registers and typed values with an explicit
+//! [`RegisterOrder`]. Using the bytes `A B C D` of a 32-bit value from most to
+//! least significant:
+//!
+//! | Order | Word order | Byte order | Registers |
+//! |-------|------------|------------|-----------|
+//! | `ABCD` | big | big | `AB`, `CD` |
+//! | `CDAB` | little | big | `CD`, `AB` |
+//! | `BADC` | big | little | `BA`, `DC` |
+//! | `DCBA` | little | little | `DC`, `BA` |
+//!
+//! Supported values:
+//!
+//! - **Numbers**: `u16`, `i16`, `u32`, `i32`, `f32`, `u64`, `i64`, `f64` via [`RegisterValue`]
+//! - **BCD**: four decimal digits per register ([`decode_bcd`], [`encode_bcd`])
+//! - **Strings**: fixed-length ASCII/UTF-8, two characters per register,
+//!   padded with NUL ([`decode_string`], [`encode_string`])
+//! - **Structs**: whole register blocks via [`ModbusRegisters`], derivable with
+//!   the `derive` feature
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::codec::{self, RegisterOrder, RegisterValue};
+//!
+//! let registers = 1234.5f32.to_registers(RegisterOrder::CDAB);
+//! assert_eq!(f32::from_registers(&registers, RegisterOrder::CDAB).unwrap(), 1234.5);
+//!
+//! let registers = codec::encode_string("INV-01", 4, RegisterOrder::ABCD).unwrap();
+//! assert_eq!(codec::decode_string(&registers, RegisterOrder::ABCD).unwrap(), "INV-01");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ModbusError, ModbusResult};
+
+/// Order of the 16-bit words of multi-register values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// Most significant word first (Modbus convention)
+    #[default]
+    #[serde(alias = "big_endian", alias = "high_first")]
+    Big,
+    /// Least significant word first ("word swapped")
+    #[serde(alias = "little_endian", alias = "low_first", alias = "swapped")]
+    Little,
+}
+
+/// Order of the two bytes within each register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ByteOrder {
+    /// Most significant byte first (Modbus convention)
+    #[default]
+    #[serde(alias = "big_endian")]
+    Big,
+    /// Least significant byte first ("byte swapped")
+    #[serde(alias = "little_endian", alias = "swapped")]
+    Little,
+}
+
+/// Combined word and byte order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RegisterOrder {
+    /// Order of the registers
+    pub word: WordOrder,
+    /// Order of the bytes within each register
+    pub byte: ByteOrder,
+}
+
+impl RegisterOrder {
+    /// Big-endian words and bytes (Modbus convention)
+    pub const ABCD: Self = Self::new(WordOrder::Big, ByteOrder::Big);
+    /// Word swapped
+    pub const CDAB: Self = Self::new(WordOrder::Little, ByteOrder::Big);
+    /// Byte swapped
+    pub const BADC: Self = Self::new(WordOrder::Big, ByteOrder::Little);
+    /// Word and byte swapped (fully little-endian)
+    pub const DCBA: Self = Self::new(WordOrder::Little, ByteOrder::Little);
+
+    /// Create an order from its parts
+    pub const fn new(word: WordOrder, byte: ByteOrder) -> Self {
+        Self { word, byte }
+    }
+}
+
+/// Convert registers into the value's bytes, most significant first
+pub fn registers_to_bytes(registers: &[u16], order: RegisterOrder) -> Vec<u8> {
+    let mut words = registers.to_vec();
+    if order.word == WordOrder::Little {
+        words.reverse();
+    }
+    words
+        .into_iter()
+        .flat_map(|word| match order.byte {
+            ByteOrder::Big => word.to_be_bytes(),
+            ByteOrder::Little => word.to_le_bytes(),
+        })
+        .collect()
+}
+
+/// Convert a value's bytes, most significant first, into registers
+///
+/// An odd number of bytes is padded with a trailing zero byte.
+pub fn bytes_to_registers(bytes: &[u8], order: RegisterOrder) -> Vec<u16> {
+    let mut words: Vec<u16> = bytes
+        .chunks(2)
+        .map(|pair| {
+            let pair = [pair[0], pair.get(1).copied().unwrap_or(0)];
+            match order.byte {
+                ByteOrder::Big => u16::from_be_bytes(pair),
+                ByteOrder::Little => u16::from_le_bytes(pair),
+            }
+        })
+        .collect();
+    if order.word == WordOrder::Little {
+        words.reverse();
+    }
+    words
+}
+
+/// A value stored in a fixed number of registers
+pub trait RegisterValue: Sized + Send + Copy {
+    /// Number of registers occupied by the value
+    const REGISTERS: u16;
+
+    /// Decode the value from the first `REGISTERS` registers
+    fn from_registers(registers: &[u16], order: RegisterOrder) -> ModbusResult<Self>;
+
+    /// Encode the value into `REGISTERS` registers
+    fn to_registers(&self, order: RegisterOrder) -> Vec<u16>;
+}
+
+macro_rules! impl_register_value {
+    ($($ty:ty => $registers:expr),* $(,)?) => {
+        $(
+            impl RegisterValue for $ty {
+                const REGISTERS: u16 = $registers;
+
+                fn from_registers(registers: &[u16], order: RegisterOrder) -> ModbusResult<Self> {
+                    let count = Self::REGISTERS as usize;
+                    if registers.len() < count {
+                        return Err(ModbusError::invalid_data(format!(
+                            "{} needs {} registers, got {}",
+                            stringify!($ty), count, registers.len()
+                        )));
+                    }
+                    let bytes = registers_to_bytes(&registers[..count], order);
+                    Ok(<$ty>::from_be_bytes(bytes.try_into().expect("register count matches type size")))
+                }
+
+                fn to_registers(&self, order: RegisterOrder) -> Vec<u16> {
+                    bytes_to_registers(&self.to_be_bytes(), order)
+                }
+            }
+        )*
+    };
+}
+
+impl_register_value! {
+    u16 => 1,
+    i16 => 1,
+    u32 => 2,
+    i32 => 2,
+    f32 => 2,
+    u64 => 4,
+    i64 => 4,
+    f64 => 4,
+}
+
+/// A struct stored in a contiguous block of registers
+///
+/// With the `derive` feature this is implemented by `#[derive(ModbusRegisters)]`,
+/// so a whole struct can be read with one `read_03` and written back with one `write_10`.
+pub trait ModbusRegisters: Sized {
+    /// Number of registers in the block
+    const REGISTER_COUNT: u16;
+
+    /// Decode the struct from the first `REGISTER_COUNT` registers
+    fn from_registers(registers: &[u16]) -> ModbusResult<Self>;
+
+    /// Encode the struct into `REGISTER_COUNT` registers
+    ///
+    /// Fails if a string field does not fit in its registers.
+    fn to_registers(&self) -> ModbusResult<Vec<u16>>;
+}
+
+/// Decode a BCD number, four decimal digits per register
+///
+/// # Returns
+///
+/// The number, or an error if a nibble is not a decimal digit
+pub fn decode_bcd(registers: &[u16], order: RegisterOrder) -> ModbusResult<u64> {
+    if registers.len() > 4 {
+        return Err(ModbusError::invalid_data("BCD values are limited to 16 digits"));
+    }
+
+    let mut value = 0u64;
+    for byte in registers_to_bytes(registers, order) {
+        for nibble in [byte >> 4, byte & 0x0F] {
+            if nibble > 9 {
+                return Err(ModbusError::invalid_data(format!("Invalid BCD digit 0x{:X}", nibble)));
+            }
+            value = value * 10 + nibble as u64;
+        }
+    }
+    Ok(value)
+}
+
+/// Encode a number as BCD into `registers` registers
+pub fn encode_bcd(value: u64, registers: u16, order: RegisterOrder) -> ModbusResult<Vec<u16>> {
+    let digits = registers as u32 * 4;
+    if registers == 0 || registers > 4 || (digits < 20 && value >= 10u64.pow(digits)) {
+        return Err(ModbusError::invalid_data(format!("{} does not fit in {} BCD registers", value, registers)));
+    }
+
+    let mut bytes = vec![0u8; registers as usize * 2];
+    let mut remaining = value;
+    for byte in bytes.iter_mut().rev() {
+        let low = (remaining % 10) as u8;
+        remaining /= 10;
+        let high = (remaining % 10) as u8;
+        remaining /= 10;
+        *byte = (high << 4) | low;
+    }
+    Ok(bytes_to_registers(&bytes, order))
+}
+
+/// Decode a UTF-8 string packed two bytes per register
+///
+/// Trailing NUL and space padding is removed.
+pub fn decode_string(registers: &[u16], order: RegisterOrder) -> ModbusResult<String> {
+    let bytes = registers_to_bytes(registers, order);
+    let end = bytes.iter().rposition(|&b| b != 0 && b != b' ').map(|i| i + 1).unwrap_or(0);
+    String::from_utf8(bytes[..end].to_vec())
+        .map_err(|e| ModbusError::invalid_data(format!("Invalid string data: {}", e)))
+}
+
+/// Encode a string into exactly `registers` registers, padded with NUL
+pub fn encode_string(value: &str, registers: u16, order: RegisterOrder) -> ModbusResult<Vec<u16>> {
+    let capacity = registers as usize * 2;
+    if value.len() > capacity {
+        return Err(ModbusError::invalid_data(format!(
+            "String of {} bytes does not fit in {} registers",
+            value.len(), registers
+        )));
+    }
+
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.resize(capacity, 0);
+    Ok(bytes_to_registers(&bytes, order))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_orders() {
+        let value = 0x11223344u32;
+        assert_eq!(value.to_registers(RegisterOrder::ABCD), vec![0x1122, 0x3344]);
+        assert_eq!(value.to_registers(RegisterOrder::CDAB), vec![0x3344, 0x1122]);
+        assert_eq!(value.to_registers(RegisterOrder::BADC), vec![0x2211, 0x4433]);
+        assert_eq!(value.to_registers(RegisterOrder::DCBA), vec![0x4433, 0x2211]);
+
+        for order in [RegisterOrder::ABCD, RegisterOrder::CDAB, RegisterOrder::BADC, RegisterOrder::DCBA] {
+            assert_eq!(u32::from_registers(&value.to_registers(order), order).unwrap(), value);
+            assert_eq!(f64::from_registers(&(-1.25e10f64).to_registers(order), order).unwrap(), -1.25e10);
+            assert_eq!(i64::from_registers(&i64::MIN.to_registers(order), order).unwrap(), i64::MIN);
+            assert_eq!(i16::from_registers(&(-2i16).to_registers(order), order).unwrap(), -2);
+        }
+
+        assert!(u32::from_registers(&[1], RegisterOrder::ABCD).is_err());
+    }
+
+    #[test]
+    fn test_bcd_and_strings() {
+        assert_eq!(encode_bcd(1234, 1, RegisterOrder::ABCD).unwrap(), vec![0x1234]);
+        assert_eq!(encode_bcd(12345678, 2, RegisterOrder::CDAB).unwrap(), vec![0x5678, 0x1234]);
+        assert_eq!(decode_bcd(&[0x5678, 0x1234], RegisterOrder::CDAB).unwrap(), 12345678);
+        assert!(decode_bcd(&[0x12A4], RegisterOrder::ABCD).is_err());
+        assert!(encode_bcd(10000, 1, RegisterOrder::ABCD).is_err());
+
+        let registers = encode_string("SN123", 4, RegisterOrder::ABCD).unwrap();
+        assert_eq!(registers, vec![0x534E, 0x3132, 0x3300, 0x0000]);
+        assert_eq!(decode_string(&registers, RegisterOrder::ABCD).unwrap(), "SN123");
+        let swapped = encode_string("SN123", 4, RegisterOrder::BADC).unwrap();
+        assert_eq!(swapped[0], 0x4E53);
+        assert_eq!(decode_string(&swapped, RegisterOrder::BADC).unwrap(), "SN123");
+        assert!(encode_string("too long", 2, RegisterOrder::ABCD).is_err());
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_register_block() {
+        use crate::ModbusRegisters;
+
+        #[derive(Debug, PartialEq, ModbusRegisters)]
+        #[modbus(order = "cdab")]
+        struct Inverter {
+            #[modbus(ty = "u16", scale = 0.1)]
+            voltage: f32,
+            #[modbus(offset = 2)]
+            power: f32,
+            #[modbus(order = "abcd")]
+            temperature: i16,
+            #[modbus(offset = 8, len = 2, order = "abcd")]
+            model: String,
+            #[modbus(skip)]
+            polled: bool,
+        }
+
+        assert_eq!(Inverter::REGISTER_COUNT, 10);
+
+        let registers = vec![2301, 0, 0x0000, 0x4120, 0xFFF6, 0, 0, 0, 0x5356, 0x3100];
+        let inverter = Inverter::from_registers(&registers).unwrap();
+        assert!((inverter.voltage - 230.1).abs() < 1e-3);
+        assert_eq!(inverter.power, 10.0);
+        assert_eq!(inverter.temperature, -10);
+        assert_eq!(inverter.model, "SV1");
+        assert!(!inverter.polled);
+        assert_eq!(inverter.to_registers().unwrap(), registers);
+
+        assert!(Inverter::from_registers(&registers[..9]).is_err());
+    }
+}
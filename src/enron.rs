@@ -0,0 +1,286 @@
+//! # Enron Modbus Extension
+//!
+//! Flow computers following the Enron (Daniel) convention extend Modbus with:
+//!
+//! - **32-bit registers**: in the 5000 (long integer) and 7000 (float) ranges
+//!   each register holds 32 bits, and the request quantity counts 32-bit values
+//! - **Event log**: reading register 32 returns up to 12 pending event/alarm
+//!   records; writing coil 32 acknowledges them
+//! - **Archives**: reading an archive register (700 hourly, 701 daily) returns
+//!   one record, selected by the request's quantity field
+//!
+//! Enron mode is enabled with an [`EnronConfig`] on the client
+//! ([`GenericModbusClient::with_enron`](crate::client::GenericModbusClient::with_enron)),
+//! which also configures the transport, and on the register bank served by a
+//! server ([`ModbusRegisterBank::with_enron`](crate::register_bank::ModbusRegisterBank::with_enron)).
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusClient, ModbusTcpClient};
+//! use voltage_modbus::enron::{EnronConfig, ENRON_HOURLY_ARCHIVE};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let mut client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//! client.set_enron(Some(EnronConfig::default()));
+//!
+//! let flow_rates = client.read_enron_f32(1, 7001, 4).await?;
+//! let events = client.read_enron_events(1).await?;
+//! client.acknowledge_enron_events(1).await?;
+//! let last_hour = client.read_enron_archive(1, ENRON_HOURLY_ARCHIVE, 1).await?;
+//! println!("{:?} {:?} {:?}", flow_rates, events, last_hour.values);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ops::RangeInclusive;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusFunction, ModbusRequest};
+
+/// Register holding the event log
+pub const ENRON_EVENT_REGISTER: u16 = 32;
+
+/// Standard hourly archive register
+pub const ENRON_HOURLY_ARCHIVE: u16 = 700;
+
+/// Standard daily archive register
+pub const ENRON_DAILY_ARCHIVE: u16 = 701;
+
+/// Maximum event records returned by one event log read
+pub const ENRON_MAX_EVENTS_PER_READ: usize = 12;
+
+/// Maximum 32-bit values per read (250 data bytes)
+pub const ENRON_MAX_READ_VALUES: u16 = 62;
+
+/// Maximum 32-bit values per write (246 data bytes)
+pub const ENRON_MAX_WRITE_VALUES: u16 = 61;
+
+/// Registers of one event record
+const EVENT_RECORD_REGISTERS: usize = 10;
+
+/// Enron mode configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnronConfig {
+    /// Address ranges whose registers are 32 bits wide
+    pub long_ranges: Vec<RangeInclusive<u16>>,
+    /// Archive registers, read with the record index as quantity
+    pub archive_registers: Vec<u16>,
+}
+
+impl Default for EnronConfig {
+    fn default() -> Self {
+        Self {
+            long_ranges: vec![5000..=5999, 7000..=7999],
+            archive_registers: vec![ENRON_HOURLY_ARCHIVE, ENRON_DAILY_ARCHIVE],
+        }
+    }
+}
+
+impl EnronConfig {
+    /// Check whether a register is 32 bits wide
+    pub fn is_long(&self, address: u16) -> bool {
+        self.long_ranges.iter().any(|range| range.contains(&address))
+    }
+
+    /// Check whether `quantity` registers from `address` lie in one 32-bit range
+    ///
+    /// # Returns
+    ///
+    /// `Ok(true)` for a 32-bit range, `Ok(false)` for 16-bit registers, or an
+    /// error if the request straddles a range boundary
+    pub fn long_range(&self, address: u16, quantity: u16) -> ModbusResult<bool> {
+        let last = address.saturating_add(quantity.max(1) - 1);
+        match self.long_ranges.iter().find(|range| range.contains(&address)) {
+            Some(range) if range.contains(&last) => Ok(true),
+            Some(_) => Err(ModbusError::invalid_address(address, quantity)),
+            None if self.long_ranges.iter().any(|range| *range.start() > address && *range.start() <= last) => {
+                Err(ModbusError::invalid_address(address, quantity))
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Check whether a register is the event log or an archive
+    pub fn is_record_register(&self, address: u16) -> bool {
+        address == ENRON_EVENT_REGISTER || self.archive_registers.contains(&address)
+    }
+
+    /// Validate a request under Enron rules
+    ///
+    /// Requests that do not touch Enron registers get the standard validation.
+    pub fn validate(&self, request: &ModbusRequest) -> ModbusResult<()> {
+        match request.function {
+            ModbusFunction::ReadHoldingRegisters if self.is_record_register(request.address) => {
+                if request.slave_id == 0 || request.slave_id > 247 {
+                    return Err(ModbusError::invalid_data(format!("Invalid slave ID: {}", request.slave_id)));
+                }
+                Ok(())
+            }
+            ModbusFunction::ReadHoldingRegisters if self.long_range(request.address, request.quantity)? => {
+                if request.quantity > ENRON_MAX_READ_VALUES {
+                    return Err(ModbusError::invalid_data(format!("Too many 32-bit registers requested: {}", request.quantity)));
+                }
+                request.validate()
+            }
+            ModbusFunction::WriteMultipleRegisters if self.long_range(request.address, request.quantity)? => {
+                if request.quantity == 0 || request.quantity > ENRON_MAX_WRITE_VALUES || request.data.len() != request.quantity as usize * 4 {
+                    return Err(ModbusError::invalid_data(format!("Invalid 32-bit register write of {} values", request.quantity)));
+                }
+                request.validate()
+            }
+            _ => request.validate(),
+        }
+    }
+}
+
+/// Join big-endian register pairs into 32-bit values
+pub fn registers_to_u32(registers: &[u16]) -> ModbusResult<Vec<u32>> {
+    if !registers.len().is_multiple_of(2) {
+        return Err(ModbusError::invalid_data("32-bit data has an odd number of registers"));
+    }
+    Ok(registers.chunks(2).map(|pair| ((pair[0] as u32) << 16) | pair[1] as u32).collect())
+}
+
+/// Split 32-bit values into big-endian register pairs
+pub fn u32_to_registers(values: &[u32]) -> Vec<u16> {
+    values.iter().flat_map(|&value| [(value >> 16) as u16, value as u16]).collect()
+}
+
+/// Combine MMDDYY date and HHMMSS time values into a timestamp
+///
+/// Years below 70 are taken as 20xx.
+pub fn decode_timestamp(date: f32, time: f32) -> Option<NaiveDateTime> {
+    let (date, time) = (date as u32, time as u32);
+    let year = (date % 100) as i32;
+    let year = if year < 70 { 2000 + year } else { 1900 + year };
+    NaiveDate::from_ymd_opt(year, date / 10000, (date / 100) % 100)?
+        .and_hms_opt(time / 10000, (time / 100) % 100, time % 100)
+}
+
+/// An event or alarm record from the event log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnronEvent {
+    /// Operator change / alarm status flags
+    pub status: u16,
+    /// Register the event refers to
+    pub register: u16,
+    /// Time as HHMMSS
+    pub time: f32,
+    /// Date as MMDDYY
+    pub date: f32,
+    /// Value before an operator change, or the alarm value
+    pub previous_value: f32,
+    /// Value after an operator change
+    pub current_value: f32,
+}
+
+impl EnronEvent {
+    /// Timestamp of the event
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        decode_timestamp(self.date, self.time)
+    }
+
+    /// Decode event records from an event log response
+    pub fn decode_all(registers: &[u16]) -> ModbusResult<Vec<Self>> {
+        if !registers.len().is_multiple_of(EVENT_RECORD_REGISTERS) {
+            return Err(ModbusError::invalid_data(format!("Event log of {} registers is not a whole number of records", registers.len())));
+        }
+        Ok(registers
+            .chunks(EVENT_RECORD_REGISTERS)
+            .map(|r| {
+                let float = |i: usize| f32::from_bits(((r[i] as u32) << 16) | r[i + 1] as u32);
+                Self {
+                    status: r[0],
+                    register: r[1],
+                    time: float(2),
+                    date: float(4),
+                    previous_value: float(6),
+                    current_value: float(8),
+                }
+            })
+            .collect())
+    }
+
+    /// Encode the record as registers
+    pub fn to_registers(&self) -> Vec<u16> {
+        let mut registers = vec![self.status, self.register];
+        registers.extend(u32_to_registers(&[
+            self.time.to_bits(),
+            self.date.to_bits(),
+            self.previous_value.to_bits(),
+            self.current_value.to_bits(),
+        ]));
+        registers
+    }
+}
+
+/// One archive record
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveRecord {
+    /// Archive register the record was read from
+    pub register: u16,
+    /// Record index
+    pub index: u16,
+    /// Record values; by convention the date (MMDDYY) and time (HHMMSS) come first
+    pub values: Vec<f32>,
+}
+
+impl ArchiveRecord {
+    /// Timestamp from the leading date and time values
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        match self.values.as_slice() {
+            [date, time, ..] => decode_timestamp(*date, *time),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges_and_validation() {
+        let config = EnronConfig::default();
+        assert!(config.is_long(7001));
+        assert!(!config.is_long(4999));
+        assert!(config.long_range(5001, 10).unwrap());
+        assert!(!config.long_range(100, 10).unwrap());
+        assert!(config.long_range(5995, 10).is_err());
+        assert!(config.long_range(4995, 10).is_err());
+
+        let archive = ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, ENRON_HOURLY_ARCHIVE, 840);
+        assert!(archive.validate().is_err());
+        assert!(config.validate(&archive).is_ok());
+
+        let longs = ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 7001, 63);
+        assert!(config.validate(&longs).is_err());
+
+        let mut write = ModbusRequest::new_write(1, ModbusFunction::WriteMultipleRegisters, 7001, vec![0; 8]);
+        write.quantity = 2;
+        assert!(config.validate(&write).is_ok());
+        write.quantity = 4;
+        assert!(config.validate(&write).is_err());
+    }
+
+    #[test]
+    fn test_event_records() {
+        let event = EnronEvent {
+            status: 0x0001,
+            register: 7003,
+            time: 134501.0,
+            date: 102426.0,
+            previous_value: 1.5,
+            current_value: 2.5,
+        };
+        let registers = event.to_registers();
+        assert_eq!(registers.len(), 10);
+        assert_eq!(EnronEvent::decode_all(&registers).unwrap(), vec![event]);
+        assert_eq!(event.timestamp().unwrap().to_string(), "2026-10-24 13:45:01");
+        assert!(EnronEvent::decode_all(&registers[..9]).is_err());
+    }
+}
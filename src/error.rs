@@ -32,6 +32,7 @@
 //! ### System Errors
 //! - **Configuration Errors**: Client/server configuration issues
 //! - **Device Errors**: Device-specific communication problems
+//! - **Chunk Errors**: Failed part of a split read or write, with its address range
 //! - **Internal Errors**: Library internal errors (should not occur in normal operation)
 //! 
 //! ## Error Recovery
@@ -324,6 +325,17 @@ pub enum ModbusError {
     #[error("Internal error: {message}")]
     Internal { message: String },
 
+    /// A chunk of a split read or write failed
+    /// 
+    /// Reported by the chunked client methods, identifying the register
+    /// or coil range of the request that failed.
+    /// 
+    /// # Examples
+    /// - Timeout on the third 100-register block of a 600-register read
+    /// - Illegal data address for a block past the end of the device map
+    #[error("Chunk failed: start={start}, count={count}: {source}")]
+    ChunkFailed { start: u16, count: u16, source: Box<ModbusError> },
+
     // Legacy aliases for compatibility
     /// Legacy timeout error (use Timeout instead)
     #[error("Timeout")]
@@ -543,6 +555,21 @@ impl ModbusError {
         Self::Internal { message: message.into() }
     }
     
+    /// Create a chunk failure error
+    /// 
+    /// # Arguments
+    /// 
+    /// * `start` - Start address of the failed chunk
+    /// * `count` - Number of registers or coils in the failed chunk
+    /// * `source` - Error returned for the chunk
+    /// 
+    /// # Returns
+    /// 
+    /// New `ModbusError::ChunkFailed` variant
+    pub fn chunk_failed(start: u16, count: u16, source: ModbusError) -> Self {
+        Self::ChunkFailed { start, count, source: Box::new(source) }
+    }
+    
     /// Check if the error is recoverable (can retry)
     /// 
     /// Determines whether an operation that failed with this error
@@ -571,6 +598,7 @@ impl ModbusError {
             Self::Connection { .. } => true,
             Self::Timeout { .. } => true,
             Self::DeviceNotResponding { .. } => true,
+            Self::ChunkFailed { source, .. } => source.is_recoverable(),
             Self::Exception { code, .. } => {
                 // Some exceptions are recoverable
                 matches!(code, 0x05 | 0x06) // Acknowledge, Busy
@@ -0,0 +1,748 @@
+//! # Fault Injection
+//!
+//! Client stacks have to survive devices that misbehave. A server with a
+//! [`FaultInjector`] misbehaves on demand: each [`FaultRule`] fires with a
+//! probability for matching requests and injects a [`ServerFault`]:
+//!
+//! - **Delay**: wait before replying
+//! - **Dropped reply**: process the request, send nothing
+//! - **Corrupted CRC**: flip the CRC of RTU replies
+//! - **Wrong transaction id**: answer TCP requests with another transaction id
+//! - **Truncated frame**: send only the first half of the reply
+//! - **Forced exception**: answer with an exception such as Acknowledge (0x05)
+//!   or Slave Device Busy (0x06) without processing the request
+//! - **Connection reset**: abort the TCP connection instead of replying
+//!
+//! Rules can be limited to a function code and to requests touching an
+//! address range. Several rules may fire for one request; their faults are
+//! combined. With a seed, the same requests see the same faults on every run.
+//!
+//! ## Example Configuration (YAML)
+//!
+//! ```yaml
+//! seed: 42
+//! rules:
+//!   - fault: { kind: delay, ms: 300 }
+//!     probability: 0.2
+//!   - fault: { kind: exception, code: 6 }
+//!     probability: 0.05
+//!     function: 16
+//!   - fault: { kind: drop_reply }
+//!     probability: 0.5
+//!     addresses: { start: 100, end: 199 }
+//! ```
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use voltage_modbus::{ModbusServer, ModbusTcpServer};
+//! use voltage_modbus::fault_injection::{FaultConfig, FaultRule, ServerFault};
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let faults = FaultConfig::new()
+//!     .with_seed(42)
+//!     .rule(FaultRule::new(ServerFault::delay(Duration::from_millis(300))).with_probability(0.2))
+//!     .rule(FaultRule::new(ServerFault::busy()).with_probability(0.05).for_function(0x10));
+//!
+//! let mut server = ModbusTcpServer::new("127.0.0.1:5020")?.with_faults(faults)?;
+//! server.start().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Client Side
+//!
+//! [`FaultyTransport`] wraps any [`ModbusTransport`] and injects
+//! [`TransportFault`]s into the requests of the client above it: latency,
+//! timeouts, I/O errors, exception responses and bit flips. Faults follow a
+//! [`FaultScenario`] script, request by request, and once the script is done
+//! come from seeded random rules. Every injection is recorded, so tests can
+//! assert on what the client went through.
+//!
+//! ```rust
+//! use voltage_modbus::{ModbusClient, RetryPolicy};
+//! use voltage_modbus::client::GenericModbusClient;
+//! use voltage_modbus::fault_injection::{FaultScenario, FaultyTransport, TransportFault};
+//! # use voltage_modbus::transport::{ModbusTransport, TransportStats};
+//! # use voltage_modbus::{ModbusRequest, ModbusResponse, ModbusResult};
+//! # struct Device;
+//! # #[async_trait::async_trait]
+//! # impl ModbusTransport for Device {
+//! #     async fn request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+//! #         Ok(ModbusResponse::new_success(request.slave_id, request.function, vec![2, 0, 42]))
+//! #     }
+//! #     fn is_connected(&self) -> bool { true }
+//! #     async fn close(&mut self) -> ModbusResult<()> { Ok(()) }
+//! #     fn get_stats(&self) -> TransportStats { TransportStats::default() }
+//! # }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! // First request times out, second sees a broken link, then the device answers
+//! let scenario = FaultScenario::new().inject(TransportFault::Timeout).inject(TransportFault::IoError);
+//! let transport = FaultyTransport::new(Device, 7).with_scenario(scenario);
+//! let mut client = GenericModbusClient::new(transport).with_retry_policy(RetryPolicy::new(3));
+//!
+//! assert_eq!(client.read_03(1, 0, 1).await.unwrap(), vec![42]);
+//! assert_eq!(client.transport().injections().len(), 2);
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::enron::EnronConfig;
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusFunction, ModbusRequest, ModbusResponse, SlaveId};
+use crate::transport::{ModbusTransport, TransportStats};
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// Misbehavior of a server for one request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ServerFault {
+    /// Wait before replying
+    Delay {
+        /// Delay in milliseconds
+        ms: u64,
+    },
+    /// Process the request but send no reply
+    DropReply,
+    /// Invert the CRC of the reply (RTU only)
+    CorruptCrc,
+    /// Reply with the next transaction id (TCP only)
+    WrongTransactionId,
+    /// Send only the first half of the reply
+    Truncate,
+    /// Reply with an exception instead of processing the request
+    Exception {
+        /// Exception code
+        code: u8,
+    },
+    /// Abort the connection instead of replying; serial servers send nothing
+    ResetConnection,
+}
+
+impl ServerFault {
+    /// Delay the reply
+    pub fn delay(delay: Duration) -> Self {
+        ServerFault::Delay { ms: delay.as_millis() as u64 }
+    }
+
+    /// Exception 0x05 (Acknowledge)
+    pub fn acknowledge() -> Self {
+        ServerFault::Exception { code: 0x05 }
+    }
+
+    /// Exception 0x06 (Slave Device Busy)
+    pub fn busy() -> Self {
+        ServerFault::Exception { code: 0x06 }
+    }
+}
+
+/// Fault injected with a probability into matching requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultRule {
+    /// Fault to inject
+    pub fault: ServerFault,
+    /// Probability of firing for a matching request, from 0 to 1
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// Only requests with this function code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<u8>,
+    /// Only requests touching an address in this range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addresses: Option<RangeInclusive<u16>>,
+}
+
+impl FaultRule {
+    /// Inject `fault` into every request
+    pub fn new(fault: ServerFault) -> Self {
+        Self { fault, probability: 1.0, function: None, addresses: None }
+    }
+
+    /// Fire with `probability`
+    pub fn with_probability(mut self, probability: f64) -> Self {
+        self.probability = probability;
+        self
+    }
+
+    /// Only match requests with `function` code
+    pub fn for_function(mut self, function: u8) -> Self {
+        self.function = Some(function);
+        self
+    }
+
+    /// Only match requests touching `addresses`
+    pub fn for_addresses(mut self, addresses: RangeInclusive<u16>) -> Self {
+        self.addresses = Some(addresses);
+        self
+    }
+
+    /// Check whether the rule applies to a request
+    ///
+    /// # Arguments
+    ///
+    /// * `function` - Function code of the request
+    /// * `range` - First and last address of the request, if it has any
+    pub fn matches(&self, function: u8, range: Option<(u16, u16)>) -> bool {
+        if self.function.is_some_and(|expected| expected != function) {
+            return false;
+        }
+        match (&self.addresses, range) {
+            (None, _) => true,
+            (Some(addresses), Some((first, last))) => first <= *addresses.end() && last >= *addresses.start(),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Fault injection configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct FaultConfig {
+    /// Seed of the random draws, for reproducible runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Rules, all evaluated for each request
+    #[serde(default)]
+    pub rules: Vec<FaultRule>,
+}
+
+impl FaultConfig {
+    /// Configuration without rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule
+    pub fn rule(mut self, rule: FaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Seed the random draws
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Parse a configuration from YAML
+    pub fn from_yaml_str(yaml: &str) -> ModbusResult<Self> {
+        let config: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| ModbusError::configuration(format!("Invalid fault configuration: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a configuration from a YAML file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ModbusError::configuration(format!("Cannot read {}: {}", path.display(), e)))?;
+        Self::from_yaml_str(&content)
+    }
+
+    /// Check the probabilities and address ranges of the rules
+    pub fn validate(&self) -> ModbusResult<()> {
+        for rule in &self.rules {
+            if !(0.0..=1.0).contains(&rule.probability) {
+                return Err(ModbusError::configuration(format!("Fault probability {} is not between 0 and 1", rule.probability)));
+            }
+            if rule.addresses.as_ref().is_some_and(|addresses| addresses.is_empty()) {
+                return Err(ModbusError::configuration("Fault rule has an empty address range"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Faults drawn for one request, combined
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FaultPlan {
+    /// Total delay before replying
+    pub delay: Duration,
+    /// Exception replacing the request's processing
+    pub exception: Option<u8>,
+    /// Send no reply
+    pub drop_reply: bool,
+    /// Abort the connection
+    pub reset: bool,
+    /// Invert the reply CRC
+    pub corrupt_crc: bool,
+    /// Change the reply transaction id
+    pub wrong_transaction_id: bool,
+    /// Cut the reply in half
+    pub truncate: bool,
+}
+
+impl FaultPlan {
+    /// Check whether no fault was drawn
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check whether a reply is sent at all
+    pub fn sends_reply(&self) -> bool {
+        !self.drop_reply && !self.reset
+    }
+
+    fn add(&mut self, fault: &ServerFault) {
+        match *fault {
+            ServerFault::Delay { ms } => self.delay += Duration::from_millis(ms),
+            ServerFault::DropReply => self.drop_reply = true,
+            ServerFault::CorruptCrc => self.corrupt_crc = true,
+            ServerFault::WrongTransactionId => self.wrong_transaction_id = true,
+            ServerFault::Truncate => self.truncate = true,
+            ServerFault::Exception { code } => self.exception = self.exception.or(Some(code)),
+            ServerFault::ResetConnection => self.reset = true,
+        }
+    }
+
+    /// Apply the frame faults to a TCP reply (MBAP header included)
+    pub(crate) fn apply_tcp(&self, reply: &mut Vec<u8>) {
+        if self.wrong_transaction_id && reply.len() >= 2 {
+            let transaction_id = u16::from_be_bytes([reply[0], reply[1]]).wrapping_add(1);
+            reply[..2].copy_from_slice(&transaction_id.to_be_bytes());
+        }
+        self.apply_truncate(reply);
+    }
+
+    /// Apply the frame faults to an RTU reply (CRC included)
+    pub(crate) fn apply_rtu(&self, reply: &mut Vec<u8>) {
+        if self.corrupt_crc && reply.len() >= 2 {
+            let len = reply.len();
+            reply[len - 2] ^= 0xFF;
+            reply[len - 1] ^= 0xFF;
+        }
+        self.apply_truncate(reply);
+    }
+
+    fn apply_truncate(&self, reply: &mut Vec<u8>) {
+        if self.truncate {
+            reply.truncate(reply.len() / 2);
+        }
+    }
+}
+
+/// Draws faults for the requests of a server
+#[derive(Debug)]
+pub struct FaultInjector {
+    config: FaultConfig,
+    rng: Mutex<StdRng>,
+    injected: AtomicU64,
+}
+
+impl FaultInjector {
+    /// Create an injector from a validated configuration
+    pub fn new(config: FaultConfig) -> ModbusResult<Self> {
+        config.validate()?;
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(Self { config, rng: Mutex::new(rng), injected: AtomicU64::new(0) })
+    }
+
+    /// Fault configuration
+    pub fn config(&self) -> &FaultConfig {
+        &self.config
+    }
+
+    /// Number of requests that received at least one fault
+    pub fn injected(&self) -> u64 {
+        self.injected.load(Ordering::Relaxed)
+    }
+
+    /// Draw the faults for a request
+    ///
+    /// # Arguments
+    ///
+    /// * `pdu` - Function code followed by the request data
+    pub fn plan(&self, pdu: &[u8]) -> FaultPlan {
+        let mut plan = FaultPlan::default();
+        let Some(&function) = pdu.first() else {
+            return plan;
+        };
+        let range = request_range(pdu);
+
+        let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+        for rule in &self.config.rules {
+            if rule.matches(function, range) && rng.gen_bool(rule.probability) {
+                plan.add(&rule.fault);
+            }
+        }
+        if !plan.is_empty() {
+            self.injected.fetch_add(1, Ordering::Relaxed);
+        }
+        plan
+    }
+}
+
+/// First and last address touched by a request
+fn request_range(pdu: &[u8]) -> Option<(u16, u16)> {
+    if pdu.len() < 5 {
+        return None;
+    }
+    let address = u16::from_be_bytes([pdu[1], pdu[2]]);
+    let quantity = match pdu[0] {
+        0x05 | 0x06 => 1,
+        0x01..=0x04 | 0x0F | 0x10 => u16::from_be_bytes([pdu[3], pdu[4]]).max(1),
+        _ => return None,
+    };
+    Some((address, address.saturating_add(quantity - 1)))
+}
+
+/// Fault injected into a client request by [`FaultyTransport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportFault {
+    /// Wait before forwarding the request
+    Latency(Duration),
+    /// Drop the request and fail with a timeout
+    Timeout,
+    /// Drop the request and fail with an I/O error
+    IoError,
+    /// Fail with an exception from the device without forwarding the request
+    Exception(u8),
+    /// Forward the request and flip one random bit of the response data
+    BitFlip,
+}
+
+/// Random fault rule of a [`FaultyTransport`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportFaultRule {
+    /// Fault to inject
+    pub fault: TransportFault,
+    /// Probability of firing for a matching request, from 0 to 1
+    pub probability: f64,
+    /// Only requests with this function
+    pub function: Option<ModbusFunction>,
+}
+
+impl TransportFaultRule {
+    /// Inject `fault` with `probability` into every request
+    pub fn new(fault: TransportFault, probability: f64) -> Self {
+        Self { fault, probability, function: None }
+    }
+
+    /// Only match requests with `function`
+    pub fn for_function(mut self, function: ModbusFunction) -> Self {
+        self.function = Some(function);
+        self
+    }
+}
+
+/// Script of faults for consecutive requests
+///
+/// Each step covers one request: `None` passes it through untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FaultScenario {
+    steps: Vec<Option<TransportFault>>,
+    repeat: bool,
+}
+
+impl FaultScenario {
+    /// Empty script
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pass the next `count` requests through
+    pub fn pass(mut self, count: usize) -> Self {
+        self.steps.extend(std::iter::repeat_n(None, count));
+        self
+    }
+
+    /// Inject `fault` into the next request
+    pub fn inject(self, fault: TransportFault) -> Self {
+        self.inject_times(fault, 1)
+    }
+
+    /// Inject `fault` into each of the next `count` requests
+    pub fn inject_times(mut self, fault: TransportFault, count: usize) -> Self {
+        self.steps.extend(std::iter::repeat_n(Some(fault), count));
+        self
+    }
+
+    /// Start over once the script is done instead of falling back to the random rules
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Number of requests covered by one run of the script
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Check whether the script has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Record of one injected fault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Injection {
+    /// Index of the request through the transport, from 0
+    pub request: u64,
+    /// Slave of the request
+    pub slave_id: SlaveId,
+    /// Function of the request
+    pub function: ModbusFunction,
+    /// Start address of the request
+    pub address: u16,
+    /// Injected fault
+    pub fault: TransportFault,
+    /// Byte and bit of the response data flipped by a bit flip
+    pub flipped: Option<(usize, u8)>,
+}
+
+/// Transport decorator injecting faults into the requests of a client
+///
+/// Statistics and connection state are those of the wrapped transport.
+pub struct FaultyTransport<T: ModbusTransport> {
+    inner: T,
+    rng: StdRng,
+    rules: Vec<TransportFaultRule>,
+    scenario: FaultScenario,
+    script: VecDeque<Option<TransportFault>>,
+    timeout: Duration,
+    requests: u64,
+    injections: Vec<Injection>,
+}
+
+impl<T: ModbusTransport> FaultyTransport<T> {
+    /// Wrap `inner`, drawing random faults from `seed`
+    pub fn new(inner: T, seed: u64) -> Self {
+        Self {
+            inner,
+            rng: StdRng::seed_from_u64(seed),
+            rules: Vec::new(),
+            scenario: FaultScenario::default(),
+            script: VecDeque::new(),
+            timeout: Duration::ZERO,
+            requests: 0,
+            injections: Vec::new(),
+        }
+    }
+
+    /// Add a random fault rule; the first rule that fires wins
+    pub fn with_rule(mut self, rule: TransportFaultRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Follow `scenario` before applying the random rules
+    pub fn with_scenario(mut self, scenario: FaultScenario) -> Self {
+        self.script = scenario.steps.iter().cloned().collect();
+        self.scenario = scenario;
+        self
+    }
+
+    /// Time an injected timeout takes to fail (zero by default)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Faults injected so far, in order
+    pub fn injections(&self) -> &[Injection] {
+        &self.injections
+    }
+
+    /// Forget the recorded injections
+    pub fn clear_injections(&mut self) {
+        self.injections.clear();
+    }
+
+    /// Wrapped transport
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Fault for the next request, if any
+    fn next_fault(&mut self, request: &ModbusRequest) -> Option<TransportFault> {
+        if self.script.is_empty() && self.scenario.repeat {
+            self.script = self.scenario.steps.iter().cloned().collect();
+        }
+        if let Some(step) = self.script.pop_front() {
+            return step;
+        }
+
+        let rng = &mut self.rng;
+        self.rules
+            .iter()
+            .filter(|rule| rule.function.is_none_or(|function| function == request.function))
+            .find(|rule| rng.gen_bool(rule.probability.clamp(0.0, 1.0)))
+            .map(|rule| rule.fault.clone())
+    }
+}
+
+#[async_trait]
+impl<T: ModbusTransport> ModbusTransport for FaultyTransport<T> {
+    async fn request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+        let index = self.requests;
+        self.requests += 1;
+        let Some(fault) = self.next_fault(request) else {
+            return self.inner.request(request).await;
+        };
+
+        let mut flipped = None;
+        let result = match fault {
+            TransportFault::Latency(delay) => {
+                tokio::time::sleep(delay).await;
+                self.inner.request(request).await
+            }
+            TransportFault::Timeout => {
+                tokio::time::sleep(self.timeout).await;
+                Err(ModbusError::timeout("injected timeout", self.timeout.as_millis() as u64))
+            }
+            TransportFault::IoError => Err(ModbusError::io("Injected I/O error")),
+            TransportFault::Exception(code) => Err(ModbusError::exception(request.function.to_u8(), code)),
+            TransportFault::BitFlip => {
+                let mut result = self.inner.request(request).await;
+                if let Ok(response) = &mut result {
+                    if !response.data.is_empty() {
+                        let (byte, bit) = (self.rng.gen_range(0..response.data.len()), self.rng.gen_range(0..8u8));
+                        response.data[byte] ^= 1 << bit;
+                        flipped = Some((byte, bit));
+                    }
+                }
+                result
+            }
+        };
+
+        self.injections.push(Injection {
+            request: index,
+            slave_id: request.slave_id,
+            function: request.function,
+            address: request.address,
+            fault,
+            flipped,
+        });
+        result
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        self.inner.close().await
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        self.inner.get_stats()
+    }
+
+    fn set_enron_mode(&mut self, config: Option<EnronConfig>) {
+        self.inner.set_enron_mode(config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_and_plans() {
+        let config = FaultConfig::from_yaml_str(
+            r#"
+seed: 1
+rules:
+  - fault: { kind: exception, code: 6 }
+    function: 16
+  - fault: { kind: delay, ms: 50 }
+    addresses: { start: 100, end: 199 }
+  - fault: { kind: truncate }
+    probability: 0.5
+"#,
+        )
+        .unwrap();
+        let injector = FaultInjector::new(config).unwrap();
+
+        // Write 2 registers at 99: function and address rules both fire
+        let plan = injector.plan(&[0x10, 0x00, 0x63, 0x00, 0x02]);
+        assert_eq!((plan.exception, plan.delay), (Some(0x06), Duration::from_millis(50)));
+        // Read at 10: only the random rule can fire
+        let plan = injector.plan(&[0x03, 0x00, 0x0A, 0x00, 0x01]);
+        assert_eq!((plan.exception, plan.delay), (None, Duration::ZERO));
+
+        let truncated = (0..1000).filter(|_| injector.plan(&[0x04, 0x00, 0x00, 0x00, 0x01]).truncate).count();
+        assert!((400..600).contains(&truncated), "{}", truncated);
+
+        let mut reply = vec![0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x01, 0x83, 0x02];
+        FaultPlan { wrong_transaction_id: true, truncate: true, ..Default::default() }.apply_tcp(&mut reply);
+        assert_eq!(reply, vec![0x00, 0x02, 0x00, 0x00]);
+
+        assert!(FaultConfig::new().rule(FaultRule::new(ServerFault::DropReply).with_probability(1.5)).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_faulty_transport() {
+        use crate::client::{GenericModbusClient, ModbusClient};
+        use crate::retry::RetryPolicy;
+        use crate::test_utils::ScriptedTransport;
+
+        let device = ScriptedTransport::new();
+        for _ in 0..3 {
+            device.push_registers(ModbusFunction::ReadHoldingRegisters, &[0x00FF]);
+        }
+        let scenario = FaultScenario::new()
+            .inject(TransportFault::Timeout)
+            .inject(TransportFault::Exception(0x06))
+            .pass(1)
+            .inject(TransportFault::BitFlip);
+        let transport = FaultyTransport::new(device.clone(), 3).with_scenario(scenario);
+        let policy = RetryPolicy::new(3).with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+        let mut client = GenericModbusClient::new(transport).with_retry_policy(policy);
+
+        // Timeout and Busy are retried; only the third attempt reaches the device
+        assert_eq!(client.read_03(1, 10, 1).await.unwrap(), vec![0x00FF]);
+        assert_eq!(device.request_count(), 1);
+        let faults: Vec<_> = client.transport().injections().iter().map(|injection| (injection.request, injection.fault.clone())).collect();
+        assert_eq!(faults, vec![(0, TransportFault::Timeout), (1, TransportFault::Exception(0x06))]);
+
+        // The flip lands anywhere in the response data, byte count included
+        let request = ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 10, 1);
+        let response = client.transport_mut().request(&request).await.unwrap();
+        let (byte, bit) = client.transport().injections()[2].flipped.unwrap();
+        let mut expected = vec![0x02, 0x00, 0xFF];
+        expected[byte] ^= 1 << bit;
+        assert_eq!(response.data, expected);
+
+        // Seeded rules inject the same faults on every run
+        let draws = |seed| {
+            let rule = TransportFaultRule::new(TransportFault::IoError, 0.3).for_function(ModbusFunction::ReadHoldingRegisters);
+            let mut transport = FaultyTransport::new(ScriptedTransport::new(), seed).with_rule(rule);
+            async move {
+                for _ in 0..50 {
+                    let _ = transport.request(&ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 0, 1)).await;
+                }
+                transport.injections().iter().map(|injection| injection.request).collect::<Vec<_>>()
+            }
+        };
+        let first = draws(9).await;
+        assert!(!first.is_empty() && first.len() < 50);
+        assert_eq!(first, draws(9).await);
+    }
+}
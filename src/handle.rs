@@ -0,0 +1,410 @@
+//! # Shareable Client Handle
+//!
+//! All [`ModbusClient`] methods take `&mut self`, which makes sharing one bus
+//! between tokio tasks awkward. [`ModbusClientHandle`] solves this with an
+//! actor: a single I/O task owns the client (and with it the transport) and
+//! executes requests one at a time, while any number of cheap, cloneable
+//! handles submit requests to it.
+//!
+//! - **Priorities**: queued requests are served highest [`RequestPriority`]
+//!   first, in submission order within the same priority, so writes and
+//!   commands can jump ahead of background polling
+//! - **Cancellation**: a request whose caller was dropped (e.g. by a timeout
+//!   or `select!`) before it reached the bus is discarded without being sent
+//! - **Shutdown**: the I/O task closes the client once the last handle is dropped
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusClient, ModbusClientHandle, ModbusRtuClient, RequestPriority};
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let client = ModbusRtuClient::new("/dev/ttyUSB0", 9600)?;
+//! let handle = ModbusClientHandle::spawn(client);
+//!
+//! // Background polling at low priority
+//! let mut poller = handle.with_priority(RequestPriority::Low);
+//! tokio::spawn(async move {
+//!     loop {
+//!         let _ = poller.read_03(1, 0, 10).await;
+//!     }
+//! });
+//!
+//! // Commands overtake queued polls
+//! let mut commands = handle.with_priority(RequestPriority::High);
+//! commands.write_06(1, 100, 0x1234).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::client::ModbusClient;
+use crate::address::AddressingMode;
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::SlaveId;
+use crate::transport::TransportStats;
+
+/// Scheduling priority of a queued request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum RequestPriority {
+    /// Background polling
+    Low,
+    /// Regular requests
+    #[default]
+    Normal,
+    /// Writes and commands that should overtake queued polling
+    High,
+}
+
+/// Operation executed by the I/O task
+enum Operation {
+    ReadBits {
+        function: u8,
+        slave_id: SlaveId,
+        address: u16,
+        quantity: u16,
+        reply: oneshot::Sender<ModbusResult<Vec<bool>>>,
+    },
+    ReadRegisters {
+        function: u8,
+        slave_id: SlaveId,
+        address: u16,
+        quantity: u16,
+        reply: oneshot::Sender<ModbusResult<Vec<u16>>>,
+    },
+    WriteCoils {
+        slave_id: SlaveId,
+        address: u16,
+        values: Vec<bool>,
+        single: bool,
+        reply: oneshot::Sender<ModbusResult<()>>,
+    },
+    WriteRegisters {
+        slave_id: SlaveId,
+        address: u16,
+        values: Vec<u16>,
+        single: bool,
+        reply: oneshot::Sender<ModbusResult<()>>,
+    },
+    Close {
+        reply: oneshot::Sender<ModbusResult<()>>,
+    },
+}
+
+impl Operation {
+    /// Whether the caller stopped waiting for the result
+    fn is_cancelled(&self) -> bool {
+        match self {
+            Self::ReadBits { reply, .. } => reply.is_closed(),
+            Self::ReadRegisters { reply, .. } => reply.is_closed(),
+            Self::WriteCoils { reply, .. } => reply.is_closed(),
+            Self::WriteRegisters { reply, .. } => reply.is_closed(),
+            Self::Close { reply } => reply.is_closed(),
+        }
+    }
+
+    /// Run the operation against the client and deliver the result
+    async fn execute<C: ModbusClient>(self, client: &mut C) {
+        match self {
+            Self::ReadBits { function, slave_id, address, quantity, reply } => {
+                let result = if function == 0x01 {
+                    client.read_01(slave_id, address, quantity).await
+                } else {
+                    client.read_02(slave_id, address, quantity).await
+                };
+                let _ = reply.send(result);
+            }
+            Self::ReadRegisters { function, slave_id, address, quantity, reply } => {
+                let result = if function == 0x03 {
+                    client.read_03(slave_id, address, quantity).await
+                } else {
+                    client.read_04(slave_id, address, quantity).await
+                };
+                let _ = reply.send(result);
+            }
+            Self::WriteCoils { slave_id, address, values, single, reply } => {
+                let result = if single {
+                    client.write_05(slave_id, address, values[0]).await
+                } else {
+                    client.write_0f(slave_id, address, &values).await
+                };
+                let _ = reply.send(result);
+            }
+            Self::WriteRegisters { slave_id, address, values, single, reply } => {
+                let result = if single {
+                    client.write_06(slave_id, address, values[0]).await
+                } else {
+                    client.write_10(slave_id, address, &values).await
+                };
+                let _ = reply.send(result);
+            }
+            Self::Close { reply } => {
+                let _ = reply.send(client.close().await);
+            }
+        }
+    }
+}
+
+/// Queued operation ordered by priority, then submission order
+struct Queued {
+    priority: RequestPriority,
+    sequence: u64,
+    operation: Operation,
+}
+
+impl PartialEq for Queued {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Queued {}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap: higher priority first, then lower sequence first
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// State shared between the handles and the I/O task
+#[derive(Default)]
+struct Shared {
+    connected: AtomicBool,
+    cancelled: AtomicU64,
+    stats: Mutex<TransportStats>,
+    addressing: AddressingMode,
+}
+
+/// Cloneable handle to a client owned by a dedicated I/O task
+///
+/// Implements [`ModbusClient`], so it can be used wherever a client is expected.
+/// Each clone carries its own priority, see [`with_priority`](Self::with_priority).
+#[derive(Clone)]
+pub struct ModbusClientHandle {
+    sender: mpsc::UnboundedSender<(RequestPriority, Operation)>,
+    shared: Arc<Shared>,
+    priority: RequestPriority,
+}
+
+impl ModbusClientHandle {
+    /// Move the client into a new I/O task and return a handle to it
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn spawn<C: ModbusClient + 'static>(client: C) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let shared = Arc::new(Shared {
+            connected: AtomicBool::new(client.is_connected()),
+            cancelled: AtomicU64::new(0),
+            stats: Mutex::new(client.get_stats()),
+            addressing: client.addressing_mode(),
+        });
+
+        tokio::spawn(Self::run(client, receiver, shared.clone()));
+
+        Self {
+            sender,
+            shared,
+            priority: RequestPriority::default(),
+        }
+    }
+
+    /// Clone the handle with a different priority for its requests
+    pub fn with_priority(&self, priority: RequestPriority) -> Self {
+        Self {
+            priority,
+            ..self.clone()
+        }
+    }
+
+    /// Priority used for requests from this handle
+    pub fn priority(&self) -> RequestPriority {
+        self.priority
+    }
+
+    /// Number of requests discarded because their caller was dropped
+    pub fn cancelled_requests(&self) -> u64 {
+        self.shared.cancelled.load(AtomicOrdering::Relaxed)
+    }
+
+    /// I/O task: serve queued operations until every handle is dropped
+    async fn run<C: ModbusClient>(
+        mut client: C,
+        mut receiver: mpsc::UnboundedReceiver<(RequestPriority, Operation)>,
+        shared: Arc<Shared>,
+    ) {
+        let mut queue = BinaryHeap::new();
+        let mut sequence = 0u64;
+
+        loop {
+            // Wait for work when idle, then take everything already submitted
+            if queue.is_empty() {
+                match receiver.recv().await {
+                    Some((priority, operation)) => {
+                        queue.push(Queued { priority, sequence, operation });
+                        sequence += 1;
+                    }
+                    None => break,
+                }
+            }
+            while let Ok((priority, operation)) = receiver.try_recv() {
+                queue.push(Queued { priority, sequence, operation });
+                sequence += 1;
+            }
+
+            let Some(next) = queue.pop() else { continue };
+            if next.operation.is_cancelled() {
+                shared.cancelled.fetch_add(1, AtomicOrdering::Relaxed);
+                continue;
+            }
+
+            next.operation.execute(&mut client).await;
+            shared.connected.store(client.is_connected(), AtomicOrdering::Relaxed);
+            *shared.stats.lock().unwrap() = client.get_stats();
+        }
+
+        let _ = client.close().await;
+        shared.connected.store(false, AtomicOrdering::Relaxed);
+    }
+
+    /// Submit an operation and wait for its result
+    async fn submit<R>(
+        &self,
+        operation: impl FnOnce(oneshot::Sender<ModbusResult<R>>) -> Operation,
+    ) -> ModbusResult<R> {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send((self.priority, operation(reply)))
+            .map_err(|_| ModbusError::internal("Client I/O task has stopped"))?;
+        result
+            .await
+            .map_err(|_| ModbusError::internal("Client I/O task dropped the request"))?
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusClient for ModbusClientHandle {
+    async fn read_01(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.submit(|reply| Operation::ReadBits { function: 0x01, slave_id, address, quantity, reply }).await
+    }
+
+    async fn read_02(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.submit(|reply| Operation::ReadBits { function: 0x02, slave_id, address, quantity, reply }).await
+    }
+
+    async fn read_03(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.submit(|reply| Operation::ReadRegisters { function: 0x03, slave_id, address, quantity, reply }).await
+    }
+
+    async fn read_04(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.submit(|reply| Operation::ReadRegisters { function: 0x04, slave_id, address, quantity, reply }).await
+    }
+
+    async fn write_05(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
+        self.submit(|reply| Operation::WriteCoils { slave_id, address, values: vec![value], single: true, reply }).await
+    }
+
+    async fn write_06(&mut self, slave_id: SlaveId, address: u16, value: u16) -> ModbusResult<()> {
+        self.submit(|reply| Operation::WriteRegisters { slave_id, address, values: vec![value], single: true, reply }).await
+    }
+
+    async fn write_0f(&mut self, slave_id: SlaveId, address: u16, values: &[bool]) -> ModbusResult<()> {
+        let values = values.to_vec();
+        self.submit(|reply| Operation::WriteCoils { slave_id, address, values, single: false, reply }).await
+    }
+
+    async fn write_10(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
+        let values = values.to_vec();
+        self.submit(|reply| Operation::WriteRegisters { slave_id, address, values, single: false, reply }).await
+    }
+
+    fn addressing_mode(&self) -> AddressingMode {
+        self.shared.addressing
+    }
+
+    fn is_connected(&self) -> bool {
+        self.shared.connected.load(AtomicOrdering::Relaxed)
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        self.submit(|reply| Operation::Close { reply }).await
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        self.shared.stats.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use crate::client::GenericModbusClient;
+    use crate::protocol::ModbusFunction;
+    use crate::test_utils::ScriptedTransport;
+
+    #[tokio::test]
+    async fn test_handle_shared_between_tasks() {
+        let transport = ScriptedTransport::new();
+        for i in 0..4 {
+            transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[i]);
+        }
+        let handle = ModbusClientHandle::spawn(GenericModbusClient::new(transport.clone()));
+
+        let tasks: Vec<_> = (0..4)
+            .map(|_| {
+                let mut handle = handle.clone();
+                tokio::spawn(async move { handle.read_03(1, 0, 1).await.unwrap()[0] })
+            })
+            .collect();
+
+        let mut values = Vec::new();
+        for task in tasks {
+            values.push(task.await.unwrap());
+        }
+        values.sort_unstable();
+        assert_eq!(values, vec![0, 1, 2, 3]);
+        assert_eq!(handle.get_stats().requests_sent, 4);
+    }
+
+    #[test]
+    fn test_queue_order() {
+        let op = || Operation::Close { reply: oneshot::channel().0 };
+        let mut queue = BinaryHeap::new();
+        queue.push(Queued { priority: RequestPriority::Low, sequence: 0, operation: op() });
+        queue.push(Queued { priority: RequestPriority::Normal, sequence: 1, operation: op() });
+        queue.push(Queued { priority: RequestPriority::High, sequence: 2, operation: op() });
+        queue.push(Queued { priority: RequestPriority::Normal, sequence: 3, operation: op() });
+
+        let order: Vec<u64> = std::iter::from_fn(|| queue.pop().map(|queued| queued.sequence)).collect();
+        assert_eq!(order, vec![2, 1, 3, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_requests_are_skipped() {
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[7]);
+        let mut handle = ModbusClientHandle::spawn(GenericModbusClient::new(transport.clone()));
+
+        // Submit a request, then drop it before the I/O task gets to run
+        let mut dropped = handle.clone();
+        assert!(dropped.read_03(1, 0, 1).now_or_never().is_none());
+
+        assert_eq!(handle.read_03(1, 0, 1).await.unwrap(), vec![7]);
+        assert_eq!(transport.request_count(), 1);
+        assert_eq!(handle.cancelled_requests(), 1);
+    }
+}
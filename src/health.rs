@@ -0,0 +1,404 @@
+//! # Per-Slave Health Tracking
+//!
+//! On a shared RS-485 bus every request to a dead device costs a full timeout,
+//! stalling polling for all other devices on the line. This module tracks the
+//! health of each slave and short-circuits requests to slaves that are known
+//! to be offline.
+//!
+//! Each slave has a circuit breaker with three states:
+//!
+//! - **Closed**: requests pass through; consecutive failures are counted
+//! - **Open**: the slave is considered offline and requests fail immediately
+//!   with [`ModbusError::DeviceNotResponding`]
+//! - **Half-open**: the open period has elapsed and a single probe request is
+//!   let through; success closes the breaker, failure opens it again
+//!
+//! Transport failures (timeouts, I/O and connection errors) and replies that
+//! cannot be used (CRC mismatches, malformed frames, replies from another
+//! slave) count against a slave. Only a valid reply, including an exception
+//! response, proves that the device is alive. Errors raised before a request
+//! is sent, such as an invalid quantity, are not recorded.
+//!
+//! Nothing probes offline slaves on its own: the application calls
+//! `probe_offline_slaves` on the client periodically, e.g. from its poll loop
+//! or a `tokio::time::interval`.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusRtuClient, ModbusClient, HealthConfig, HealthEvent};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let mut client = ModbusRtuClient::new("/dev/ttyUSB0", 9600)?;
+//! client.enable_health_tracking(HealthConfig {
+//!     failure_threshold: 3,
+//!     open_duration: Duration::from_secs(30),
+//!     ..Default::default()
+//! });
+//!
+//! let mut events = client.subscribe_health().unwrap();
+//! tokio::spawn(async move {
+//!     while let Ok(event) = events.recv().await {
+//!         match event {
+//!             HealthEvent::Offline { slave_id } => println!("Slave {} offline", slave_id),
+//!             HealthEvent::Online { slave_id } => println!("Slave {} back online", slave_id),
+//!         }
+//!     }
+//! });
+//!
+//! // Requests to slaves that are offline fail fast instead of timing out
+//! let _ = client.read_03(7, 0, 10).await;
+//!
+//! // Probe offline slaves whose open period has elapsed
+//! client.probe_offline_slaves().await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::Instant;
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusFunction, ModbusRequest, SlaveId};
+
+/// Capacity of the health event channel
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Circuit breaker state of a slave
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests pass through
+    Closed,
+    /// Slave is offline; requests fail fast
+    Open,
+    /// Open period elapsed; the next request is a probe
+    HalfOpen,
+}
+
+/// Slave availability change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthEvent {
+    /// The slave answered again after being offline
+    Online { slave_id: SlaveId },
+    /// The slave reached the failure threshold and is considered offline
+    Offline { slave_id: SlaveId },
+}
+
+/// Health tracking configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthConfig {
+    /// Consecutive failures before a slave is considered offline
+    pub failure_threshold: u32,
+    /// How long requests fail fast before a probe is allowed
+    pub open_duration: Duration,
+    /// Function used by [`HealthTracker::probe_request`]
+    pub probe_function: ModbusFunction,
+    /// Start address used by [`HealthTracker::probe_request`]
+    pub probe_address: u16,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            open_duration: Duration::from_secs(10),
+            probe_function: ModbusFunction::ReadHoldingRegisters,
+            probe_address: 0,
+        }
+    }
+}
+
+/// Health record of a single slave
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlaveHealth {
+    /// Circuit breaker state
+    pub state: CircuitState,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Total failures observed
+    pub total_failures: u64,
+    /// Total successful requests
+    pub total_successes: u64,
+    /// When the breaker last opened
+    pub opened_at: Option<Instant>,
+    /// When the slave last answered
+    pub last_success: Option<Instant>,
+}
+
+impl Default for SlaveHealth {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            total_failures: 0,
+            total_successes: 0,
+            opened_at: None,
+            last_success: None,
+        }
+    }
+}
+
+/// Per-slave circuit breakers
+#[derive(Debug)]
+pub struct HealthTracker {
+    config: HealthConfig,
+    slaves: HashMap<SlaveId, SlaveHealth>,
+    events: broadcast::Sender<HealthEvent>,
+}
+
+impl HealthTracker {
+    /// Create a tracker with the given configuration
+    pub fn new(config: HealthConfig) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            config,
+            slaves: HashMap::new(),
+            events,
+        }
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &HealthConfig {
+        &self.config
+    }
+
+    /// Subscribe to online/offline events
+    pub fn subscribe(&self) -> broadcast::Receiver<HealthEvent> {
+        self.events.subscribe()
+    }
+
+    /// Health record of a slave, if it has been contacted
+    pub fn slave(&self, slave_id: SlaveId) -> Option<&SlaveHealth> {
+        self.slaves.get(&slave_id)
+    }
+
+    /// Circuit state of a slave (`Closed` for slaves never contacted)
+    pub fn state(&self, slave_id: SlaveId) -> CircuitState {
+        self.slaves
+            .get(&slave_id)
+            .map(|health| health.state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// Slaves whose breaker is not closed
+    pub fn offline_slaves(&self) -> Vec<SlaveId> {
+        let mut offline: Vec<SlaveId> = self.slaves
+            .iter()
+            .filter(|(_, health)| health.state != CircuitState::Closed)
+            .map(|(slave_id, _)| *slave_id)
+            .collect();
+        offline.sort_unstable();
+        offline
+    }
+
+    /// Offline slaves whose open period has elapsed and that should be probed
+    pub fn due_probes(&self) -> Vec<SlaveId> {
+        let mut due: Vec<SlaveId> = self.slaves
+            .iter()
+            .filter(|(_, health)| self.probe_due(health))
+            .map(|(slave_id, _)| *slave_id)
+            .collect();
+        due.sort_unstable();
+        due
+    }
+
+    /// Build the probe request for a slave
+    pub fn probe_request(&self, slave_id: SlaveId) -> ModbusRequest {
+        ModbusRequest::new_read(slave_id, self.config.probe_function, self.config.probe_address, 1)
+    }
+
+    /// Check whether a request to the slave may be sent
+    ///
+    /// Moves an open breaker to half-open once the open period has elapsed.
+    ///
+    /// # Returns
+    ///
+    /// `Err(ModbusError::DeviceNotResponding)` while the breaker is open
+    pub fn check(&mut self, slave_id: SlaveId) -> ModbusResult<()> {
+        let open_duration = self.config.open_duration;
+        let Some(health) = self.slaves.get_mut(&slave_id) else { return Ok(()) };
+
+        if health.state == CircuitState::Open {
+            let elapsed = health.opened_at.map(|at| at.elapsed() >= open_duration).unwrap_or(true);
+            if !elapsed {
+                return Err(ModbusError::device_not_responding(slave_id));
+            }
+            health.state = CircuitState::HalfOpen;
+        }
+
+        Ok(())
+    }
+
+    /// Record the outcome of a request
+    ///
+    /// Responses, including exception responses, prove the slave is
+    /// reachable; transport failures and unusable replies count against it.
+    /// Other errors were raised before anything was sent and are ignored.
+    pub fn record(&mut self, slave_id: SlaveId, result: &ModbusResult<impl Sized>) {
+        match result {
+            Ok(_) | Err(ModbusError::Exception { .. }) => self.record_success(slave_id),
+            Err(error) if Self::is_failure(error) => self.record_failure(slave_id),
+            Err(_) => {}
+        }
+    }
+
+    /// Record a successful exchange with the slave
+    pub fn record_success(&mut self, slave_id: SlaveId) {
+        let health = self.slaves.entry(slave_id).or_default();
+        let was_offline = health.state != CircuitState::Closed;
+
+        health.state = CircuitState::Closed;
+        health.consecutive_failures = 0;
+        health.total_successes += 1;
+        health.opened_at = None;
+        health.last_success = Some(Instant::now());
+
+        if was_offline {
+            let _ = self.events.send(HealthEvent::Online { slave_id });
+        }
+    }
+
+    /// Record a failed exchange with the slave
+    pub fn record_failure(&mut self, slave_id: SlaveId) {
+        let threshold = self.config.failure_threshold.max(1);
+        let health = self.slaves.entry(slave_id).or_default();
+
+        health.consecutive_failures += 1;
+        health.total_failures += 1;
+
+        match health.state {
+            CircuitState::Closed if health.consecutive_failures >= threshold => {
+                health.state = CircuitState::Open;
+                health.opened_at = Some(Instant::now());
+                let _ = self.events.send(HealthEvent::Offline { slave_id });
+            }
+            CircuitState::HalfOpen | CircuitState::Open => {
+                // Failed probe: stay offline for another period
+                health.state = CircuitState::Open;
+                health.opened_at = Some(Instant::now());
+            }
+            CircuitState::Closed => {}
+        }
+    }
+
+    /// Forget all health records
+    pub fn reset(&mut self) {
+        self.slaves.clear();
+    }
+
+    /// Errors that indicate the slave did not give a valid reply
+    ///
+    /// Protocol errors come from checking the reply, e.g. a slave ID mismatch.
+    fn is_failure(error: &ModbusError) -> bool {
+        #[allow(deprecated)]
+        let unusable_reply = matches!(
+            error,
+            ModbusError::CrcMismatch { .. }
+                | ModbusError::Frame { .. }
+                | ModbusError::InvalidFrame
+                | ModbusError::Protocol { .. }
+                | ModbusError::TimeoutLegacy
+                | ModbusError::DeviceNotResponding { .. }
+        );
+        error.is_transport_error() || unusable_reply
+    }
+
+    fn probe_due(&self, health: &SlaveHealth) -> bool {
+        match health.state {
+            CircuitState::Closed => false,
+            CircuitState::HalfOpen => true,
+            CircuitState::Open => health
+                .opened_at
+                .map(|at| at.elapsed() >= self.config.open_duration)
+                .unwrap_or(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> HealthConfig {
+        HealthConfig {
+            failure_threshold: 2,
+            open_duration: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_breaker_transitions() {
+        let mut tracker = HealthTracker::new(config());
+        let mut events = tracker.subscribe();
+
+        tracker.record(5, &Err::<(), _>(ModbusError::timeout("read", 100)));
+        assert_eq!(tracker.state(5), CircuitState::Closed);
+        tracker.record(5, &Err::<(), _>(ModbusError::timeout("read", 100)));
+        assert_eq!(tracker.state(5), CircuitState::Open);
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Offline { slave_id: 5 });
+
+        // Fails fast while open, other slaves unaffected
+        assert!(matches!(tracker.check(5), Err(ModbusError::DeviceNotResponding { slave_id: 5 })));
+        assert!(tracker.check(6).is_ok());
+        assert!(tracker.due_probes().is_empty());
+
+        // Failed probe re-opens the breaker
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        assert_eq!(tracker.due_probes(), vec![5]);
+        assert!(tracker.check(5).is_ok());
+        assert_eq!(tracker.state(5), CircuitState::HalfOpen);
+        tracker.record_failure(5);
+        assert_eq!(tracker.state(5), CircuitState::Open);
+
+        // Successful probe closes it
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        assert!(tracker.check(5).is_ok());
+        tracker.record_success(5);
+        assert_eq!(tracker.state(5), CircuitState::Closed);
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Online { slave_id: 5 });
+        assert!(tracker.offline_slaves().is_empty());
+    }
+
+    #[test]
+    fn test_exceptions_count_as_alive() {
+        let mut tracker = HealthTracker::new(config());
+
+        tracker.record_failure(1);
+        tracker.record(1, &Err::<(), _>(ModbusError::exception(0x03, 0x02)));
+        tracker.record_failure(1);
+
+        assert_eq!(tracker.state(1), CircuitState::Closed);
+        assert_eq!(tracker.slave(1).unwrap().consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unusable_replies_and_local_errors() {
+        let mut tracker = HealthTracker::new(config());
+        let mut events = tracker.subscribe();
+
+        // Line noise and another slave's reply do not prove the slave is alive
+        tracker.record(2, &Err::<(), _>(ModbusError::frame("RTU frame too short")));
+        tracker.record(2, &Err::<(), _>(ModbusError::protocol("Response slave ID mismatch: expected 2, got 9")));
+        assert_eq!(tracker.state(2), CircuitState::Open);
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Offline { slave_id: 2 });
+
+        // A local validation error leaves a half-open breaker waiting for a real reply
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        assert!(tracker.check(2).is_ok());
+        tracker.record(2, &Err::<(), _>(ModbusError::invalid_data("Invalid quantity")));
+        assert_eq!(tracker.state(2), CircuitState::HalfOpen);
+        assert!(events.try_recv().is_err());
+
+        tracker.record(2, &Ok(()));
+        assert_eq!(tracker.state(2), CircuitState::Closed);
+        assert_eq!(events.try_recv().unwrap(), HealthEvent::Online { slave_id: 2 });
+
+        // Unrecorded errors do not even create an entry
+        tracker.record(3, &Err::<(), _>(ModbusError::invalid_data("Invalid quantity")));
+        assert!(tracker.slave(3).is_none());
+    }
+}
@@ -11,7 +11,7 @@
 //! 
 //! - **🚀 High Performance**: Async/await support with Tokio for maximum throughput
 //! - **🔧 Complete Protocol Support**: Modbus TCP, RTU, and ASCII protocols
-//! - **🛡️ Memory Safe**: Pure Rust implementation with zero unsafe code
+//! - **🛡️ Memory Safe**: Pure Rust implementation with zero unsafe code outside the optional `mmap` store
 //! - **⚡ Zero-Copy Operations**: Optimized for minimal memory allocations
 //! - **🔄 Concurrent Processing**: Multi-client server support
 //! - **📊 Built-in Monitoring**: Comprehensive statistics and metrics
@@ -103,6 +103,10 @@
 //! └─────────────────┘    └─────────────────┘
 //! ```
 
+// Lets `#[derive(ModbusRegisters)]` refer to `::voltage_modbus` inside this crate too
+#[cfg(feature = "derive")]
+extern crate self as voltage_modbus;
+
 /// Core error types and result handling
 /// 
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
@@ -113,6 +117,11 @@ pub mod error;
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
 pub mod protocol;
 
+/// Modicon and IEC address notation and addressing modes
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod address;
+
 /// Network transport layer for TCP and RTU communication
 /// 
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
@@ -133,6 +142,112 @@ pub mod server;
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
 pub mod register_bank;
 
+/// Register bank snapshots in JSON, YAML and binary formats
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod snapshot;
+
+/// Register bank autosave and write-ahead journal
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod persistence;
+
+/// Value generators and computed registers for simulated devices
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod simulation;
+
+/// Fault injection for misbehaving servers and client transports
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod fault_injection;
+
+/// In-memory transports and expectation-based mocks for testing
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod mock;
+
+/// Communication watchdog applying failsafe values for servers
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod watchdog;
+
+/// Pluggable data stores served by the Modbus servers
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod store;
+
+/// Memory-mapped data store shared with other local processes
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+#[cfg(feature = "mmap")]
+pub mod mmap;
+
+/// Sparse and dense storage backends for register banks
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod storage;
+
+/// Valid address ranges, access modes and value constraints for register banks
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod address_map;
+
+/// Change subscriptions, callbacks and write vetoes for register banks
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod observer;
+
+/// Retry policies with exponential backoff for client requests
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod retry;
+
+/// Per-slave health tracking and circuit breakers
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod health;
+
+/// Cloneable client handle backed by a dedicated I/O task
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod handle;
+
+/// Pooled Modbus TCP client for high-throughput polling
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod pool;
+
+/// Read request planning and coalescing
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod planner;
+
+/// Periodic polling engine with change notifications
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod poller;
+
+/// Declarative point maps loaded from YAML or JSON
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod tags;
+
+/// Typed value codec with configurable word and byte order
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod codec;
+
+/// SunSpec model discovery, decoding and device emulation
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod sunspec;
+
+/// Enron/Daniel 32-bit register extension
+/// 
+/// Author: Evan Liu <evan.liu@voltageenergy.com>
+pub mod enron;
+
 /// Utility functions and performance monitoring
 /// 
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
@@ -143,13 +258,39 @@ pub mod utils;
 /// Author: Evan Liu <evan.liu@voltageenergy.com>
 pub mod logging;
 
+#[cfg(test)]
+mod test_utils;
+
 // Re-export main types for convenience
 pub use error::{ModbusError, ModbusResult};
-pub use protocol::{ModbusRequest, ModbusResponse, ModbusFunction};
-pub use transport::{ModbusTransport, TcpTransport, RtuTransport, AsciiTransport, TransportStats};
-pub use client::{ModbusClient, ModbusTcpClient, ModbusRtuClient};
+pub use protocol::{ModbusRequest, ModbusResponse, ModbusFunction, RegisterTable};
+pub use address::{DataAddress, AddressNotation, AddressingMode};
+pub use transport::{ModbusTransport, TcpTransport, RtuTransport, AsciiTransport, TransportStats, ConnectionState, ReconnectConfig, FailoverTransport, FailoverConfig};
+pub use client::{ModbusClient, ModbusTcpClient, ModbusRtuClient, ModbusFailoverClient, ChunkLimits};
+pub use retry::{RetryPolicy, RetryOn};
+pub use handle::{ModbusClientHandle, RequestPriority};
+pub use pool::{ModbusTcpPool, PoolConfig, PoolPolicy, PooledConnection, HealthProbe};
+pub use planner::{ReadPlanner, PlannerConfig, ReadPoint, ReadPlan, ReadBlock, ExcludedRange, PointValue};
+pub use poller::{Poller, PollerHandle, PollerConfig, PollGroup, PollPoint, PollSample, Quality};
+pub use tags::{PointMap, PointDef, DataType, Access, TagValue};
+pub use address_map::{AddressMap, AddressBlock, ValueConstraint};
+pub use storage::StorageBackend;
+pub use store::DataStore;
+pub use snapshot::{BankSnapshot, SnapshotFormat};
+pub use persistence::{Persistence, PersistenceConfig};
+pub use simulation::{Simulation, SimulationConfig, SimulatedPoint, Simulator, Generator};
+pub use codec::{RegisterOrder, RegisterValue, ModbusRegisters, WordOrder, ByteOrder};
+#[cfg(feature = "derive")]
+pub use voltage_modbus_derive::ModbusRegisters;
+pub use sunspec::{SunSpecDevice, SunSpecEmulator, SunSpecModel, SunSpecValue, ModelInfo};
+pub use enron::{EnronConfig, EnronEvent, ArchiveRecord};
+pub use health::{HealthTracker, HealthConfig, HealthEvent, CircuitState, SlaveHealth};
+pub use fault_injection::{FaultConfig, FaultRule, FaultInjector, FaultPlan, ServerFault, FaultyTransport, FaultScenario, TransportFault, TransportFaultRule, Injection};
+pub use mock::{Framing, MemoryTransport, MemoryServer, MockTransport, Expect};
+pub use watchdog::{Watchdog, WatchdogConfig, WatchdogEvent};
 pub use server::{ModbusServer, ModbusTcpServer, ModbusTcpServerConfig, ServerStats};
 pub use register_bank::{ModbusRegisterBank, RegisterBankStats};
+pub use observer::{ChangeEvent, ChangeSubscription, ObserverId, WriteContext};
 pub use utils::{PerformanceMetrics, OperationTimer};
 pub use logging::{LogLevel, LogCallback, CallbackLogger, LoggingMode};
 
@@ -0,0 +1,385 @@
+//! # Memory-Mapped Data Store
+//!
+//! [`MmapStore`] keeps the four Modbus tables in a file mapped into memory,
+//! so a server can expose a process image that another local process (a PLC
+//! runtime, a simulator, a logger) reads and writes directly.
+//!
+//! ## Image Layout
+//!
+//! | Offset | Size | Content |
+//! |--------|------|---------|
+//! | 0 | 8 | magic `VMBIMG01` |
+//! | 8 | 8 | sequence counter (u64) |
+//! | 16 | 8192 | coils, bit `n` of 64-bit word `n / 64` |
+//! | 8208 | 8192 | discrete inputs, same packing |
+//! | 16400 | 131072 | holding registers, 65536 × u16 |
+//! | 147472 | 131072 | input registers, 65536 × u16 |
+//!
+//! Integers use the native byte order of the host. Every access must be
+//! atomic. Writers make the sequence counter odd, update the values, then
+//! make it even again; readers retry when the counter was odd or changed, so
+//! they never see a multi-register write half-applied. Writers in one process
+//! are serialized; writers in different processes must coordinate themselves.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use voltage_modbus::{DataStore, ModbusTcpServer, ModbusTcpServerConfig};
+//! use voltage_modbus::mmap::MmapStore;
+//!
+//! # fn example() -> voltage_modbus::ModbusResult<()> {
+//! let store = Arc::new(MmapStore::open("/dev/shm/inverter.img")?);
+//! store.write_input_registers(0, &[2300, 50])?;
+//! let server = ModbusTcpServer::with_store(ModbusTcpServerConfig::default(), store);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::OpenOptions;
+use std::hint;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{fence, AtomicU16, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+use memmap2::MmapMut;
+
+use crate::address::DataAddress;
+use crate::error::{ModbusError, ModbusResult};
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::store::DataStore;
+
+/// Magic bytes at the start of an image
+const MAGIC: &[u8; 8] = b"VMBIMG01";
+
+/// Number of addresses in a Modbus table
+const TABLE_SIZE: usize = 0x10000;
+
+const SEQUENCE_OFFSET: usize = 8;
+const COILS_OFFSET: usize = 16;
+const BIT_TABLE_BYTES: usize = TABLE_SIZE / 8;
+const DISCRETE_INPUTS_OFFSET: usize = COILS_OFFSET + BIT_TABLE_BYTES;
+const HOLDING_REGISTERS_OFFSET: usize = DISCRETE_INPUTS_OFFSET + BIT_TABLE_BYTES;
+const WORD_TABLE_BYTES: usize = TABLE_SIZE * 2;
+const INPUT_REGISTERS_OFFSET: usize = HOLDING_REGISTERS_OFFSET + WORD_TABLE_BYTES;
+
+/// Size of an image file in bytes
+pub const MMAP_IMAGE_SIZE: usize = INPUT_REGISTERS_OFFSET + WORD_TABLE_BYTES;
+
+/// Data store in a memory-mapped image file
+#[derive(Debug)]
+pub struct MmapStore {
+    map: MmapMut,
+    path: PathBuf,
+    writer: Mutex<()>,
+}
+
+impl MmapStore {
+    /// Open an image file, creating a zeroed one if it does not exist
+    ///
+    /// A new image is initialised under a temporary name and linked into
+    /// place, so other processes opening the same path never see a partly
+    /// written header.
+    ///
+    /// # Returns
+    ///
+    /// The store, or a configuration error if the file is not an image
+    pub fn open<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let io_error = |e: std::io::Error| ModbusError::configuration(format!("Cannot open {}: {}", path.display(), e));
+        let file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Self::create(path).map_err(io_error)?;
+                OpenOptions::new().read(true).write(true).open(path).map_err(io_error)?
+            }
+            Err(e) => return Err(io_error(e)),
+        };
+
+        let len = file.metadata().map_err(io_error)?.len();
+        if len != MMAP_IMAGE_SIZE as u64 {
+            return Err(ModbusError::configuration(format!("{} is not a register image ({} bytes)", path.display(), len)));
+        }
+
+        // SAFETY: other processes may change the file while it is mapped; the
+        // store only reads and writes the tables through atomics.
+        let map = unsafe { MmapMut::map_mut(&file) }.map_err(io_error)?;
+        if &map[..MAGIC.len()] != MAGIC {
+            return Err(ModbusError::configuration(format!("{} is not a register image", path.display())));
+        }
+
+        Ok(Self { map, path: path.to_path_buf(), writer: Mutex::new(()) })
+    }
+
+    /// Create a zeroed image at `path` unless another process got there first
+    fn create(path: &Path) -> std::io::Result<()> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(
+            ".{}.{}.{}.tmp",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let result = (|| {
+            let mut file = OpenOptions::new().write(true).create_new(true).open(&temp)?;
+            file.set_len(MMAP_IMAGE_SIZE as u64)?;
+            file.write_all(MAGIC)?;
+            file.sync_all()?;
+            // Unlike a rename, a link never replaces an image created concurrently
+            match std::fs::hard_link(&temp, path) {
+                Err(e) if e.kind() != ErrorKind::AlreadyExists => Err(e),
+                _ => Ok(()),
+            }
+        })();
+        let _ = std::fs::remove_file(&temp);
+        result
+    }
+
+    /// Path of the image file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the image back to the file
+    pub fn flush(&self) -> ModbusResult<()> {
+        self.map.flush().map_err(|e| ModbusError::io(format!("Cannot flush {}: {}", self.path.display(), e)))
+    }
+
+    fn sequence(&self) -> &AtomicU64 {
+        // SAFETY: the offset is 8-aligned in a page-aligned mapping that lives
+        // as long as `self`, and the counter is only accessed atomically.
+        unsafe { &*(self.map.as_ptr().add(SEQUENCE_OFFSET) as *const AtomicU64) }
+    }
+
+    fn bits(&self, table: RegisterTable) -> &[AtomicU64] {
+        let offset = match table {
+            RegisterTable::DiscreteInput => DISCRETE_INPUTS_OFFSET,
+            _ => COILS_OFFSET,
+        };
+        // SAFETY: as for `sequence`; the table lies inside the mapping.
+        unsafe { std::slice::from_raw_parts(self.map.as_ptr().add(offset) as *const AtomicU64, TABLE_SIZE / 64) }
+    }
+
+    fn words(&self, table: RegisterTable) -> &[AtomicU16] {
+        let offset = match table {
+            RegisterTable::InputRegister => INPUT_REGISTERS_OFFSET,
+            _ => HOLDING_REGISTERS_OFFSET,
+        };
+        // SAFETY: as for `sequence`; the offset is 2-aligned and the table lies
+        // inside the mapping.
+        unsafe { std::slice::from_raw_parts(self.map.as_ptr().add(offset) as *const AtomicU16, TABLE_SIZE) }
+    }
+
+    /// Run `read` until it sees no concurrent write
+    fn read<T>(&self, read: impl Fn() -> T) -> T {
+        let sequence = self.sequence();
+        loop {
+            let before = sequence.load(Ordering::Acquire);
+            if before & 1 == 0 {
+                let value = read();
+                fence(Ordering::Acquire);
+                if sequence.load(Ordering::Relaxed) == before {
+                    return value;
+                }
+            }
+            hint::spin_loop();
+        }
+    }
+
+    fn lock(&self) -> ModbusResult<MutexGuard<'_, ()>> {
+        self.writer.lock().map_err(|_| ModbusError::internal("Failed to lock register image"))
+    }
+
+    /// Apply `write` as one step for readers; the caller holds the writer lock
+    fn publish(&self, write: impl FnOnce()) {
+        let sequence = self.sequence();
+        let value = sequence.load(Ordering::Relaxed);
+        sequence.store(value.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        write();
+        sequence.store(value.wrapping_add(2), Ordering::Release);
+    }
+
+    fn read_bits(&self, table: RegisterTable, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        check_range(address, quantity as usize)?;
+        let words = self.bits(table);
+        Ok(self.read(|| {
+            (address as usize..address as usize + quantity as usize)
+                .map(|i| words[i / 64].load(Ordering::Relaxed) & (1 << (i % 64)) != 0)
+                .collect()
+        }))
+    }
+
+    fn read_words(&self, table: RegisterTable, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        check_range(address, quantity as usize)?;
+        let words = self.words(table);
+        Ok(self.read(|| words[address as usize..address as usize + quantity as usize].iter().map(|word| word.load(Ordering::Relaxed)).collect()))
+    }
+
+    fn store_bits(&self, table: RegisterTable, address: u16, values: &[bool]) {
+        let words = self.bits(table);
+        for (i, &value) in values.iter().enumerate() {
+            let index = address as usize + i;
+            let mask = 1 << (index % 64);
+            if value {
+                words[index / 64].fetch_or(mask, Ordering::Relaxed);
+            } else {
+                words[index / 64].fetch_and(!mask, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn store_words(&self, table: RegisterTable, address: u16, values: &[u16]) {
+        let words = &self.words(table)[address as usize..];
+        for (word, &value) in words.iter().zip(values) {
+            word.store(value, Ordering::Relaxed);
+        }
+    }
+
+    fn write_bits(&self, table: RegisterTable, address: u16, values: &[bool]) -> ModbusResult<()> {
+        check_range(address, values.len())?;
+        let _writer = self.lock()?;
+        self.publish(|| self.store_bits(table, address, values));
+        Ok(())
+    }
+
+    fn write_words(&self, table: RegisterTable, address: u16, values: &[u16]) -> ModbusResult<()> {
+        check_range(address, values.len())?;
+        let _writer = self.lock()?;
+        self.publish(|| self.store_words(table, address, values));
+        Ok(())
+    }
+}
+
+/// Reject ranges running past the end of a table
+fn check_range(address: u16, quantity: usize) -> ModbusResult<()> {
+    if address as usize + quantity > TABLE_SIZE {
+        return Err(ModbusError::invalid_address(address, quantity.min(u16::MAX as usize) as u16));
+    }
+    Ok(())
+}
+
+impl DataStore for MmapStore {
+    fn read_coils(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.read_bits(RegisterTable::Coil, address, quantity)
+    }
+
+    fn read_discrete_inputs(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.read_bits(RegisterTable::DiscreteInput, address, quantity)
+    }
+
+    fn read_holding_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.read_words(RegisterTable::HoldingRegister, address, quantity)
+    }
+
+    fn read_input_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.read_words(RegisterTable::InputRegister, address, quantity)
+    }
+
+    fn write_coils(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.write_bits(RegisterTable::Coil, address, values)
+    }
+
+    fn write_discrete_inputs(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.write_bits(RegisterTable::DiscreteInput, address, values)
+    }
+
+    fn write_holding_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.write_words(RegisterTable::HoldingRegister, address, values)
+    }
+
+    fn write_input_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.write_words(RegisterTable::InputRegister, address, values)
+    }
+
+    /// Apply all writes as one step for readers, or none if any is invalid
+    fn write_batch(&self, writes: &[(DataAddress, PointValue)]) -> ModbusResult<()> {
+        for (address, value) in writes {
+            let len = match (address.table.is_bit_table(), value) {
+                (true, PointValue::Bits(bits)) => bits.len(),
+                (false, PointValue::Registers(registers)) => registers.len(),
+                _ => return Err(ModbusError::invalid_data(format!("Data does not match the {} table", address.table))),
+            };
+            check_range(address.address, len)?;
+        }
+
+        let _writer = self.lock()?;
+        self.publish(|| {
+            for (address, value) in writes {
+                match value {
+                    PointValue::Bits(bits) => self.store_bits(address.table, address.address, bits),
+                    PointValue::Registers(registers) => self.store_words(address.table, address.address, registers),
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_image() {
+        let path = std::env::temp_dir().join(format!("voltage_modbus_image_{}.img", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let writer = MmapStore::open(&path).unwrap();
+        writer.write_holding_registers(65534, &[1, 2]).unwrap();
+        writer.write_coils(63, &[true, true]).unwrap();
+        assert!(writer.write_holding_registers(65535, &[1, 2]).is_err());
+
+        // A second mapping, as another process would open it, sees the same image
+        let reader = MmapStore::open(&path).unwrap();
+        assert_eq!(reader.read_holding_registers(65533, 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(reader.read_coils(62, 3).unwrap(), vec![false, true, true]);
+
+        let batch = [
+            (DataAddress::new(RegisterTable::InputRegister, 0), PointValue::Registers(vec![7])),
+            (DataAddress::new(RegisterTable::DiscreteInput, 0), PointValue::Registers(vec![1])),
+        ];
+        assert!(reader.write_batch(&batch).is_err());
+        assert_eq!(writer.read_input_registers(0, 1).unwrap(), vec![0]);
+        reader.write_batch(&batch[..1]).unwrap();
+        assert_eq!(writer.read_input_registers(0, 1).unwrap(), vec![7]);
+        writer.flush().unwrap();
+
+        // Replace the file only once nothing maps it
+        drop(reader);
+        drop(writer);
+        std::fs::write(&path, b"not an image").unwrap();
+        assert!(MmapStore::open(&path).is_err());
+        std::fs::write(&path, b"").unwrap();
+        assert!(MmapStore::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_create() {
+        let path = std::env::temp_dir().join(format!("voltage_modbus_create_{}.img", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Every opener ends up on the same initialised image
+        let stores: Vec<MmapStore> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| MmapStore::open(&path).unwrap())).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        stores[0].write_holding_registers(10, &[42]).unwrap();
+        for store in &stores {
+            assert_eq!(store.read_holding_registers(10, 1).unwrap(), vec![42]);
+        }
+
+        drop(stores);
+        let leftovers = std::fs::read_dir(std::env::temp_dir()).unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&format!(".voltage_modbus_create_{}", std::process::id())))
+            .count();
+        assert_eq!(leftovers, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
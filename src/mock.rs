@@ -0,0 +1,738 @@
+//! # In-Memory Transports for Testing
+//!
+//! Clients, pollers and application code can be tested without sockets or
+//! serial ports:
+//!
+//! - [`MemoryTransport`] sends real Modbus TCP, RTU or ASCII frames through a
+//!   [`tokio::io::duplex`] pipe. With a [`MemoryServer`] serving any
+//!   [`DataStore`] on the other end, e.g. a
+//!   [`ModbusRegisterBank`](crate::register_bank::ModbusRegisterBank), every
+//!   request goes through the same encoding, CRC/LRC checks and decoding as on
+//!   the wire.
+//! - [`MockTransport`] checks requests against a list of expectations and
+//!   answers with canned replies. [`MockTransport::verify`] then reports
+//!   whether every expectation was met.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//! use voltage_modbus::{ModbusClient, ModbusFunction, ModbusRegisterBank, ModbusRequest};
+//! use voltage_modbus::client::GenericModbusClient;
+//! use voltage_modbus::mock::{Framing, MemoryTransport, MockTransport};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> voltage_modbus::ModbusResult<()> {
+//! // A register bank behind a loopback RTU line
+//! let bank = Arc::new(ModbusRegisterBank::new());
+//! bank.write_10(0, &[230, 50])?;
+//! let (transport, _server) = MemoryTransport::loopback(bank.clone(), Framing::Rtu);
+//! let mut client = GenericModbusClient::new(transport);
+//! assert_eq!(client.read_03(1, 0, 2).await?, vec![230, 50]);
+//!
+//! // Expectations checked request by request
+//! let mut mock = MockTransport::new();
+//! mock.expect(ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 0, 2))
+//!     .returns_registers(&[230, 50])
+//!     .expect(ModbusRequest::new_write(1, ModbusFunction::WriteSingleRegister, 10, vec![0, 0]))
+//!     .acknowledges();
+//!
+//! let mut client = GenericModbusClient::new(mock);
+//! assert_eq!(client.read_03(1, 0, 2).await?, vec![230, 50]);
+//! client.write_06(1, 10, 0).await?;
+//! client.transport().verify()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::debug;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+use crate::enron::EnronConfig;
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{data_utils, ModbusFunction, ModbusRequest, ModbusResponse};
+use crate::server::ModbusTcpServer;
+use crate::store::DataStore;
+use crate::transport::{AsciiTransport, ModbusTransport, RtuTransport, TcpTransport, TransportStats};
+
+/// Buffer size of each direction of a loopback pipe
+const PIPE_CAPACITY: usize = 1024;
+
+/// Maximum ASCII frame length (':' + 2 * (address, PDU, LRC) + CR LF)
+const MAX_ASCII_FRAME_SIZE: usize = 513;
+
+/// Maximum RTU frame length (address, 253-byte PDU, CRC)
+const MAX_RTU_FRAME_SIZE: usize = 256;
+
+/// Silence that ends an RTU frame whose length the function code does not tell
+const RTU_FRAME_GAP: Duration = Duration::from_millis(5);
+
+/// Default response timeout of a [`MemoryTransport`]
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Wire format used over an in-memory pipe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// MBAP header and PDU, as on Modbus TCP
+    Tcp,
+    /// Address, PDU and CRC-16, as on Modbus RTU
+    Rtu,
+    /// Hex-encoded address, PDU and LRC between ':' and CR LF, as on Modbus ASCII
+    Ascii,
+}
+
+/// Client transport over one end of an in-memory pipe
+///
+/// Requests are encoded and responses decoded with the codecs of
+/// [`TcpTransport`], [`RtuTransport`] or [`AsciiTransport`], depending on the
+/// [`Framing`].
+pub struct MemoryTransport {
+    stream: Option<DuplexStream>,
+    framing: Framing,
+    timeout: Duration,
+    transaction_id: u16,
+    stats: TransportStats,
+    enron: Option<EnronConfig>,
+}
+
+impl MemoryTransport {
+    /// Create a transport talking over `stream`
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - Client end of a [`tokio::io::duplex`] pipe
+    /// * `framing` - Wire format, which the peer must speak as well
+    pub fn new(stream: DuplexStream, framing: Framing) -> Self {
+        Self {
+            stream: Some(stream),
+            framing,
+            timeout: DEFAULT_TIMEOUT,
+            transaction_id: 0,
+            stats: TransportStats::default(),
+            enron: None,
+        }
+    }
+
+    /// Create a transport connected to a [`MemoryServer`] serving `store`
+    ///
+    /// # Returns
+    ///
+    /// The transport and the server, which stops serving when dropped
+    pub fn loopback<S: DataStore>(store: Arc<S>, framing: Framing) -> (Self, MemoryServer) {
+        let (client, server) = tokio::io::duplex(PIPE_CAPACITY);
+        (Self::new(client, framing), MemoryServer::spawn(server, store, framing))
+    }
+
+    /// Set how long to wait for a response (1 second by default)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wire format of this transport
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// Encode a request in the wire format
+    fn encode_request(&mut self, request: &ModbusRequest) -> ModbusResult<Vec<u8>> {
+        match self.framing {
+            Framing::Tcp => {
+                self.transaction_id = self.transaction_id.wrapping_add(1);
+                Ok(TcpTransport::encode_frame(self.transaction_id, request))
+            }
+            Framing::Rtu => RtuTransport::encode_request(request),
+            Framing::Ascii => AsciiTransport::encode_request(request),
+        }
+    }
+
+    /// Decode a response in the wire format
+    fn decode_response(&self, frame: &[u8]) -> ModbusResult<ModbusResponse> {
+        match self.framing {
+            Framing::Tcp => TcpTransport::decode_response(frame),
+            Framing::Rtu => RtuTransport::decode_response(frame),
+            Framing::Ascii => AsciiTransport::decode_response(frame),
+        }
+    }
+}
+
+#[async_trait]
+impl ModbusTransport for MemoryTransport {
+    async fn request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+        request.validate_with(self.enron.as_ref())?;
+
+        let frame = self.encode_request(request)?;
+        let framing = self.framing;
+        let stream = self.stream.as_mut()
+            .ok_or_else(|| ModbusError::connection("In-memory transport is closed"))?;
+        self.stats.requests_sent += 1;
+        self.stats.bytes_sent += frame.len() as u64;
+
+        let exchange = async {
+            stream.write_all(&frame).await
+                .map_err(|e| ModbusError::io(format!("Failed to send request: {}", e)))?;
+            read_frame(stream, framing, false).await
+        };
+        let response_frame = match timeout(self.timeout, exchange).await {
+            Ok(Ok(frame)) => frame,
+            Ok(Err(e)) => {
+                // The peer is gone or out of step
+                self.stats.errors += 1;
+                self.stream = None;
+                return Err(e);
+            }
+            Err(_) => {
+                self.stats.timeouts += 1;
+                self.stats.errors += 1;
+                self.stream = None;
+                return Err(ModbusError::timeout("read response", self.timeout.as_millis() as u64));
+            }
+        };
+
+        self.stats.responses_received += 1;
+        self.stats.bytes_received += response_frame.len() as u64;
+
+        let response = self.decode_response(&response_frame)?;
+        if response.slave_id != request.slave_id {
+            self.stats.errors += 1;
+            return Err(ModbusError::protocol(format!(
+                "Response slave ID mismatch: expected {}, got {}",
+                request.slave_id, response.slave_id
+            )));
+        }
+        if let Some(error) = response.get_exception() {
+            self.stats.errors += 1;
+            return Err(error);
+        }
+        Ok(response)
+    }
+
+    fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        Ok(())
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        self.stats.clone()
+    }
+
+    fn set_enron_mode(&mut self, config: Option<EnronConfig>) {
+        self.enron = config;
+    }
+}
+
+/// Server end of an in-memory pipe, answering requests from a data store
+///
+/// Every unit ID is answered, like [`ModbusTcpServer`] does. On RTU and ASCII
+/// framing, broadcasts (unit ID 0) are processed without a reply and frames
+/// with a bad CRC or LRC are ignored, as a device on a serial line would.
+pub struct MemoryServer {
+    task: JoinHandle<()>,
+}
+
+impl MemoryServer {
+    /// Serve `store` over `stream` until the other end is closed
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - Server end of a [`tokio::io::duplex`] pipe
+    /// * `store` - Data store answering the requests
+    /// * `framing` - Wire format, which the peer must speak as well
+    pub fn spawn<S: DataStore>(stream: DuplexStream, store: Arc<S>, framing: Framing) -> Self {
+        Self { task: tokio::spawn(serve(stream, store, framing)) }
+    }
+
+    /// Check whether the server is still serving
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stop serving
+    pub fn stop(self) {}
+}
+
+impl Drop for MemoryServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Answer requests until the pipe is closed or out of step
+async fn serve<S: DataStore>(mut stream: DuplexStream, store: Arc<S>, framing: Framing) {
+    loop {
+        let frame = match read_frame(&mut stream, framing, true).await {
+            Ok(frame) => frame,
+            Err(e) => {
+                debug!("In-memory server stopped: {}", e);
+                break;
+            }
+        };
+        if let Some(reply) = reply(&frame, &store, framing).await {
+            if stream.write_all(&reply).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Process a request frame and build the reply frame, if any
+async fn reply<S: DataStore>(frame: &[u8], store: &Arc<S>, framing: Framing) -> Option<Vec<u8>> {
+    let (unit_id, pdu) = match framing {
+        Framing::Tcp => (frame[6], frame[7..].to_vec()),
+        Framing::Rtu => {
+            // Address, function code and CRC at the least
+            if frame.len() < 4 {
+                debug!("In-memory server ignored {}-byte RTU frame", frame.len());
+                return None;
+            }
+            let (body, crc) = frame.split_at(frame.len() - 2);
+            if RtuTransport::calculate_crc(body).to_le_bytes() != crc {
+                debug!("In-memory server ignored frame with bad CRC");
+                return None;
+            }
+            (body[0], body[1..].to_vec())
+        }
+        Framing::Ascii => match AsciiTransport::decode_frame(frame) {
+            Ok(raw) if !raw.is_empty() => (raw[0], raw[1..].to_vec()),
+            Ok(_) => {
+                debug!("In-memory server ignored empty ASCII frame");
+                return None;
+            }
+            Err(e) => {
+                debug!("In-memory server ignored frame: {}", e);
+                return None;
+            }
+        },
+    };
+
+    if pdu.is_empty() {
+        debug!("In-memory server ignored frame without a PDU");
+        return None;
+    }
+
+    let response = match ModbusTcpServer::<S>::handle_pdu(unit_id, &pdu, store, None).await {
+        Ok(response) => response,
+        Err(e) => vec![pdu[0] | 0x80, ModbusTcpServer::exception_code(&e)],
+    };
+
+    match framing {
+        Framing::Tcp => Some(ModbusTcpServer::create_response(frame, &response)),
+        // Broadcasts are never answered on a serial line
+        _ if unit_id == 0 => None,
+        Framing::Rtu => {
+            let mut reply = vec![unit_id];
+            reply.extend_from_slice(&response);
+            let crc = RtuTransport::calculate_crc(&reply);
+            reply.extend_from_slice(&crc.to_le_bytes());
+            Some(reply)
+        }
+        Framing::Ascii => {
+            let mut raw = vec![unit_id];
+            raw.extend_from_slice(&response);
+            Some(AsciiTransport::encode_frame(&raw))
+        }
+    }
+}
+
+/// Read one frame, a request when `request` is set and a response otherwise
+async fn read_frame(stream: &mut DuplexStream, framing: Framing, request: bool) -> ModbusResult<Vec<u8>> {
+    match framing {
+        Framing::Tcp => read_tcp_frame(stream).await,
+        Framing::Rtu => read_rtu_frame(stream, request).await,
+        Framing::Ascii => read_ascii_frame(stream).await,
+    }
+}
+
+/// Append `count` bytes read from `stream` to `frame`
+async fn read_more(stream: &mut DuplexStream, frame: &mut Vec<u8>, count: usize) -> ModbusResult<()> {
+    let start = frame.len();
+    frame.resize(start + count, 0);
+    stream.read_exact(&mut frame[start..]).await
+        .map_err(|e| ModbusError::io(format!("Failed to read frame: {}", e)))?;
+    Ok(())
+}
+
+/// Read a frame delimited by the length field of its MBAP header
+async fn read_tcp_frame(stream: &mut DuplexStream) -> ModbusResult<Vec<u8>> {
+    let mut frame = Vec::new();
+    // MBAP header and unit ID
+    read_more(stream, &mut frame, 7).await?;
+    let length = u16::from_be_bytes([frame[4], frame[5]]) as usize;
+    if !(2..=254).contains(&length) {
+        return Err(ModbusError::frame(format!("Invalid MBAP length {}", length)));
+    }
+    read_more(stream, &mut frame, length - 1).await?;
+    Ok(frame)
+}
+
+/// Read an RTU frame
+///
+/// On a serial line, frames end with a silent interval. A pipe has no
+/// timing, so the length is derived from the function code instead.
+async fn read_rtu_frame(stream: &mut DuplexStream, request: bool) -> ModbusResult<Vec<u8>> {
+    let mut frame = Vec::new();
+    read_more(stream, &mut frame, 2).await?;
+
+    // Length up to and including the byte count, if the frame has one
+    let function = frame[1];
+    let (header, counted) = match (request, function) {
+        (false, f) if f & 0x80 != 0 => (3, false),
+        (true, 0x01..=0x06) | (false, 0x05 | 0x06 | 0x0F | 0x10) => (6, false),
+        (true, 0x0F | 0x10) => (7, true),
+        (false, 0x01..=0x04) => (3, true),
+        _ => {
+            // Unknown function: take the rest of the frame, as a serial line
+            // would, so the server can answer Illegal Function
+            read_until_silent(stream, &mut frame).await?;
+            return Ok(frame);
+        }
+    };
+    read_more(stream, &mut frame, header - 2).await?;
+    if counted {
+        let byte_count = frame[header - 1] as usize;
+        read_more(stream, &mut frame, byte_count).await?;
+    }
+    // CRC
+    read_more(stream, &mut frame, 2).await?;
+    Ok(frame)
+}
+
+/// Append bytes from `stream` to `frame` until it stays silent for [`RTU_FRAME_GAP`]
+async fn read_until_silent(stream: &mut DuplexStream, frame: &mut Vec<u8>) -> ModbusResult<()> {
+    let mut buffer = [0u8; MAX_RTU_FRAME_SIZE];
+    loop {
+        match timeout(RTU_FRAME_GAP, stream.read(&mut buffer)).await {
+            Err(_) => return Ok(()),
+            Ok(Ok(0)) => return Err(ModbusError::io("Failed to read frame: stream closed")),
+            Ok(Ok(count)) => frame.extend_from_slice(&buffer[..count]),
+            Ok(Err(e)) => return Err(ModbusError::io(format!("Failed to read frame: {}", e))),
+        }
+        if frame.len() > MAX_RTU_FRAME_SIZE {
+            return Err(ModbusError::frame("RTU frame too large"));
+        }
+    }
+}
+
+/// Read an ASCII frame up to and including CR LF
+async fn read_ascii_frame(stream: &mut DuplexStream) -> ModbusResult<Vec<u8>> {
+    let mut frame = Vec::new();
+    while !frame.ends_with(b"\r\n") {
+        if frame.len() >= MAX_ASCII_FRAME_SIZE {
+            return Err(ModbusError::frame("ASCII frame too large"));
+        }
+        let byte = stream.read_u8().await
+            .map_err(|e| ModbusError::io(format!("Failed to read frame: {}", e)))?;
+        frame.push(byte);
+    }
+    Ok(frame)
+}
+
+/// Expected request and the reply to give
+#[derive(Debug)]
+struct Expectation {
+    request: ModbusRequest,
+    reply: ModbusResult<ModbusResponse>,
+}
+
+/// Transport answering from a list of expected requests
+///
+/// Requests must arrive in the order they were expected and equal the
+/// expected [`ModbusRequest`] field by field. Anything else fails with a
+/// protocol error, leaves the expectations untouched and is reported by
+/// [`verify`](Self::verify).
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    expectations: VecDeque<Expectation>,
+    unexpected: Vec<String>,
+    stats: TransportStats,
+    enron: Option<EnronConfig>,
+}
+
+impl MockTransport {
+    /// Create a mock without expectations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect `request` after the previously expected ones
+    ///
+    /// The expectation is registered once its reply is set on the returned
+    /// [`Expect`].
+    pub fn expect(&mut self, request: ModbusRequest) -> Expect<'_> {
+        Expect { mock: self, request }
+    }
+
+    /// Number of expected requests that have not arrived yet
+    pub fn pending(&self) -> usize {
+        self.expectations.len()
+    }
+
+    /// Check that every expected request arrived and nothing else did
+    pub fn verify(&self) -> ModbusResult<()> {
+        if let Some(first) = self.unexpected.first() {
+            return Err(ModbusError::protocol(format!(
+                "{} unexpected request(s), first: {}",
+                self.unexpected.len(), first
+            )));
+        }
+        if let Some(next) = self.expectations.front() {
+            return Err(ModbusError::protocol(format!(
+                "{} expected request(s) not received, next: {:?}",
+                self.expectations.len(), next.request
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ModbusTransport for MockTransport {
+    async fn request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+        request.validate_with(self.enron.as_ref())?;
+        self.stats.requests_sent += 1;
+
+        let problem = match self.expectations.front() {
+            Some(expected) if expected.request == *request => None,
+            Some(expected) => Some(format!("expected {:?}, got {:?}", expected.request, request)),
+            None => Some(format!("no request expected, got {:?}", request)),
+        };
+        if let Some(problem) = problem {
+            self.stats.errors += 1;
+            let error = ModbusError::protocol(format!("Unexpected request: {}", problem));
+            self.unexpected.push(problem);
+            return Err(error);
+        }
+
+        let reply = self.expectations.pop_front().map(|expected| expected.reply);
+        match reply {
+            Some(Ok(response)) => {
+                self.stats.responses_received += 1;
+                Ok(response)
+            }
+            Some(Err(e)) => {
+                self.stats.errors += 1;
+                Err(e)
+            }
+            None => unreachable!("expectation checked above"),
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        Ok(())
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        self.stats.clone()
+    }
+
+    fn set_enron_mode(&mut self, config: Option<EnronConfig>) {
+        self.enron = config;
+    }
+}
+
+/// Reply to an expected request, see [`MockTransport::expect`]
+///
+/// Each method registers the expectation and returns the mock, so
+/// expectations can be chained.
+#[must_use = "the expectation is only registered once its reply is set"]
+pub struct Expect<'a> {
+    mock: &'a mut MockTransport,
+    request: ModbusRequest,
+}
+
+impl<'a> Expect<'a> {
+    /// Reply with `response`
+    pub fn returns(self, response: ModbusResponse) -> &'a mut MockTransport {
+        self.reply(Ok(response))
+    }
+
+    /// Reply to a register read with `values`
+    pub fn returns_registers(self, values: &[u16]) -> &'a mut MockTransport {
+        let mut data = vec![(values.len() * 2) as u8];
+        data.extend_from_slice(&data_utils::registers_to_bytes(values));
+        self.respond_with(data)
+    }
+
+    /// Reply to a coil or discrete input read with `values`
+    pub fn returns_bits(self, values: &[bool]) -> &'a mut MockTransport {
+        let packed = data_utils::pack_bits(values);
+        let mut data = vec![packed.len() as u8];
+        data.extend_from_slice(&packed);
+        self.respond_with(data)
+    }
+
+    /// Acknowledge a write the way a device does, echoing address and value or quantity
+    pub fn acknowledges(self) -> &'a mut MockTransport {
+        let request = &self.request;
+        let mut data = request.address.to_be_bytes().to_vec();
+        match request.function {
+            ModbusFunction::WriteSingleCoil => {
+                let on = request.data.first().is_some_and(|&b| b != 0);
+                data.extend_from_slice(if on { &[0xFF, 0x00] } else { &[0x00, 0x00] });
+            }
+            ModbusFunction::WriteSingleRegister => {
+                data.extend_from_slice(request.data.get(..2).unwrap_or(&[0, 0]));
+            }
+            _ => data.extend_from_slice(&request.quantity.to_be_bytes()),
+        }
+        self.respond_with(data)
+    }
+
+    /// Reply with an exception response carrying `code`
+    pub fn returns_exception(self, code: u8) -> &'a mut MockTransport {
+        let error = ModbusError::exception(self.request.function.to_u8(), code);
+        self.reply(Err(error))
+    }
+
+    /// Fail the request with `error`, e.g. a timeout
+    pub fn fails(self, error: ModbusError) -> &'a mut MockTransport {
+        self.reply(Err(error))
+    }
+
+    fn respond_with(self, data: Vec<u8>) -> &'a mut MockTransport {
+        let response = ModbusResponse::new_success(self.request.slave_id, self.request.function, data);
+        self.reply(Ok(response))
+    }
+
+    fn reply(self, reply: ModbusResult<ModbusResponse>) -> &'a mut MockTransport {
+        self.mock.expectations.push_back(Expectation { request: self.request, reply });
+        self.mock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{GenericModbusClient, ModbusClient};
+    use crate::register_bank::ModbusRegisterBank;
+
+    #[tokio::test]
+    async fn test_loopback_framings() {
+        // Reading two registers takes 12 bytes on TCP, 8 on RTU and 17 on ASCII
+        for (framing, request_size) in [(Framing::Tcp, 12), (Framing::Rtu, 8), (Framing::Ascii, 17)] {
+            let bank = Arc::new(ModbusRegisterBank::new().with_default_sizes());
+            bank.write_10(0, &[230, 50]).unwrap();
+            let (transport, _server) = MemoryTransport::loopback(bank.clone(), framing);
+            let mut client = GenericModbusClient::new(transport);
+
+            assert_eq!(client.read_03(1, 0, 2).await.unwrap(), vec![230, 50]);
+            assert_eq!(client.get_stats().bytes_sent, request_size, "{:?}", framing);
+
+            client.write_10(1, 100, &[1, 2, 3]).await.unwrap();
+            client.write_0f(1, 5, &[true, false, true]).await.unwrap();
+            assert_eq!(bank.read_03(100, 3).unwrap(), vec![1, 2, 3]);
+            assert_eq!(client.read_01(1, 5, 3).await.unwrap(), vec![true, false, true]);
+
+            match client.read_03(1, 9999, 10).await {
+                Err(ModbusError::Exception { code, .. }) => assert_eq!(code, 0x02),
+                other => panic!("expected an exception for {:?}, got {:?}", framing, other),
+            }
+
+            // Still in step after the exception
+            assert_eq!(client.read_03(1, 1, 1).await.unwrap(), vec![50]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rtu_unknown_function() {
+        let bank = Arc::new(ModbusRegisterBank::new().with_default_sizes());
+        bank.write_10(0, &[230]).unwrap();
+        let (mut client, server) = tokio::io::duplex(PIPE_CAPACITY);
+        let server = MemoryServer::spawn(server, bank.clone(), Framing::Rtu);
+
+        // Read device identification (0x2B) is not supported: Illegal Function
+        let mut frame = vec![1, 0x2B, 0x0E, 0x01, 0x00];
+        frame.extend_from_slice(&RtuTransport::calculate_crc(&frame).to_le_bytes());
+        client.write_all(&frame).await.unwrap();
+        let reply = read_frame(&mut client, Framing::Rtu, false).await.unwrap();
+        assert_eq!(reply[..3], [1, 0xAB, 0x01]);
+        assert!(server.is_running());
+
+        let mut transport = MemoryTransport::new(client, Framing::Rtu);
+        let response = transport.request(&ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 0, 1)).await.unwrap();
+        assert_eq!(response.data, vec![2, 0, 230]);
+    }
+
+    #[tokio::test]
+    async fn test_rtu_short_frames_are_ignored() {
+        let bank = Arc::new(ModbusRegisterBank::new().with_default_sizes());
+        bank.write_10(0, &[230]).unwrap();
+        let (mut client, server) = tokio::io::duplex(PIPE_CAPACITY);
+        let server = MemoryServer::spawn(server, bank, Framing::Rtu);
+
+        // Only a CRC, and an address without a PDU, both with a valid CRC
+        for body in [&[][..], &[1][..]] {
+            // The second byte reads as an unknown function, so the frame ends at the silence
+            let mut frame = body.to_vec();
+            frame.extend_from_slice(&RtuTransport::calculate_crc(body).to_le_bytes());
+            client.write_all(&frame).await.unwrap();
+            tokio::time::sleep(RTU_FRAME_GAP * 4).await;
+            assert!(server.is_running());
+        }
+
+        let mut transport = MemoryTransport::new(client, Framing::Rtu);
+        let response = transport.request(&ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 0, 1)).await.unwrap();
+        assert_eq!(response.data, vec![2, 0, 230]);
+    }
+
+    #[tokio::test]
+    async fn test_slave_id_mismatch() {
+        let (client, mut server) = tokio::io::duplex(PIPE_CAPACITY);
+        tokio::spawn(async move {
+            read_frame(&mut server, Framing::Rtu, true).await.unwrap();
+            // Another slave answers
+            let mut reply = vec![2, 0x03, 0x02, 0x00, 0x2A];
+            reply.extend_from_slice(&RtuTransport::calculate_crc(&reply).to_le_bytes());
+            server.write_all(&reply).await.unwrap();
+            let _ = server.read_u8().await;
+        });
+
+        let mut transport = MemoryTransport::new(client, Framing::Rtu);
+        let error = transport.request(&ModbusRequest::new_read(1, ModbusFunction::ReadHoldingRegisters, 0, 1)).await.unwrap_err();
+        assert!(error.to_string().contains("slave ID mismatch"), "{}", error);
+        assert_eq!(transport.get_stats().errors, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mock_expectations() {
+        let mut mock = MockTransport::new();
+        mock.expect(ModbusRequest::new_read(1, ModbusFunction::ReadCoils, 0, 3))
+            .returns_bits(&[true, false, true])
+            .expect(ModbusRequest::new_write(1, ModbusFunction::WriteSingleRegister, 7, vec![0x01, 0x2C]))
+            .acknowledges()
+            .expect(ModbusRequest::new_read(2, ModbusFunction::ReadInputRegisters, 0, 1))
+            .returns_exception(0x06)
+            .expect(ModbusRequest::new_read(2, ModbusFunction::ReadInputRegisters, 0, 1))
+            .returns_registers(&[42]);
+
+        let mut client = GenericModbusClient::new(mock);
+        assert_eq!(client.read_01(1, 0, 3).await.unwrap(), vec![true, false, true]);
+        client.write_06(1, 7, 300).await.unwrap();
+        assert!(client.read_04(2, 0, 1).await.is_err());
+        assert_eq!(client.transport().pending(), 1);
+        assert!(client.transport().verify().is_err());
+
+        // A request out of order is rejected without consuming the expectation
+        assert!(client.read_04(2, 1, 1).await.is_err());
+        assert_eq!(client.read_04(2, 0, 1).await.unwrap(), vec![42]);
+        assert_eq!(client.transport().pending(), 0);
+        assert!(client.transport().verify().unwrap_err().to_string().contains("1 unexpected request"));
+    }
+}
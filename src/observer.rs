@@ -0,0 +1,349 @@
+//! # Register Bank Observers
+//!
+//! Server applications usually need to react when a master writes a setpoint.
+//! [`ModbusRegisterBank`](crate::register_bank::ModbusRegisterBank) reports
+//! every write as a [`ChangeEvent`] carrying the old and new values, the unit
+//! id of the request and the peer address of the master:
+//!
+//! - **Streams**: [`subscribe_changes`](crate::register_bank::ModbusRegisterBank::subscribe_changes)
+//!   returns a [`ChangeSubscription`] backed by a `tokio::sync::broadcast` channel
+//! - **Callbacks**: [`on_change`](crate::register_bank::ModbusRegisterBank::on_change)
+//!   runs a closure synchronously after each write
+//! - **Vetoes**: [`add_write_veto`](crate::register_bank::ModbusRegisterBank::add_write_veto)
+//!   runs before the write and can reject it; servers answer rejected writes
+//!   with exception 0x03 (Illegal Data Value)
+//!
+//! Each observer watches one table and address range and only sees the part
+//! of a write that falls inside it.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::{ModbusRegisterBank, RegisterTable};
+//! use voltage_modbus::planner::PointValue;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let bank = ModbusRegisterBank::new();
+//! let mut setpoints = bank.subscribe_changes(RegisterTable::HoldingRegister, 100..=199);
+//!
+//! // Setpoints above 1000 are rejected
+//! bank.add_write_veto(RegisterTable::HoldingRegister, 100..=199, |event| {
+//!     event.new.as_registers().is_some_and(|values| values.iter().all(|&value| value <= 1000))
+//! });
+//!
+//! bank.write_06(100, 500).unwrap();
+//! assert!(bank.write_06(101, 5000).is_err());
+//!
+//! let event = setpoints.recv().await.unwrap();
+//! assert_eq!((event.address, event.new), (100, PointValue::Registers(vec![500])));
+//! # }
+//! ```
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use tokio::sync::broadcast;
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::planner::PointValue;
+use crate::protocol::{RegisterTable, SlaveId};
+
+/// Capacity of the change event channel
+const CHANGE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Where a write came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WriteContext {
+    /// Unit id of the request, `None` for local writes
+    pub unit_id: Option<SlaveId>,
+    /// Address of the master, `None` for local and serial writes
+    pub peer: Option<SocketAddr>,
+}
+
+impl WriteContext {
+    /// Write made by the application itself
+    pub fn local() -> Self {
+        Self::default()
+    }
+
+    /// Write made by a Modbus request
+    pub fn remote(unit_id: SlaveId, peer: Option<SocketAddr>) -> Self {
+        Self { unit_id: Some(unit_id), peer }
+    }
+
+    /// Check whether the write came from a Modbus request
+    pub fn is_remote(&self) -> bool {
+        self.unit_id.is_some()
+    }
+}
+
+/// A write to a contiguous range of one table
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    /// Table written
+    pub table: RegisterTable,
+    /// First protocol address written
+    pub address: u16,
+    /// Values before the write
+    pub old: PointValue,
+    /// Values written
+    pub new: PointValue,
+    /// Origin of the write
+    pub context: WriteContext,
+    /// Enron 32-bit register write: each address holds two registers of
+    /// `old` and `new`, high word first
+    pub long: bool,
+}
+
+impl ChangeEvent {
+    /// Number of addresses written
+    pub fn len(&self) -> usize {
+        point_len(&self.new) / self.width()
+    }
+
+    /// Values per address
+    fn width(&self) -> usize {
+        if self.long { 2 } else { 1 }
+    }
+
+    /// Check whether the write was empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check whether any value differs from before the write
+    pub fn is_changed(&self) -> bool {
+        self.old != self.new
+    }
+
+    /// The part of the write inside `range` of `table`, if any
+    pub fn restrict(&self, table: RegisterTable, range: &RangeInclusive<u16>) -> Option<Self> {
+        if table != self.table || self.is_empty() {
+            return None;
+        }
+        let first = self.address as usize;
+        let last = first + self.len() - 1;
+        let start = first.max(*range.start() as usize);
+        let end = last.min(*range.end() as usize);
+        if start > end {
+            return None;
+        }
+        if (start, end) == (first, last) {
+            return Some(self.clone());
+        }
+        let width = self.width();
+        let span = (start - first) * width..(end - first + 1) * width;
+        Some(Self {
+            table,
+            address: start as u16,
+            old: slice_point(&self.old, span.clone()),
+            new: slice_point(&self.new, span),
+            context: self.context,
+            long: self.long,
+        })
+    }
+}
+
+fn point_len(value: &PointValue) -> usize {
+    match value {
+        PointValue::Registers(values) => values.len(),
+        PointValue::Bits(values) => values.len(),
+    }
+}
+
+fn slice_point(value: &PointValue, span: std::ops::Range<usize>) -> PointValue {
+    match value {
+        PointValue::Registers(values) => PointValue::Registers(values[span].to_vec()),
+        PointValue::Bits(values) => PointValue::Bits(values[span].to_vec()),
+    }
+}
+
+/// Identifies a callback or veto so it can be removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(u64);
+
+/// Stream of writes to one table and address range
+pub struct ChangeSubscription {
+    receiver: broadcast::Receiver<ChangeEvent>,
+    table: RegisterTable,
+    range: RangeInclusive<u16>,
+}
+
+impl ChangeSubscription {
+    /// Wait for the next write inside the watched range
+    ///
+    /// # Returns
+    ///
+    /// The watched part of the write, or `RecvError::Lagged` when events
+    /// were dropped because the subscriber fell behind
+    pub async fn recv(&mut self) -> Result<ChangeEvent, broadcast::error::RecvError> {
+        loop {
+            let event = self.receiver.recv().await?;
+            if let Some(event) = event.restrict(self.table, &self.range) {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Get the next pending write inside the watched range without waiting
+    pub fn try_recv(&mut self) -> Result<ChangeEvent, broadcast::error::TryRecvError> {
+        loop {
+            let event = self.receiver.try_recv()?;
+            if let Some(event) = event.restrict(self.table, &self.range) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+type CallbackFn = dyn Fn(&ChangeEvent) + Send + Sync;
+type VetoFn = dyn Fn(&ChangeEvent) -> bool + Send + Sync;
+type Callback = Arc<CallbackFn>;
+type Veto = Arc<VetoFn>;
+
+/// A callback or veto with the range it watches
+struct Watch<F: ?Sized> {
+    id: ObserverId,
+    table: RegisterTable,
+    range: RangeInclusive<u16>,
+    handler: Arc<F>,
+}
+
+/// Observers of one register bank, shared by its clones
+pub(crate) struct Observers {
+    sender: broadcast::Sender<ChangeEvent>,
+    callbacks: RwLock<Vec<Watch<CallbackFn>>>,
+    vetoes: RwLock<Vec<Watch<VetoFn>>>,
+    next_id: AtomicU64,
+}
+
+impl Observers {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            sender,
+            callbacks: RwLock::new(Vec::new()),
+            vetoes: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    fn next_id(&self) -> ObserverId {
+        ObserverId(self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub(crate) fn subscribe(&self, table: RegisterTable, range: RangeInclusive<u16>) -> ChangeSubscription {
+        ChangeSubscription { receiver: self.sender.subscribe(), table, range }
+    }
+
+    pub(crate) fn add_callback(&self, table: RegisterTable, range: RangeInclusive<u16>, handler: Callback) -> ObserverId {
+        let id = self.next_id();
+        self.callbacks.write().unwrap().push(Watch { id, table, range, handler });
+        id
+    }
+
+    pub(crate) fn add_veto(&self, table: RegisterTable, range: RangeInclusive<u16>, handler: Veto) -> ObserverId {
+        let id = self.next_id();
+        self.vetoes.write().unwrap().push(Watch { id, table, range, handler });
+        id
+    }
+
+    pub(crate) fn remove(&self, id: ObserverId) -> bool {
+        let mut callbacks = self.callbacks.write().unwrap();
+        let mut vetoes = self.vetoes.write().unwrap();
+        let before = callbacks.len() + vetoes.len();
+        callbacks.retain(|watch| watch.id != id);
+        vetoes.retain(|watch| watch.id != id);
+        callbacks.len() + vetoes.len() != before
+    }
+
+    /// Check whether writes need to build change events at all
+    pub(crate) fn is_active(&self) -> bool {
+        self.sender.receiver_count() > 0
+            || !self.callbacks.read().unwrap().is_empty()
+            || !self.vetoes.read().unwrap().is_empty()
+    }
+
+    /// Run the vetoes watching a pending write
+    pub(crate) fn check(&self, event: &ChangeEvent) -> ModbusResult<()> {
+        let vetoes: Vec<(ChangeEvent, Veto)> = self
+            .vetoes
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|watch| Some((event.restrict(watch.table, &watch.range)?, watch.handler.clone())))
+            .collect();
+        for (part, veto) in vetoes {
+            if !veto(&part) {
+                return Err(ModbusError::invalid_data(format!(
+                    "Write of {} {} value(s) at {} rejected",
+                    part.len(),
+                    part.table,
+                    part.address
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Deliver a completed write to callbacks and subscribers
+    pub(crate) fn notify(&self, event: ChangeEvent) {
+        let callbacks: Vec<(ChangeEvent, Callback)> = self
+            .callbacks
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|watch| Some((event.restrict(watch.table, &watch.range)?, watch.handler.clone())))
+            .collect();
+        for (part, callback) in callbacks {
+            callback(&part);
+        }
+        // No receivers is not an error
+        let _ = self.sender.send(event);
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("subscribers", &self.sender.receiver_count())
+            .field("callbacks", &self.callbacks.read().map(|c| c.len()).unwrap_or(0))
+            .field("vetoes", &self.vetoes.read().map(|v| v.len()).unwrap_or(0))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restrict_to_range() {
+        let event = ChangeEvent {
+            table: RegisterTable::HoldingRegister,
+            address: 10,
+            old: PointValue::Registers(vec![0, 0, 0, 0]),
+            new: PointValue::Registers(vec![1, 2, 3, 4]),
+            context: WriteContext::remote(1, None),
+            long: false,
+        };
+
+        let part = event.restrict(RegisterTable::HoldingRegister, &(12..=20)).unwrap();
+        assert_eq!((part.address, part.new), (12, PointValue::Registers(vec![3, 4])));
+        assert_eq!(event.restrict(RegisterTable::HoldingRegister, &(0..=100)).unwrap(), event);
+        assert!(event.restrict(RegisterTable::HoldingRegister, &(14..=20)).is_none());
+        assert!(event.restrict(RegisterTable::InputRegister, &(0..=100)).is_none());
+        assert!(event.is_changed());
+        assert!(event.context.is_remote());
+
+        // 32-bit writes slice whole register pairs
+        let long = ChangeEvent { long: true, ..event };
+        assert_eq!(long.len(), 2);
+        let part = long.restrict(RegisterTable::HoldingRegister, &(11..=20)).unwrap();
+        assert_eq!((part.address, part.new), (11, PointValue::Registers(vec![3, 4])));
+    }
+}
@@ -0,0 +1,456 @@
+//! # Register Bank Persistence
+//!
+//! [`Persistence`] keeps a [`ModbusRegisterBank`] on disk so an emulated
+//! device comes back with its setpoints after a restart:
+//!
+//! - **Restore**: on start, the bank is loaded from the snapshot file and the
+//!   journal is replayed on top of it
+//! - **Autosave**: after any change, the bank is saved as a
+//!   [`BankSnapshot`] once no further change arrived for the debounce time,
+//!   or at the latest after the maximum delay when changes keep coming
+//! - **Journal**: optionally, every coil and holding register write made by a
+//!   Modbus master is appended to a write-ahead journal before it is applied,
+//!   so it survives a crash between two snapshots
+//!
+//! Each saved snapshot checkpoints the journal: records it already covers are
+//! dropped. Journal records are JSON lines; a record torn by a crash is
+//! ignored on replay.
+//!
+//! The journal is written from a write veto
+//! ([`add_write_veto`](ModbusRegisterBank::add_write_veto)), so start
+//! persistence after registering other vetoes; writes that fail to reach the
+//! journal are rejected with exception 0x03.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use voltage_modbus::ModbusRegisterBank;
+//! use voltage_modbus::persistence::{Persistence, PersistenceConfig};
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let bank = ModbusRegisterBank::new();
+//! let config = PersistenceConfig::new("inverter.bin")
+//!     .with_debounce(Duration::from_secs(2))
+//!     .with_journal("inverter.journal");
+//! let persistence = Persistence::start(&bank, config)?;
+//!
+//! // ... serve the bank ...
+//!
+//! persistence.stop()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::address::DataAddress;
+use crate::error::{ModbusError, ModbusResult};
+use crate::observer::{ChangeEvent, ObserverId};
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::register_bank::ModbusRegisterBank;
+use crate::snapshot::BankSnapshot;
+
+/// Persistence configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersistenceConfig {
+    /// Snapshot file; the extension selects the format
+    pub snapshot_path: PathBuf,
+    /// Quiet time after the last change before the snapshot is saved
+    pub debounce: Duration,
+    /// Longest time from the first unsaved change to the save, however often the bank changes
+    pub max_delay: Duration,
+    /// Write-ahead journal of writes made by Modbus masters
+    pub journal_path: Option<PathBuf>,
+    /// Flush each journal record to disk before applying the write
+    pub sync_journal: bool,
+}
+
+impl PersistenceConfig {
+    /// Autosave to `snapshot_path` one second after the last change, without a journal
+    ///
+    /// A bank that never stays quiet is saved every ten seconds.
+    pub fn new<P: Into<PathBuf>>(snapshot_path: P) -> Self {
+        Self {
+            snapshot_path: snapshot_path.into(),
+            debounce: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            journal_path: None,
+            sync_journal: true,
+        }
+    }
+
+    /// Set the quiet time before saving
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Set the longest delay from the first unsaved change to the save
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Journal writes made by Modbus masters to `path`
+    pub fn with_journal<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.journal_path = Some(path.into());
+        self
+    }
+}
+
+/// One journaled write
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct JournalRecord {
+    table: RegisterTable,
+    address: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    registers: Vec<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bits: Vec<bool>,
+}
+
+impl JournalRecord {
+    fn value(&self) -> PointValue {
+        if self.table.is_bit_table() {
+            PointValue::Bits(self.bits.clone())
+        } else {
+            PointValue::Registers(self.registers.clone())
+        }
+    }
+}
+
+/// Append-only journal file
+#[derive(Debug)]
+struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+    sync: bool,
+}
+
+impl Journal {
+    fn open(path: &Path, sync: bool) -> ModbusResult<Self> {
+        Ok(Self { path: path.to_path_buf(), file: Mutex::new(open_append(path)?), sync })
+    }
+
+    fn lock(&self) -> ModbusResult<std::sync::MutexGuard<'_, File>> {
+        self.file.lock().map_err(|_| ModbusError::internal("Failed to lock journal"))
+    }
+
+    fn append(&self, event: &ChangeEvent) -> ModbusResult<()> {
+        let (registers, bits) = match &event.new {
+            PointValue::Registers(registers) => (registers.clone(), Vec::new()),
+            PointValue::Bits(bits) => (Vec::new(), bits.clone()),
+        };
+        let record = JournalRecord { table: event.table, address: event.address, registers, bits };
+        let mut line = serde_json::to_vec(&record).map_err(|e| ModbusError::internal(format!("Cannot encode journal record: {}", e)))?;
+        line.push(b'\n');
+
+        let mut file = self.lock()?;
+        file.write_all(&line).map_err(|e| self.io_error(e))?;
+        if self.sync {
+            file.sync_data().map_err(|e| self.io_error(e))?;
+        }
+        Ok(())
+    }
+
+    /// Current end of the journal
+    fn position(&self) -> ModbusResult<u64> {
+        let file = self.lock()?;
+        Ok(file.metadata().map_err(|e| self.io_error(e))?.len())
+    }
+
+    /// Drop the records before `position`
+    fn truncate_front(&self, position: u64) -> ModbusResult<()> {
+        let mut file = self.lock()?;
+        let mut rest = Vec::new();
+        let mut reader = File::open(&self.path).map_err(|e| self.io_error(e))?;
+        reader.seek(SeekFrom::Start(position)).map_err(|e| self.io_error(e))?;
+        reader.read_to_end(&mut rest).map_err(|e| self.io_error(e))?;
+
+        let mut temp = self.path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let mut rewritten = File::create(&temp).map_err(|e| self.io_error(e))?;
+        rewritten.write_all(&rest).map_err(|e| self.io_error(e))?;
+        rewritten.sync_all().map_err(|e| self.io_error(e))?;
+        drop(rewritten);
+        std::fs::rename(&temp, &self.path).map_err(|e| self.io_error(e))?;
+        *file = open_append(&self.path)?;
+        Ok(())
+    }
+
+    /// Apply the journaled writes to `bank`
+    ///
+    /// # Returns
+    ///
+    /// Number of records replayed
+    fn replay(path: &Path, bank: &ModbusRegisterBank) -> ModbusResult<usize> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(ModbusError::io(format!("Cannot read {}: {}", path.display(), e))),
+        };
+
+        let mut count = 0;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| ModbusError::io(format!("Cannot read {}: {}", path.display(), e)))?;
+            let record: JournalRecord = match serde_json::from_str(&line) {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!("Ignoring torn journal record in {}: {}", path.display(), e);
+                    break;
+                }
+            };
+            bank.write_at(DataAddress::new(record.table, record.address), &record.value())?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn io_error(&self, e: std::io::Error) -> ModbusError {
+        ModbusError::io(format!("Journal {}: {}", self.path.display(), e))
+    }
+}
+
+fn open_append(path: &Path) -> ModbusResult<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| ModbusError::io(format!("Cannot open {}: {}", path.display(), e)))
+}
+
+/// State shared with the autosave task
+#[derive(Debug)]
+struct Shared {
+    bank: ModbusRegisterBank,
+    snapshot_path: PathBuf,
+    journal: Option<Arc<Journal>>,
+    changed: Notify,
+}
+
+impl Shared {
+    /// Save a snapshot and drop the journal records it covers
+    fn checkpoint(&self) -> ModbusResult<()> {
+        // Records before this position passed their veto; the table snapshot
+        // waits for such writes to be applied, with either storage backend
+        let position = match &self.journal {
+            Some(journal) => Some(journal.position()?),
+            None => None,
+        };
+        self.bank.snapshot()?.save(&self.snapshot_path)?;
+        if let (Some(journal), Some(position)) = (&self.journal, position) {
+            journal.truncate_front(position)?;
+        }
+        Ok(())
+    }
+}
+
+/// Running persistence of one register bank
+///
+/// Dropping it stops autosaving without a final save; use
+/// [`stop`](Self::stop) for a clean shutdown.
+#[derive(Debug)]
+pub struct Persistence {
+    shared: Arc<Shared>,
+    observers: Vec<ObserverId>,
+    task: JoinHandle<()>,
+}
+
+impl Persistence {
+    /// Restore `bank` from disk and start persisting it
+    ///
+    /// Must be called within a tokio runtime.
+    ///
+    /// # Returns
+    ///
+    /// The running persistence, or an error if the snapshot or journal cannot
+    /// be read
+    pub fn start(bank: &ModbusRegisterBank, config: PersistenceConfig) -> ModbusResult<Self> {
+        let bank = bank.protocol_view();
+        if config.snapshot_path.exists() {
+            bank.restore(&BankSnapshot::load(&config.snapshot_path)?)?;
+        }
+        let journal = match &config.journal_path {
+            Some(path) => {
+                Journal::replay(path, &bank)?;
+                Some(Arc::new(Journal::open(path, config.sync_journal)?))
+            }
+            None => None,
+        };
+
+        let shared = Arc::new(Shared { bank: bank.clone(), snapshot_path: config.snapshot_path, journal, changed: Notify::new() });
+        shared.checkpoint()?;
+
+        let mut observers = Vec::new();
+        if let Some(journal) = &shared.journal {
+            for table in [RegisterTable::Coil, RegisterTable::HoldingRegister] {
+                let journal = journal.clone();
+                observers.push(bank.add_write_veto(table, 0..=u16::MAX, move |event| {
+                    if !event.context.is_remote() {
+                        return true;
+                    }
+                    match journal.append(event) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            error!("Rejecting write that could not be journaled: {}", e);
+                            false
+                        }
+                    }
+                }));
+            }
+        }
+        for table in [RegisterTable::Coil, RegisterTable::DiscreteInput, RegisterTable::HoldingRegister, RegisterTable::InputRegister] {
+            let shared = shared.clone();
+            observers.push(bank.on_change(table, 0..=u16::MAX, move |_| shared.changed.notify_one()));
+        }
+
+        let task = tokio::spawn(Self::autosave(shared.clone(), config.debounce, config.max_delay));
+        Ok(Self { shared, observers, task })
+    }
+
+    /// Save once no change arrived for `debounce`, or `max_delay` after the first change
+    async fn autosave(shared: Arc<Shared>, debounce: Duration, max_delay: Duration) {
+        loop {
+            shared.changed.notified().await;
+            let deadline = Instant::now() + max_delay;
+            while tokio::time::timeout_at((Instant::now() + debounce).min(deadline), shared.changed.notified()).await.is_ok() {}
+
+            let saving = shared.clone();
+            match tokio::task::spawn_blocking(move || saving.checkpoint()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => error!("Failed to save register bank snapshot: {}", e),
+                Err(e) => error!("Register bank snapshot task failed: {}", e),
+            }
+        }
+    }
+
+    /// Save a snapshot now
+    pub fn checkpoint(&self) -> ModbusResult<()> {
+        self.shared.checkpoint()
+    }
+
+    /// Stop persisting after a final save
+    pub fn stop(self) -> ModbusResult<()> {
+        self.task.abort();
+        for &id in &self.observers {
+            self.shared.bank.remove_observer(id);
+        }
+        self.shared.checkpoint()
+    }
+}
+
+impl Drop for Persistence {
+    fn drop(&mut self) {
+        self.task.abort();
+        for &id in &self.observers {
+            self.shared.bank.remove_observer(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::WriteContext;
+    use crate::storage::StorageBackend;
+
+    #[tokio::test]
+    async fn test_journal_survives_crash() {
+        let dir = std::env::temp_dir().join(format!("voltage_modbus_persistence_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = PersistenceConfig::new(dir.join("bank.json"))
+            .with_debounce(Duration::from_millis(20))
+            .with_journal(dir.join("bank.journal"));
+
+        let bank = ModbusRegisterBank::new();
+        let persistence = Persistence::start(&bank, config.clone()).unwrap();
+        bank.set_input_register(0, 7).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(BankSnapshot::load(dir.join("bank.json")).unwrap(), bank.snapshot().unwrap());
+
+        // A master's setpoint reaches the journal; the process dies before the next save
+        let master = bank.clone().with_write_context(WriteContext::remote(1, None));
+        master.write_10(100, &[2300, 50]).unwrap();
+        drop(persistence);
+        assert!(std::fs::read_to_string(dir.join("bank.journal")).unwrap().contains("2300"));
+
+        let restarted = ModbusRegisterBank::new();
+        let persistence = Persistence::start(&restarted, config).unwrap();
+        assert_eq!(restarted.read_03(100, 2).unwrap(), vec![2300, 50]);
+        assert_eq!(restarted.read_04(0, 1).unwrap(), vec![7]);
+        persistence.stop().unwrap();
+        assert!(std::fs::read_to_string(dir.join("bank.journal")).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_keeps_journaled_dense_write() {
+        let dir = std::env::temp_dir().join(format!("voltage_modbus_dense_checkpoint_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = PersistenceConfig::new(dir.join("bank.json"))
+            .with_debounce(Duration::from_secs(60))
+            .with_journal(dir.join("bank.journal"));
+
+        let bank = ModbusRegisterBank::with_backend(StorageBackend::Dense);
+        let persistence = Persistence::start(&bank, config.clone()).unwrap();
+
+        // Hold the write between its journal record and its publication
+        let (journaled, wait) = std::sync::mpsc::channel();
+        bank.add_write_veto(RegisterTable::HoldingRegister, 100..=100, move |_| {
+            let _ = journaled.send(());
+            std::thread::sleep(Duration::from_millis(100));
+            true
+        });
+        let master = bank.clone().with_write_context(WriteContext::remote(1, None));
+        let writer = std::thread::spawn(move || master.write_06(100, 2300).unwrap());
+        wait.recv().unwrap();
+        persistence.checkpoint().unwrap();
+        writer.join().unwrap();
+
+        // Crash: no final save
+        drop(persistence);
+        let restarted = ModbusRegisterBank::with_backend(StorageBackend::Dense);
+        let persistence = Persistence::start(&restarted, config).unwrap();
+        assert_eq!(restarted.read_03(100, 1).unwrap(), vec![2300]);
+        persistence.stop().unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_delay_saves_busy_bank() {
+        let dir = std::env::temp_dir().join(format!("voltage_modbus_max_delay_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = PersistenceConfig::new(dir.join("bank.json"))
+            .with_debounce(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(150));
+
+        let bank = ModbusRegisterBank::new();
+        let persistence = Persistence::start(&bank, config).unwrap();
+
+        // Changes every 10 ms never leave the bank quiet for the debounce time
+        for value in 1..=40 {
+            bank.set_input_register(0, value).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let saved = ModbusRegisterBank::new();
+        saved.restore(&BankSnapshot::load(dir.join("bank.json")).unwrap()).unwrap();
+        assert_ne!(saved.read_04(0, 1).unwrap(), vec![0]);
+
+        persistence.stop().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,426 @@
+//! # Read Request Planner
+//!
+//! Polling a device point by point wastes most of the bus time on request
+//! overhead. The [`ReadPlanner`] takes the set of points an application needs
+//! and coalesces them into as few read requests as possible:
+//!
+//! - Points of the same function whose ranges overlap or lie within
+//!   `max_gap` addresses of each other share one request
+//! - No request exceeds `max_registers` / `max_bits`
+//! - Gaps are never bridged across [excluded ranges](PlannerConfig::excluded),
+//!   for devices that answer reads of unmapped holes with an exception
+//!
+//! The resulting [`ReadPlan`] executes the block reads and hands every point
+//! its own slice of the data, in the order the points were given.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusTcpClient, ReadPlanner, PlannerConfig, ReadPoint};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let mut client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//!
+//! let planner = ReadPlanner::new(PlannerConfig { max_gap: 4, ..Default::default() });
+//! let plan = planner.plan(&[
+//!     ReadPoint::holding(0, 2),   // voltage
+//!     ReadPoint::holding(2, 2),   // current
+//!     ReadPoint::holding(8, 1),   // status
+//!     ReadPoint::input(100, 4),   // energy
+//! ])?;
+//! assert_eq!(plan.blocks().len(), 2);
+//!
+//! let values = plan.execute(&mut client, 1).await?;
+//! println!("status = {:?}", values[2].as_registers());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::ModbusClient;
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusFunction, SlaveId};
+
+/// A value range the application wants to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadPoint {
+    /// Read function (0x01 - 0x04)
+    pub function: ModbusFunction,
+    /// Start address
+    pub address: u16,
+    /// Number of registers or bits
+    pub length: u16,
+}
+
+impl ReadPoint {
+    /// Create a read point
+    pub fn new(function: ModbusFunction, address: u16, length: u16) -> Self {
+        Self { function, address, length }
+    }
+
+    /// Coils (0x01)
+    pub fn coil(address: u16, length: u16) -> Self {
+        Self::new(ModbusFunction::ReadCoils, address, length)
+    }
+
+    /// Discrete inputs (0x02)
+    pub fn discrete_input(address: u16, length: u16) -> Self {
+        Self::new(ModbusFunction::ReadDiscreteInputs, address, length)
+    }
+
+    /// Holding registers (0x03)
+    pub fn holding(address: u16, length: u16) -> Self {
+        Self::new(ModbusFunction::ReadHoldingRegisters, address, length)
+    }
+
+    /// Input registers (0x04)
+    pub fn input(address: u16, length: u16) -> Self {
+        Self::new(ModbusFunction::ReadInputRegisters, address, length)
+    }
+
+    /// One past the last address
+    fn end(&self) -> u32 {
+        self.address as u32 + self.length as u32
+    }
+}
+
+/// Address range that must never be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExcludedRange {
+    /// Function whose address space the range belongs to
+    pub function: ModbusFunction,
+    /// First excluded address
+    pub start: u16,
+    /// Number of excluded addresses
+    pub count: u16,
+}
+
+impl ExcludedRange {
+    /// Create an excluded range
+    pub fn new(function: ModbusFunction, start: u16, count: u16) -> Self {
+        Self { function, start, count }
+    }
+
+    /// Check whether the half-open address range `[start, end)` touches this range
+    fn overlaps(&self, function: ModbusFunction, start: u32, end: u32) -> bool {
+        let own_end = self.start as u32 + self.count as u32;
+        self.function == function && start < own_end && (self.start as u32) < end
+    }
+}
+
+/// Planner configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannerConfig {
+    /// Largest number of unrequested registers/bits read to join two points
+    pub max_gap: u16,
+    /// Maximum registers per request (0x03, 0x04)
+    pub max_registers: u16,
+    /// Maximum bits per request (0x01, 0x02)
+    pub max_bits: u16,
+    /// Ranges that must not be covered by any request
+    pub excluded: Vec<ExcludedRange>,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            max_gap: 0,
+            max_registers: crate::MAX_REGISTERS_PER_REQUEST,
+            max_bits: crate::MAX_COILS_PER_REQUEST,
+            excluded: Vec::new(),
+        }
+    }
+}
+
+/// A single read request of a plan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadBlock {
+    /// Read function
+    pub function: ModbusFunction,
+    /// Start address
+    pub address: u16,
+    /// Number of registers or bits
+    pub quantity: u16,
+    /// Indices of the points served by this block
+    pub points: Vec<usize>,
+}
+
+/// Data returned for a block or a point
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointValue {
+    /// Register values (0x03, 0x04)
+    Registers(Vec<u16>),
+    /// Bit values (0x01, 0x02)
+    Bits(Vec<bool>),
+}
+
+impl PointValue {
+    /// Register values, if this is register data
+    pub fn as_registers(&self) -> Option<&[u16]> {
+        match self {
+            Self::Registers(values) => Some(values),
+            Self::Bits(_) => None,
+        }
+    }
+
+    /// Bit values, if this is bit data
+    pub fn as_bits(&self) -> Option<&[bool]> {
+        match self {
+            Self::Bits(values) => Some(values),
+            Self::Registers(_) => None,
+        }
+    }
+
+    /// Extract `length` items starting at `offset`
+    fn slice(&self, offset: usize, length: usize) -> Option<Self> {
+        match self {
+            Self::Registers(values) => values.get(offset..offset + length).map(|v| Self::Registers(v.to_vec())),
+            Self::Bits(values) => values.get(offset..offset + length).map(|v| Self::Bits(v.to_vec())),
+        }
+    }
+}
+
+/// Coalesces read points into block requests
+#[derive(Debug, Clone, Default)]
+pub struct ReadPlanner {
+    config: PlannerConfig,
+}
+
+impl ReadPlanner {
+    /// Create a planner with the given configuration
+    pub fn new(config: PlannerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &PlannerConfig {
+        &self.config
+    }
+
+    /// Plan the reads for a set of points
+    ///
+    /// # Returns
+    ///
+    /// The plan, or an error if a point is not readable, exceeds the block
+    /// size or lies in an excluded range
+    pub fn plan(&self, points: &[ReadPoint]) -> ModbusResult<ReadPlan> {
+        for point in points {
+            self.validate(point)?;
+        }
+
+        // Sort by function, then address, remembering the original positions
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by_key(|&i| (points[i].function.to_u8(), points[i].address, points[i].length));
+
+        let mut blocks: Vec<ReadBlock> = Vec::new();
+        let mut current: Option<(ReadBlock, u32)> = None;
+
+        for index in order {
+            let point = points[index];
+            if let Some((block, end)) = current.as_mut() {
+                if self.can_extend(block, *end, &point) {
+                    *end = (*end).max(point.end());
+                    block.quantity = (*end - block.address as u32) as u16;
+                    block.points.push(index);
+                    continue;
+                }
+                blocks.push(block.clone());
+            }
+
+            current = Some((
+                ReadBlock {
+                    function: point.function,
+                    address: point.address,
+                    quantity: point.length,
+                    points: vec![index],
+                },
+                point.end(),
+            ));
+        }
+        if let Some((block, _)) = current {
+            blocks.push(block);
+        }
+
+        Ok(ReadPlan {
+            points: points.to_vec(),
+            blocks,
+        })
+    }
+
+    fn max_block(&self, function: ModbusFunction) -> u16 {
+        match function {
+            ModbusFunction::ReadCoils | ModbusFunction::ReadDiscreteInputs => self.config.max_bits,
+            _ => self.config.max_registers,
+        }
+    }
+
+    fn validate(&self, point: &ReadPoint) -> ModbusResult<()> {
+        if !point.function.is_read_function() {
+            return Err(ModbusError::invalid_function(point.function.to_u8()));
+        }
+        if point.length == 0 || point.length > self.max_block(point.function) || point.end() > 0x10000 {
+            return Err(ModbusError::invalid_address(point.address, point.length));
+        }
+        if self.config.excluded.iter().any(|range| range.overlaps(point.function, point.address as u32, point.end())) {
+            return Err(ModbusError::configuration(format!(
+                "Point at {} (length {}) lies in an excluded range",
+                point.address, point.length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check whether a point can join the block ending at `end`
+    fn can_extend(&self, block: &ReadBlock, end: u32, point: &ReadPoint) -> bool {
+        if point.function != block.function {
+            return false;
+        }
+        if point.address as u32 > end + self.config.max_gap as u32 {
+            return false;
+        }
+        let new_end = end.max(point.end());
+        if new_end - block.address as u32 > self.max_block(block.function) as u32 {
+            return false;
+        }
+        // Only the bridged gap can touch an excluded range; the points themselves were validated
+        !self.config.excluded.iter().any(|range| range.overlaps(block.function, end, point.address as u32))
+    }
+}
+
+/// Block requests for a set of points
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadPlan {
+    points: Vec<ReadPoint>,
+    blocks: Vec<ReadBlock>,
+}
+
+impl ReadPlan {
+    /// The planned points, in the order they were given
+    pub fn points(&self) -> &[ReadPoint] {
+        &self.points
+    }
+
+    /// The block requests
+    pub fn blocks(&self) -> &[ReadBlock] {
+        &self.blocks
+    }
+
+    /// Read every block, continuing after failures
+    ///
+    /// # Returns
+    ///
+    /// One result per block, in block order
+    pub async fn read_blocks<C: ModbusClient + ?Sized>(&self, client: &mut C, slave_id: SlaveId) -> Vec<ModbusResult<PointValue>> {
+        let mut results = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            let result = match block.function {
+                ModbusFunction::ReadCoils => client.read_01(slave_id, block.address, block.quantity).await.map(PointValue::Bits),
+                ModbusFunction::ReadDiscreteInputs => client.read_02(slave_id, block.address, block.quantity).await.map(PointValue::Bits),
+                ModbusFunction::ReadHoldingRegisters => client.read_03(slave_id, block.address, block.quantity).await.map(PointValue::Registers),
+                ModbusFunction::ReadInputRegisters => client.read_04(slave_id, block.address, block.quantity).await.map(PointValue::Registers),
+                function => Err(ModbusError::invalid_function(function.to_u8())),
+            };
+            results.push(result.map_err(|e| ModbusError::chunk_failed(block.address, block.quantity, e)));
+        }
+        results
+    }
+
+    /// Split block results back into per-point values
+    ///
+    /// # Arguments
+    ///
+    /// * `block_results` - One result per block, as returned by [`read_blocks`](Self::read_blocks)
+    ///
+    /// # Returns
+    ///
+    /// One result per point, in the order the points were given. Points of a
+    /// failed block receive that block's error.
+    pub fn demux(&self, block_results: &[ModbusResult<PointValue>]) -> Vec<ModbusResult<PointValue>> {
+        let mut values: Vec<ModbusResult<PointValue>> = self
+            .points
+            .iter()
+            .map(|_| Err(ModbusError::internal("Point not covered by the plan")))
+            .collect();
+
+        for (block, result) in self.blocks.iter().zip(block_results) {
+            for &index in &block.points {
+                let point = &self.points[index];
+                values[index] = match result {
+                    Ok(data) => data
+                        .slice((point.address - block.address) as usize, point.length as usize)
+                        .ok_or_else(|| ModbusError::frame("Block response shorter than requested")),
+                    Err(error) => Err(error.clone()),
+                };
+            }
+        }
+
+        values
+    }
+
+    /// Read all blocks and return per-point values, failing on the first block error
+    pub async fn execute<C: ModbusClient + ?Sized>(&self, client: &mut C, slave_id: SlaveId) -> ModbusResult<Vec<PointValue>> {
+        let mut block_results = Vec::with_capacity(self.blocks.len());
+        for result in self.read_blocks(client, slave_id).await {
+            block_results.push(Ok(result?));
+        }
+        self.demux(&block_results).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GenericModbusClient;
+    use crate::test_utils::ScriptedTransport;
+
+    #[test]
+    fn test_coalescing_rules() {
+        let planner = ReadPlanner::new(PlannerConfig {
+            max_gap: 3,
+            max_registers: 20,
+            excluded: vec![ExcludedRange::new(ModbusFunction::ReadHoldingRegisters, 40, 5)],
+            ..Default::default()
+        });
+
+        let plan = planner.plan(&[
+            ReadPoint::holding(10, 2),
+            ReadPoint::holding(0, 4),
+            ReadPoint::holding(6, 2),  // gap of 2 after [0, 4): joined
+            ReadPoint::holding(12, 1), // adjacent: joined
+            ReadPoint::holding(17, 4), // would exceed 20 registers: new block
+            ReadPoint::holding(38, 2), // gap to 45 crosses the excluded range
+            ReadPoint::holding(45, 1),
+            ReadPoint::input(0, 1),    // different function
+        ]).unwrap();
+
+        let ranges: Vec<(u8, u16, u16)> = plan.blocks().iter()
+            .map(|b| (b.function.to_u8(), b.address, b.quantity))
+            .collect();
+        assert_eq!(ranges, vec![(3, 0, 13), (3, 17, 4), (3, 38, 2), (3, 45, 1), (4, 0, 1)]);
+        assert_eq!(plan.blocks()[0].points, vec![1, 2, 0, 3]);
+
+        assert!(planner.plan(&[ReadPoint::holding(41, 1)]).is_err());
+        assert!(planner.plan(&[ReadPoint::holding(0, 21)]).is_err());
+        assert!(planner.plan(&[ReadPoint::new(ModbusFunction::WriteSingleRegister, 0, 1)]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_demultiplexes_in_point_order() {
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[10, 11, 12, 13, 14, 15]);
+        let mut client = GenericModbusClient::new(transport.clone());
+
+        let planner = ReadPlanner::new(PlannerConfig { max_gap: 2, ..Default::default() });
+        let plan = planner.plan(&[ReadPoint::holding(104, 2), ReadPoint::holding(100, 1), ReadPoint::holding(101, 2)]).unwrap();
+        let values = plan.execute(&mut client, 1).await.unwrap();
+
+        assert_eq!(transport.request_count(), 1);
+        assert_eq!(values[0].as_registers().unwrap(), &[14, 15]);
+        assert_eq!(values[1].as_registers().unwrap(), &[10]);
+        assert_eq!(values[2].as_registers().unwrap(), &[11, 12]);
+
+        // A failed block is reported for each of its points with its range
+        let results = plan.demux(&plan.read_blocks(&mut client, 1).await);
+        assert!(results.iter().all(|r| matches!(r, Err(ModbusError::ChunkFailed { start: 100, count: 6, .. }))));
+    }
+}
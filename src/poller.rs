@@ -0,0 +1,565 @@
+//! # Periodic Polling Engine
+//!
+//! Most Modbus applications boil down to "read these blocks every N ms and
+//! tell me what changed". The [`Poller`] does exactly that on top of any
+//! [`ModbusClient`]:
+//!
+//! - **Poll groups** with independent intervals, each planned into as few
+//!   requests as possible with the [`ReadPlanner`]
+//! - **No overlap**: groups run one after another on the shared client; a
+//!   group that falls behind skips missed cycles instead of piling up
+//! - **Samples** for every point on every cycle, timestamped and flagged
+//!   with a [`Quality`]
+//! - **Change events** only when a value moves beyond its deadband or the
+//!   quality changes
+//!
+//! Both feeds are `tokio::sync::broadcast` channels and are also available
+//! as `futures` streams.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusTcpClient, Poller, PollGroup, PollPoint, ReadPoint};
+//! use futures::StreamExt;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//!
+//! let mut poller = Poller::new(client);
+//! poller.add_group(PollGroup::new("fast", 1, Duration::from_millis(200))
+//!     .with_point(PollPoint::new("frequency", ReadPoint::holding(0, 1)).with_deadband(2.0)))?;
+//! poller.add_group(PollGroup::new("slow", 1, Duration::from_secs(5))
+//!     .with_point(PollPoint::new("energy", ReadPoint::input(100, 2))))?;
+//!
+//! let handle = poller.spawn();
+//! let mut changes = Box::pin(handle.change_stream());
+//! while let Some(event) = changes.next().await {
+//!     println!("{} {} = {:?} ({:?})", event.timestamp, event.point, event.value, event.quality);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::client::ModbusClient;
+use crate::codec::RegisterOrder;
+use crate::error::{ModbusError, ModbusResult};
+use crate::planner::{PlannerConfig, PointValue, ReadPlan, ReadPlanner, ReadPoint};
+use crate::protocol::{ModbusFunction, SlaveId};
+use crate::tags::{decode_registers, DataType};
+
+/// Default capacity of the sample and change channels
+const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Quality of a polled value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quality {
+    /// Read successfully in this cycle
+    Good,
+    /// Read failed in this cycle; the value is the last good one, if any
+    Bad,
+}
+
+/// A named value to poll
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollPoint {
+    /// Point name, reported in samples
+    pub name: String,
+    /// Address range to read
+    pub point: ReadPoint,
+    /// Type the registers are decoded as before the deadband is applied
+    pub data_type: DataType,
+    /// Word and byte order of multi-register values
+    pub order: RegisterOrder,
+    /// Factor applied to the decoded value
+    pub scale: f64,
+    /// Minimum change of any engineering value (or any bit flip) that counts as a change
+    pub deadband: f64,
+}
+
+impl PollPoint {
+    /// Create a point without deadband
+    pub fn new(name: impl Into<String>, point: ReadPoint) -> Self {
+        Self {
+            name: name.into(),
+            point,
+            data_type: DataType::U16,
+            order: RegisterOrder::default(),
+            scale: 1.0,
+            deadband: 0.0,
+        }
+    }
+
+    /// Decode the registers as `data_type` in `order`
+    ///
+    /// A point longer than one value is treated as consecutive values of
+    /// the same type.
+    pub fn with_data_type(mut self, data_type: DataType, order: RegisterOrder) -> Self {
+        self.data_type = data_type;
+        self.order = order;
+        self
+    }
+
+    /// Multiply decoded values by `scale`
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Decode registers into scaled engineering values
+    fn engineering_values(&self, registers: &[u16]) -> Option<Vec<f64>> {
+        let count = self.data_type.register_count() as usize;
+        if !registers.len().is_multiple_of(count) {
+            return None;
+        }
+        registers
+            .chunks(count)
+            .map(|chunk| decode_registers(self.data_type, chunk, self.order).ok().map(|v| v * self.scale))
+            .collect()
+    }
+
+    /// Only report changes of the decoded, scaled value larger than `deadband`
+    pub fn with_deadband(mut self, deadband: f64) -> Self {
+        self.deadband = deadband.abs();
+        self
+    }
+}
+
+/// Points of one slave polled at a common interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollGroup {
+    /// Group name, reported in samples
+    pub name: String,
+    /// Slave to poll
+    pub slave_id: SlaveId,
+    /// Poll interval
+    pub interval: Duration,
+    /// Points to read
+    pub points: Vec<PollPoint>,
+}
+
+impl PollGroup {
+    /// Create an empty group
+    pub fn new(name: impl Into<String>, slave_id: SlaveId, interval: Duration) -> Self {
+        Self {
+            name: name.into(),
+            slave_id,
+            interval,
+            points: Vec::new(),
+        }
+    }
+
+    /// Add a point
+    pub fn with_point(mut self, point: PollPoint) -> Self {
+        self.points.push(point);
+        self
+    }
+}
+
+/// A timestamped value of a point
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollSample {
+    /// Group the point belongs to
+    pub group: String,
+    /// Point name
+    pub point: String,
+    /// Slave the point was read from
+    pub slave_id: SlaveId,
+    /// Time the cycle completed
+    pub timestamp: DateTime<Utc>,
+    /// Current value, or the last good value when the read failed
+    pub value: Option<PointValue>,
+    /// Quality of the value
+    pub quality: Quality,
+    /// Error of the failed read, for `Quality::Bad`
+    pub error: Option<ModbusError>,
+}
+
+/// Poller configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollerConfig {
+    /// How point reads are coalesced into requests
+    pub planner: PlannerConfig,
+    /// Capacity of the sample and change channels
+    pub channel_capacity: usize,
+}
+
+impl Default for PollerConfig {
+    fn default() -> Self {
+        Self {
+            planner: PlannerConfig::default(),
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+}
+
+/// Last reported state of a point, for change detection
+struct LastState {
+    value: Option<PointValue>,
+    quality: Quality,
+}
+
+struct ScheduledGroup {
+    group: PollGroup,
+    plan: ReadPlan,
+    next_due: Instant,
+}
+
+/// Polls groups of points over a client and publishes the results
+pub struct Poller<C: ModbusClient> {
+    client: C,
+    planner: ReadPlanner,
+    groups: Vec<ScheduledGroup>,
+    last: HashMap<(usize, usize), LastState>,
+    samples: broadcast::Sender<PollSample>,
+    changes: broadcast::Sender<PollSample>,
+}
+
+impl<C: ModbusClient + 'static> Poller<C> {
+    /// Create a poller with default configuration
+    pub fn new(client: C) -> Self {
+        Self::with_config(client, PollerConfig::default())
+    }
+
+    /// Create a poller with custom configuration
+    pub fn with_config(client: C, config: PollerConfig) -> Self {
+        let capacity = config.channel_capacity.max(1);
+        Self {
+            client,
+            planner: ReadPlanner::new(config.planner),
+            groups: Vec::new(),
+            last: HashMap::new(),
+            samples: broadcast::channel(capacity).0,
+            changes: broadcast::channel(capacity).0,
+        }
+    }
+
+    /// Add a poll group
+    ///
+    /// Fails if the group's points cannot be planned or the interval is zero.
+    pub fn add_group(&mut self, group: PollGroup) -> ModbusResult<()> {
+        if group.interval.is_zero() {
+            return Err(ModbusError::configuration(format!("Poll group '{}' has a zero interval", group.name)));
+        }
+
+        for point in &group.points {
+            let count = point.data_type.register_count();
+            let registers = matches!(point.point.function, ModbusFunction::ReadHoldingRegisters | ModbusFunction::ReadInputRegisters);
+            if registers && (point.data_type == DataType::Bool || !point.point.length.is_multiple_of(count)) {
+                return Err(ModbusError::configuration(format!(
+                    "Point '{}' length {} does not hold whole {:?} values",
+                    point.name, point.point.length, point.data_type
+                )));
+            }
+        }
+
+        let points: Vec<ReadPoint> = group.points.iter().map(|p| p.point).collect();
+        let plan = self.planner.plan(&points)?;
+        self.groups.push(ScheduledGroup {
+            group,
+            plan,
+            next_due: Instant::now(),
+        });
+        Ok(())
+    }
+
+    /// Subscribe to every sample of every cycle
+    pub fn subscribe(&self) -> broadcast::Receiver<PollSample> {
+        self.samples.subscribe()
+    }
+
+    /// Subscribe to change events only
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<PollSample> {
+        self.changes.subscribe()
+    }
+
+    /// Poll one group immediately and publish its samples
+    ///
+    /// # Returns
+    ///
+    /// The samples of this cycle, in point order
+    pub async fn poll_group(&mut self, index: usize) -> Vec<PollSample> {
+        let Some(scheduled) = self.groups.get(index) else { return Vec::new() };
+        let slave_id = scheduled.group.slave_id;
+        let block_results = scheduled.plan.read_blocks(&mut self.client, slave_id).await;
+
+        let scheduled = &self.groups[index];
+        let values = scheduled.plan.demux(&block_results);
+        let timestamp = Utc::now();
+
+        let mut samples = Vec::with_capacity(values.len());
+        for (point_index, (point, result)) in scheduled.group.points.iter().zip(values).enumerate() {
+            let last = self.last.get(&(index, point_index));
+            let (value, quality, error) = match result {
+                Ok(value) => (Some(value), Quality::Good, None),
+                Err(error) => (last.and_then(|state| state.value.clone()), Quality::Bad, Some(error)),
+            };
+
+            let changed = match last {
+                None => true,
+                Some(state) => state.quality != quality || exceeds_deadband(point, &state.value, &value),
+            };
+
+            let sample = PollSample {
+                group: scheduled.group.name.clone(),
+                point: point.name.clone(),
+                slave_id,
+                timestamp,
+                value,
+                quality,
+                error,
+            };
+
+            if changed {
+                self.last.insert((index, point_index), LastState {
+                    value: sample.value.clone(),
+                    quality,
+                });
+                let _ = self.changes.send(sample.clone());
+            }
+            let _ = self.samples.send(sample.clone());
+            samples.push(sample);
+        }
+
+        samples
+    }
+
+    /// Run the schedule until stopped, in a background task
+    pub fn spawn(self) -> PollerHandle<C> {
+        let samples = self.samples.clone();
+        let changes = self.changes.clone();
+        let (shutdown, stop) = watch::channel(false);
+        let task = tokio::spawn(self.run(stop));
+
+        PollerHandle {
+            samples,
+            changes,
+            shutdown,
+            task,
+        }
+    }
+
+    async fn run(mut self, mut stop: watch::Receiver<bool>) -> C {
+        if self.groups.is_empty() {
+            let _ = stop.changed().await;
+            return self.client;
+        }
+
+        loop {
+            let (index, due) = self
+                .groups
+                .iter()
+                .enumerate()
+                .map(|(i, g)| (i, g.next_due))
+                .min_by_key(|&(_, due)| due)
+                .expect("at least one group");
+
+            tokio::select! {
+                _ = tokio::time::sleep_until(due) => {}
+                _ = stop.changed() => break,
+            }
+
+            self.poll_group(index).await;
+
+            // Skip cycles that were missed while other groups were polled
+            let group = &mut self.groups[index];
+            let now = Instant::now();
+            group.next_due += group.group.interval;
+            if group.next_due < now {
+                group.next_due = now;
+            }
+        }
+
+        self.client
+    }
+}
+
+/// Check whether a point's engineering value moved beyond its deadband
+///
+/// Registers that cannot be decoded fall back to a raw comparison.
+fn exceeds_deadband(point: &PollPoint, old: &Option<PointValue>, new: &Option<PointValue>) -> bool {
+    let (Some(PointValue::Registers(old_raw)), Some(PointValue::Registers(new_raw))) = (old, new) else {
+        return old != new;
+    };
+    match (point.engineering_values(old_raw), point.engineering_values(new_raw)) {
+        (Some(old), Some(new)) if old.len() == new.len() => old.iter().zip(&new).any(|(a, b)| {
+            let delta = (a - b).abs();
+            if delta.is_nan() {
+                // NaN or infinities on either side: any bit change counts
+                a.to_bits() != b.to_bits()
+            } else if point.deadband > 0.0 {
+                delta > point.deadband
+            } else {
+                delta > 0.0
+            }
+        }),
+        _ => old_raw != new_raw,
+    }
+}
+
+/// Handle to a running [`Poller`]
+pub struct PollerHandle<C> {
+    samples: broadcast::Sender<PollSample>,
+    changes: broadcast::Sender<PollSample>,
+    shutdown: watch::Sender<bool>,
+    task: JoinHandle<C>,
+}
+
+impl<C> PollerHandle<C> {
+    /// Subscribe to every sample of every cycle
+    pub fn subscribe(&self) -> broadcast::Receiver<PollSample> {
+        self.samples.subscribe()
+    }
+
+    /// Subscribe to change events only
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<PollSample> {
+        self.changes.subscribe()
+    }
+
+    /// Every sample as a stream; samples missed by a slow consumer are skipped
+    pub fn sample_stream(&self) -> impl Stream<Item = PollSample> {
+        broadcast_stream(self.samples.subscribe())
+    }
+
+    /// Change events as a stream; events missed by a slow consumer are skipped
+    pub fn change_stream(&self) -> impl Stream<Item = PollSample> {
+        broadcast_stream(self.changes.subscribe())
+    }
+
+    /// Stop polling after the current cycle and get the client back
+    pub async fn stop(self) -> ModbusResult<C> {
+        let _ = self.shutdown.send(true);
+        self.task
+            .await
+            .map_err(|e| ModbusError::internal(format!("Poller task failed: {}", e)))
+    }
+}
+
+/// Adapt a broadcast receiver into a stream, skipping lagged items
+fn broadcast_stream(receiver: broadcast::Receiver<PollSample>) -> impl Stream<Item = PollSample> {
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(sample) => return Some((sample, receiver)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GenericModbusClient;
+    use crate::codec::RegisterValue;
+    use crate::test_utils::ScriptedTransport;
+
+    #[tokio::test]
+    async fn test_change_detection_with_deadband_and_quality() {
+        let transport = ScriptedTransport::new();
+        for values in [[100, 1], [103, 1], [106, 1]] {
+            transport.push_registers(ModbusFunction::ReadHoldingRegisters, &values);
+        }
+        // Fourth cycle: script exhausted, read times out
+
+        let mut poller = Poller::new(GenericModbusClient::new(transport.clone()));
+        poller.add_group(PollGroup::new("meter", 1, Duration::from_secs(1))
+            .with_point(PollPoint::new("power", ReadPoint::holding(0, 1)).with_deadband(5.0))
+            .with_point(PollPoint::new("state", ReadPoint::holding(1, 1)))).unwrap();
+        let mut changes = poller.subscribe_changes();
+
+        let first = poller.poll_group(0).await;
+        assert_eq!(first.len(), 2);
+        assert_eq!(transport.request_count(), 1);
+        assert_eq!(changes.try_recv().unwrap().point, "power");
+        assert_eq!(changes.try_recv().unwrap().point, "state");
+
+        // 103 is within the deadband of 100; 106 is not
+        poller.poll_group(0).await;
+        assert!(changes.try_recv().is_err());
+        poller.poll_group(0).await;
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.value, Some(PointValue::Registers(vec![106])));
+        assert!(changes.try_recv().is_err());
+
+        // Failed read keeps the last value with bad quality
+        let samples = poller.poll_group(0).await;
+        assert_eq!(samples[0].quality, Quality::Bad);
+        assert_eq!(samples[0].value, Some(PointValue::Registers(vec![106])));
+        assert_eq!(changes.try_recv().unwrap().quality, Quality::Bad);
+    }
+
+    #[tokio::test]
+    async fn test_deadband_applies_to_decoded_scaled_values() {
+        let transport = ScriptedTransport::new();
+        for (voltage, current) in [(230.0f32, -1i16), (230.05, 0), (230.5, 10)] {
+            let mut values = voltage.to_registers(RegisterOrder::ABCD);
+            values.extend(current.to_registers(RegisterOrder::ABCD));
+            transport.push_registers(ModbusFunction::ReadHoldingRegisters, &values);
+        }
+
+        let mut poller = Poller::new(GenericModbusClient::new(transport));
+        poller.add_group(PollGroup::new("meter", 1, Duration::from_secs(1))
+            .with_point(PollPoint::new("voltage", ReadPoint::holding(0, 2))
+                .with_data_type(DataType::F32, RegisterOrder::ABCD)
+                .with_deadband(0.1))
+            .with_point(PollPoint::new("current", ReadPoint::holding(2, 1))
+                .with_data_type(DataType::I16, RegisterOrder::ABCD)
+                .with_scale(0.1)
+                .with_deadband(0.5))).unwrap();
+        let mut changes = poller.subscribe_changes();
+
+        poller.poll_group(0).await;
+        assert_eq!(changes.try_recv().unwrap().point, "voltage");
+        assert_eq!(changes.try_recv().unwrap().point, "current");
+
+        // Raw registers change a lot, engineering values barely move
+        poller.poll_group(0).await;
+        assert!(changes.try_recv().is_err());
+
+        poller.poll_group(0).await;
+        assert_eq!(changes.try_recv().unwrap().point, "voltage");
+        assert_eq!(changes.try_recv().unwrap().point, "current");
+    }
+
+    #[test]
+    fn test_add_group_rejects_partial_values() {
+        let mut poller = Poller::new(GenericModbusClient::new(ScriptedTransport::new()));
+        let result = poller.add_group(PollGroup::new("meter", 1, Duration::from_secs(1))
+            .with_point(PollPoint::new("energy", ReadPoint::holding(0, 3))
+                .with_data_type(DataType::U32, RegisterOrder::ABCD)));
+        assert!(result.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_schedule_runs_groups_at_their_intervals() {
+        let transport = ScriptedTransport::new();
+        for _ in 0..32 {
+            transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[0]);
+        }
+
+        let mut poller = Poller::new(GenericModbusClient::new(transport.clone()));
+        poller.add_group(PollGroup::new("fast", 1, Duration::from_millis(100))
+            .with_point(PollPoint::new("a", ReadPoint::holding(0, 1)))).unwrap();
+        poller.add_group(PollGroup::new("slow", 2, Duration::from_millis(500))
+            .with_point(PollPoint::new("b", ReadPoint::holding(0, 1)))).unwrap();
+
+        let handle = poller.spawn();
+        tokio::time::sleep(Duration::from_millis(950)).await;
+        handle.stop().await.unwrap();
+
+        let requests = transport.requests.lock().unwrap();
+        let fast = requests.iter().filter(|r| r.slave_id == 1).count();
+        let slow = requests.iter().filter(|r| r.slave_id == 2).count();
+        assert_eq!((fast, slow), (10, 2));
+    }
+}
@@ -0,0 +1,576 @@
+//! # Pooled Modbus TCP Client
+//!
+//! A single Modbus TCP connection serves one request at a time. Gateways and
+//! PLCs that accept several connections can be polled much faster by spreading
+//! requests over a pool of connections.
+//!
+//! [`ModbusTcpPool`] keeps between `min_connections` and `max_connections`
+//! connections to one server:
+//!
+//! - Connections are opened on demand up to the maximum and reused afterwards
+//! - Callers wait at most `acquire_timeout` for a free connection
+//! - Idle connections are picked round-robin or by lowest average response time
+//! - An optional background health check probes idle connections with a
+//!   one-register read, reconnects broken ones, drops surplus dead ones and
+//!   tops the pool back up to the minimum
+//!
+//! The pool implements [`ModbusClient`] and is cheap to clone, so it can replace
+//! [`ModbusTcpClient`](crate::ModbusTcpClient) directly and be shared between tasks.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusClient, ModbusTcpPool, PoolConfig, PoolPolicy};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let config = PoolConfig {
+//!     min_connections: 2,
+//!     max_connections: 8,
+//!     policy: PoolPolicy::LeastBusy,
+//!     ..Default::default()
+//! };
+//! let pool = ModbusTcpPool::from_address("127.0.0.1:502", Duration::from_secs(1), config).await?;
+//!
+//! let mut tasks = Vec::new();
+//! for slave_id in 1..=8 {
+//!     let mut pool = pool.clone();
+//!     tasks.push(tokio::spawn(async move { pool.read_03(slave_id, 0, 10).await }));
+//! }
+//! for task in tasks {
+//!     println!("{:?}", task.await.unwrap());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::net::SocketAddr;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::client::{GenericModbusClient, ModbusClient};
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::SlaveId;
+use crate::retry::RetryPolicy;
+use crate::transport::{ModbusTransport, ReconnectConfig, TcpTransport, TransportStats};
+
+/// Strategy for choosing among idle connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolPolicy {
+    /// Rotate through the connections in order
+    #[default]
+    RoundRobin,
+    /// Prefer the connection with the lowest average response time
+    LeastBusy,
+}
+
+/// Request the health check sends over idle connections
+///
+/// Reads one holding register. An exception reply still proves the
+/// connection alive; a timeout or I/O error marks it broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthProbe {
+    /// Slave addressed by the probe
+    pub slave_id: SlaveId,
+    /// Holding register read by the probe
+    pub address: u16,
+}
+
+impl Default for HealthProbe {
+    fn default() -> Self {
+        Self { slave_id: 1, address: 0 }
+    }
+}
+
+/// Connection pool configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolConfig {
+    /// Connections opened up front and kept by the health check
+    pub min_connections: usize,
+    /// Upper bound for open connections (and concurrent requests)
+    pub max_connections: usize,
+    /// How long a request waits for a free connection
+    pub acquire_timeout: Duration,
+    /// Interval of the background health check (`None` = disabled)
+    pub health_check_interval: Option<Duration>,
+    /// Request sent over connected idle connections by the health check
+    /// (`None` = only reconnect connections already known to be broken)
+    pub health_probe: Option<HealthProbe>,
+    /// Strategy for choosing among idle connections
+    pub policy: PoolPolicy,
+    /// Reconnection behaviour of every connection
+    pub reconnect: ReconnectConfig,
+    /// Retry policy of every connection
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            min_connections: 1,
+            max_connections: 4,
+            acquire_timeout: Duration::from_secs(5),
+            health_check_interval: Some(Duration::from_secs(30)),
+            health_probe: Some(HealthProbe::default()),
+            policy: PoolPolicy::RoundRobin,
+            reconnect: ReconnectConfig::default(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+/// A pooled connection and its bookkeeping
+struct Slot {
+    client: Arc<AsyncMutex<GenericModbusClient<TcpTransport>>>,
+    connected: AtomicBool,
+    stats: Mutex<SlotStats>,
+}
+
+#[derive(Default)]
+struct SlotStats {
+    transport: TransportStats,
+    requests: u64,
+    busy_time: Duration,
+}
+
+impl SlotStats {
+    fn average_latency(&self) -> Duration {
+        if self.requests == 0 {
+            Duration::ZERO
+        } else {
+            self.busy_time / self.requests as u32
+        }
+    }
+}
+
+struct PoolInner {
+    address: SocketAddr,
+    timeout: Duration,
+    config: PoolConfig,
+    slots: Mutex<Vec<Arc<Slot>>>,
+    permits: Arc<Semaphore>,
+    next: AtomicUsize,
+}
+
+impl PoolInner {
+    /// Create a connection slot without connecting
+    fn new_slot(&self) -> Arc<Slot> {
+        let transport = TcpTransport::disconnected(self.address, self.timeout, self.config.reconnect.clone());
+        let client = GenericModbusClient::new(transport).with_retry_policy(self.config.retry_policy.clone());
+        Arc::new(Slot {
+            client: Arc::new(AsyncMutex::new(client)),
+            connected: AtomicBool::new(false),
+            stats: Mutex::new(SlotStats::default()),
+        })
+    }
+
+    /// Claim an idle slot according to the policy, opening a new one if allowed
+    fn claim(&self) -> Option<(Arc<Slot>, OwnedMutexGuard<GenericModbusClient<TcpTransport>>)> {
+        let mut slots = self.slots.lock().unwrap();
+        let count = slots.len();
+
+        let order: Vec<usize> = match self.config.policy {
+            PoolPolicy::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed);
+                (0..count).map(|i| (start + i) % count.max(1)).collect()
+            }
+            PoolPolicy::LeastBusy => {
+                let mut order: Vec<usize> = (0..count).collect();
+                order.sort_by_key(|&i| slots[i].stats.lock().unwrap().average_latency());
+                order
+            }
+        };
+
+        for i in order {
+            if let Ok(guard) = slots[i].client.clone().try_lock_owned() {
+                return Some((slots[i].clone(), guard));
+            }
+        }
+
+        if count < self.config.max_connections {
+            let slot = self.new_slot();
+            let guard = slot.client.clone().try_lock_owned().ok()?;
+            slots.push(slot.clone());
+            return Some((slot, guard));
+        }
+
+        None
+    }
+
+    /// Probe idle connections, reconnect broken ones and keep the pool at its minimum size
+    async fn health_check(&self) {
+        let slots: Vec<Arc<Slot>> = self.slots.lock().unwrap().clone();
+        let mut dead = Vec::new();
+
+        for slot in &slots {
+            // A checked-out slot must hold a permit, or a caller holding a
+            // free permit could find every slot locked
+            let Ok(_permit) = self.permits.clone().try_acquire_owned() else { break };
+            let Ok(mut client) = slot.client.clone().try_lock_owned() else { continue };
+
+            if let Some(probe) = self.config.health_probe.filter(|_| client.is_connected()) {
+                match client.read_03(probe.slave_id, probe.address, 1).await {
+                    Ok(_) | Err(ModbusError::Exception { .. }) => {}
+                    Err(_) => {
+                        // The transport may still hold the dead socket
+                        let _ = client.transport_mut().close().await;
+                    }
+                }
+                slot.stats.lock().unwrap().transport = client.get_stats();
+            }
+
+            let result = client.transport_mut().try_connect().await;
+            slot.connected.store(client.is_connected(), Ordering::Relaxed);
+            if result.is_err() {
+                dead.push(slot.clone());
+            }
+        }
+
+        let mut slots = self.slots.lock().unwrap();
+        for slot in dead {
+            if slots.len() <= self.config.min_connections {
+                break;
+            }
+            slots.retain(|existing| !Arc::ptr_eq(existing, &slot));
+        }
+        while slots.len() < self.config.min_connections {
+            slots.push(self.new_slot());
+        }
+    }
+}
+
+/// Connection checked out of a [`ModbusTcpPool`]
+///
+/// Dereferences to the underlying client, so several requests can be sent
+/// over the same connection. The connection returns to the pool on drop.
+pub struct PooledConnection {
+    slot: Arc<Slot>,
+    client: OwnedMutexGuard<GenericModbusClient<TcpTransport>>,
+    acquired_at: Instant,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledConnection {
+    type Target = GenericModbusClient<TcpTransport>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.client
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        self.slot.connected.store(self.client.is_connected(), Ordering::Relaxed);
+        let mut stats = self.slot.stats.lock().unwrap();
+        stats.transport = self.client.get_stats();
+        stats.requests += 1;
+        stats.busy_time += self.acquired_at.elapsed();
+    }
+}
+
+/// Pooled Modbus TCP client
+///
+/// Clones share the same pool.
+#[derive(Clone)]
+pub struct ModbusTcpPool {
+    inner: Arc<PoolInner>,
+}
+
+impl ModbusTcpPool {
+    /// Create a pool and open `min_connections` connections
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Server address
+    /// * `timeout` - Request timeout for every connection
+    /// * `config` - Pool configuration
+    pub async fn new(address: SocketAddr, timeout: Duration, config: PoolConfig) -> ModbusResult<Self> {
+        if config.max_connections == 0 || config.min_connections > config.max_connections {
+            return Err(ModbusError::configuration(format!(
+                "Invalid pool size: min {} / max {}",
+                config.min_connections, config.max_connections
+            )));
+        }
+
+        let inner = Arc::new(PoolInner {
+            address,
+            timeout,
+            permits: Arc::new(Semaphore::new(config.max_connections)),
+            slots: Mutex::new(Vec::new()),
+            next: AtomicUsize::new(0),
+            config,
+        });
+
+        for _ in 0..inner.config.min_connections {
+            let slot = inner.new_slot();
+            {
+                let mut client = slot.client.lock().await;
+                client.transport_mut().try_connect().await?;
+            }
+            slot.connected.store(true, Ordering::Relaxed);
+            inner.slots.lock().unwrap().push(slot);
+        }
+
+        if let Some(interval) = inner.config.health_check_interval {
+            tokio::spawn(Self::run_health_checks(Arc::downgrade(&inner), interval));
+        }
+
+        Ok(Self { inner })
+    }
+
+    /// Create a pool from an address string
+    pub async fn from_address(address: &str, timeout: Duration, config: PoolConfig) -> ModbusResult<Self> {
+        let address: SocketAddr = address.parse().map_err(|e| ModbusError::configuration(format!("Invalid address: {}", e)))?;
+        Self::new(address, timeout, config).await
+    }
+
+    /// Get the server address
+    pub fn server_address(&self) -> SocketAddr {
+        self.inner.address
+    }
+
+    /// Get the pool configuration
+    pub fn config(&self) -> &PoolConfig {
+        &self.inner.config
+    }
+
+    /// Number of connections currently in the pool
+    pub fn size(&self) -> usize {
+        self.inner.slots.lock().unwrap().len()
+    }
+
+    /// Number of connections not serving a request
+    pub fn idle_count(&self) -> usize {
+        let busy = self.inner.config.max_connections - self.inner.permits.available_permits();
+        self.size().saturating_sub(busy)
+    }
+
+    /// Check a connection out of the pool
+    ///
+    /// Waits up to `acquire_timeout` for a free connection.
+    pub async fn get(&self) -> ModbusResult<PooledConnection> {
+        let acquire_timeout = self.inner.config.acquire_timeout;
+        let permit = tokio::time::timeout(acquire_timeout, self.inner.permits.clone().acquire_owned())
+            .await
+            .map_err(|_| ModbusError::timeout("acquire pooled connection", acquire_timeout.as_millis() as u64))?
+            .map_err(|_| ModbusError::connection("Connection pool is closed"))?;
+
+        // Holding a permit guarantees an idle slot or room for a new one
+        let (slot, client) = self
+            .inner
+            .claim()
+            .ok_or_else(|| ModbusError::internal("No idle connection despite free permit"))?;
+
+        Ok(PooledConnection {
+            slot,
+            client,
+            acquired_at: Instant::now(),
+            _permit: permit,
+        })
+    }
+
+    async fn run_health_checks(pool: Weak<PoolInner>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Some(inner) = pool.upgrade() else { break };
+            if inner.permits.is_closed() {
+                break;
+            }
+            inner.health_check().await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusClient for ModbusTcpPool {
+    async fn read_01(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.get().await?.read_01(slave_id, address, quantity).await
+    }
+
+    async fn read_02(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.get().await?.read_02(slave_id, address, quantity).await
+    }
+
+    async fn read_03(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.get().await?.read_03(slave_id, address, quantity).await
+    }
+
+    async fn read_04(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.get().await?.read_04(slave_id, address, quantity).await
+    }
+
+    async fn write_05(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
+        self.get().await?.write_05(slave_id, address, value).await
+    }
+
+    async fn write_06(&mut self, slave_id: SlaveId, address: u16, value: u16) -> ModbusResult<()> {
+        self.get().await?.write_06(slave_id, address, value).await
+    }
+
+    async fn write_0f(&mut self, slave_id: SlaveId, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.get().await?.write_0f(slave_id, address, values).await
+    }
+
+    async fn write_10(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.get().await?.write_10(slave_id, address, values).await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner
+            .slots
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|slot| slot.connected.load(Ordering::Relaxed))
+    }
+
+    /// Close every connection and reject further requests
+    async fn close(&mut self) -> ModbusResult<()> {
+        self.inner.permits.close();
+        let slots: Vec<Arc<Slot>> = std::mem::take(&mut *self.inner.slots.lock().unwrap());
+        for slot in slots {
+            let mut client = slot.client.lock().await;
+            client.close().await?;
+            slot.connected.store(false, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        self.inner.slots.lock().unwrap().iter().fold(TransportStats::default(), |mut total, slot| {
+            let stats = &slot.stats.lock().unwrap().transport;
+            total.requests_sent += stats.requests_sent;
+            total.responses_received += stats.responses_received;
+            total.errors += stats.errors;
+            total.timeouts += stats.timeouts;
+            total.bytes_sent += stats.bytes_sent;
+            total.bytes_received += stats.bytes_received;
+            total.retries += stats.retries;
+            total
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Server answering reads with the number of the connection that served them
+    ///
+    /// With `drop_first`, the first connection is closed right after it is accepted.
+    async fn spawn_counting_server(drop_first: bool) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connection = 0u16;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                connection += 1;
+                let id = connection;
+                if drop_first && id == 1 {
+                    continue;
+                }
+                tokio::spawn(async move {
+                    let mut frame = [0u8; 12];
+                    while stream.read_exact(&mut frame).await.is_ok() {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        let [hi, lo] = id.to_be_bytes();
+                        let reply = [frame[0], frame[1], 0, 0, 0, 5, frame[6], frame[7], 2, hi, lo];
+                        if stream.write_all(&reply).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        address
+    }
+
+    #[tokio::test]
+    async fn test_pool_grows_to_max_under_load() {
+        let address = spawn_counting_server(false).await;
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 3,
+            health_check_interval: None,
+            ..Default::default()
+        };
+        let pool = ModbusTcpPool::new(address, Duration::from_secs(1), config).await.unwrap();
+        assert_eq!(pool.size(), 1);
+
+        let tasks: Vec<_> = (0..6)
+            .map(|_| {
+                let mut pool = pool.clone();
+                tokio::spawn(async move { pool.read_03(1, 0, 1).await.unwrap()[0] })
+            })
+            .collect();
+        let mut served_by = Vec::new();
+        for task in tasks {
+            served_by.push(task.await.unwrap());
+        }
+        served_by.sort_unstable();
+        served_by.dedup();
+
+        assert_eq!(pool.size(), 3);
+        assert_eq!(served_by, vec![1, 2, 3]);
+        assert_eq!(pool.get_stats().requests_sent, 6);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_timeout() {
+        let address = spawn_counting_server(false).await;
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 1,
+            acquire_timeout: Duration::from_millis(10),
+            health_check_interval: None,
+            ..Default::default()
+        };
+        let mut pool = ModbusTcpPool::new(address, Duration::from_secs(1), config).await.unwrap();
+
+        let held = pool.get().await.unwrap();
+        assert!(matches!(pool.read_03(1, 0, 1).await, Err(ModbusError::Timeout { .. })));
+        drop(held);
+        assert_eq!(pool.read_03(1, 0, 1).await.unwrap(), vec![1]);
+
+        pool.close().await.unwrap();
+        assert!(!pool.is_connected());
+        assert!(pool.read_03(1, 0, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_replaces_dead_connection() {
+        let address = spawn_counting_server(true).await;
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 1,
+            health_check_interval: None,
+            ..Default::default()
+        };
+        let mut pool = ModbusTcpPool::new(address, Duration::from_secs(1), config).await.unwrap();
+        assert!(pool.is_connected());
+
+        // A request arriving while the check holds the only slot waits for it
+        let check = {
+            let inner = pool.inner.clone();
+            tokio::spawn(async move { inner.health_check().await })
+        };
+        tokio::task::yield_now().await;
+        assert_eq!(pool.read_03(1, 0, 1).await.unwrap(), vec![2]);
+        check.await.unwrap();
+        assert_eq!(pool.size(), 1);
+    }
+}
@@ -93,6 +93,7 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use crate::error::{ModbusError, ModbusResult};
+use crate::enron::EnronConfig;
 
 /// Modbus address type (0-65535)
 pub type ModbusAddress = u16;
@@ -183,6 +184,68 @@ impl fmt::Display for ModbusFunction {
     }
 }
 
+/// Modbus data tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterTable {
+    /// Coils (read/write bits)
+    #[serde(alias = "coils")]
+    Coil,
+    /// Discrete inputs (read-only bits)
+    #[serde(alias = "discrete", alias = "discrete_inputs")]
+    DiscreteInput,
+    /// Holding registers (read/write words)
+    #[serde(alias = "holding", alias = "holding_registers")]
+    HoldingRegister,
+    /// Input registers (read-only words)
+    #[serde(alias = "input", alias = "input_registers")]
+    InputRegister,
+}
+
+impl RegisterTable {
+    /// Function code used to read this table
+    pub fn read_function(self) -> ModbusFunction {
+        match self {
+            RegisterTable::Coil => ModbusFunction::ReadCoils,
+            RegisterTable::DiscreteInput => ModbusFunction::ReadDiscreteInputs,
+            RegisterTable::HoldingRegister => ModbusFunction::ReadHoldingRegisters,
+            RegisterTable::InputRegister => ModbusFunction::ReadInputRegisters,
+        }
+    }
+
+    /// Table accessed by a function code
+    pub fn from_function(function: ModbusFunction) -> Self {
+        match function {
+            ModbusFunction::ReadCoils | ModbusFunction::WriteSingleCoil | ModbusFunction::WriteMultipleCoils => RegisterTable::Coil,
+            ModbusFunction::ReadDiscreteInputs => RegisterTable::DiscreteInput,
+            ModbusFunction::ReadHoldingRegisters | ModbusFunction::WriteSingleRegister | ModbusFunction::WriteMultipleRegisters => RegisterTable::HoldingRegister,
+            ModbusFunction::ReadInputRegisters => RegisterTable::InputRegister,
+        }
+    }
+
+    /// Check if the table holds single bits
+    pub fn is_bit_table(self) -> bool {
+        matches!(self, RegisterTable::Coil | RegisterTable::DiscreteInput)
+    }
+
+    /// Check if Modbus clients can write to the table
+    pub fn is_writable(self) -> bool {
+        matches!(self, RegisterTable::Coil | RegisterTable::HoldingRegister)
+    }
+}
+
+impl fmt::Display for RegisterTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RegisterTable::Coil => "coil",
+            RegisterTable::DiscreteInput => "discrete input",
+            RegisterTable::HoldingRegister => "holding register",
+            RegisterTable::InputRegister => "input register",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 /// Modbus exception codes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -328,6 +391,14 @@ impl ModbusRequest {
         
         Ok(())
     }
+
+    /// Validate the request, applying Enron rules when a configuration is given
+    pub fn validate_with(&self, enron: Option<&EnronConfig>) -> ModbusResult<()> {
+        match enron {
+            Some(config) => config.validate(self),
+            None => self.validate(),
+        }
+    }
 }
 
 /// Modbus response structure
@@ -369,7 +440,7 @@ impl ModbusResponse {
     /// Get exception error if present
     pub fn get_exception(&self) -> Option<ModbusError> {
         self.exception.map(|exc| {
-            ModbusError::protocol(format!("Modbus exception: {}", exc))
+            ModbusError::exception(self.function.to_u8(), exc.to_u8())
         })
     }
     
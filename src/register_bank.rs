@@ -3,10 +3,21 @@
 /// This module provides thread-safe storage for Modbus data including coils,
 /// discrete inputs, holding registers, and input registers.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ops::RangeInclusive;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, error, info, warn};
 use crate::error::{ModbusError, ModbusResult};
+use crate::codec::{self, RegisterOrder, RegisterValue};
+use crate::address::{AddressingMode, DataAddress};
+use crate::address_map::AddressMap;
+use crate::observer::{ChangeEvent, ChangeSubscription, ObserverId, Observers, WriteContext};
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::storage::{StorageBackend, StoredValue, Table};
+use crate::snapshot::{BankSnapshot, Run};
+use crate::simulation::{Simulation, SimulationConfig};
+use crate::enron::{EnronConfig, EnronEvent, ENRON_MAX_EVENTS_PER_READ, ENRON_MAX_READ_VALUES};
 
 /// Default register bank size
 const DEFAULT_COILS_SIZE: usize = 10000;
@@ -16,42 +27,146 @@ const DEFAULT_INPUT_REGISTERS_SIZE: usize = 10000;
 
 /// Modbus register bank for storing coils, discrete inputs, holding registers, and input registers
 /// 
-/// This structure provides thread-safe access to Modbus data through Arc<RwLock<_>> wrappers.
+/// This structure provides thread-safe access to Modbus data through shared
+/// tables in the chosen [`StorageBackend`].
 /// All register operations use 0-based addressing internally.
 #[derive(Debug, Clone)]
 pub struct ModbusRegisterBank {
     /// Coils (read/write) - 1 bit each
-    coils: Arc<RwLock<HashMap<u16, bool>>>,
+    coils: Arc<Table<bool>>,
     /// Discrete inputs (read-only) - 1 bit each
-    discrete_inputs: Arc<RwLock<HashMap<u16, bool>>>,
+    discrete_inputs: Arc<Table<bool>>,
     /// Holding registers (read/write) - 16 bits each
-    holding_registers: Arc<RwLock<HashMap<u16, u16>>>,
+    holding_registers: Arc<Table<u16>>,
     /// Input registers (read-only) - 16 bits each  
-    input_registers: Arc<RwLock<HashMap<u16, u16>>>,
+    input_registers: Arc<Table<u16>>,
+    /// How addresses passed to the bank are counted
+    addressing: AddressingMode,
+    /// Origin attributed to writes made through this handle
+    context: WriteContext,
+    /// Change subscribers, callbacks and write vetoes
+    observers: Arc<Observers>,
+    /// Valid addresses and value constraints, if restricted
+    address_map: Option<Arc<AddressMap>>,
+    /// Enron mode configuration, if enabled
+    enron: Option<Arc<EnronConfig>>,
+    /// Enron 32-bit registers
+    long_registers: Arc<RwLock<HashMap<u16, u32>>>,
+    /// Enron event log
+    enron_events: Arc<RwLock<EnronEventLog>>,
+    /// Enron archive records by archive register and record index
+    enron_archives: Arc<RwLock<HashMap<u16, EnronArchive>>>,
+}
+
+/// Enron archive records by record index
+type EnronArchive = BTreeMap<u16, Vec<f32>>;
+
+/// Pending Enron events and how many of them the last read delivered
+#[derive(Debug, Default)]
+struct EnronEventLog {
+    events: VecDeque<EnronEvent>,
+    delivered: usize,
 }
 
 impl ModbusRegisterBank {
     /// Create a new register bank with empty data
     pub fn new() -> Self {
+        Self::with_backend(StorageBackend::Sparse)
+    }
+
+    /// Create a new register bank with empty data in the given storage backend
+    pub fn with_backend(backend: StorageBackend) -> Self {
         Self {
-            coils: Arc::new(RwLock::new(HashMap::new())),
-            discrete_inputs: Arc::new(RwLock::new(HashMap::new())),
-            holding_registers: Arc::new(RwLock::new(HashMap::new())),
-            input_registers: Arc::new(RwLock::new(HashMap::new())),
+            coils: Arc::new(Table::new(backend)),
+            discrete_inputs: Arc::new(Table::new(backend)),
+            holding_registers: Arc::new(Table::new(backend)),
+            input_registers: Arc::new(Table::new(backend)),
+            addressing: AddressingMode::ZeroBased,
+            context: WriteContext::local(),
+            observers: Arc::new(Observers::new()),
+            address_map: None,
+            enron: None,
+            long_registers: Arc::new(RwLock::new(HashMap::new())),
+            enron_events: Arc::new(RwLock::new(EnronEventLog::default())),
+            enron_archives: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// Get the storage backend
+    pub fn storage_backend(&self) -> StorageBackend {
+        self.coils.backend()
+    }
+
+    /// Restrict the bank to the addresses and values declared in `map`
+    /// 
+    /// Without a map every address is valid and unset points read as zero.
+    /// With one, accesses outside its blocks fail with
+    /// [`ModbusError::InvalidAddress`] and constraint violations with
+    /// [`ModbusError::InvalidData`]. Block access modes apply to handles
+    /// with a remote write context, i.e. to Modbus requests.
+    /// 
+    /// # Returns
+    /// 
+    /// The restricted bank, or a configuration error for an inconsistent map
+    pub fn with_address_map(mut self, map: AddressMap) -> ModbusResult<Self> {
+        map.validate()?;
+        self.address_map = Some(Arc::new(map));
+        Ok(self)
+    }
+
+    /// Restrict each table to its first 10000 addresses
+    pub fn with_default_sizes(self) -> Self {
+        let map = AddressMap::with_sizes(
+            DEFAULT_COILS_SIZE,
+            DEFAULT_DISCRETE_INPUTS_SIZE,
+            DEFAULT_HOLDING_REGISTERS_SIZE,
+            DEFAULT_INPUT_REGISTERS_SIZE,
+        );
+        Self { address_map: Some(Arc::new(map)), ..self }
+    }
+
+    /// Get the address map, if the bank is restricted
+    pub fn address_map(&self) -> Option<&AddressMap> {
+        self.address_map.as_deref()
+    }
+
+    /// Serve Enron 32-bit registers, event log and archives
+    /// 
+    /// Without it, every register is 16 bits wide and reads of the event log
+    /// and archive registers are ordinary holding register reads.
+    pub fn with_enron(mut self, config: EnronConfig) -> Self {
+        self.enron = Some(Arc::new(config));
+        self
+    }
+
+    /// Get the Enron configuration, if enabled
+    pub fn enron(&self) -> Option<&EnronConfig> {
+        self.enron.as_deref()
+    }
     
+    /// Use the given addressing mode for all addresses passed to the bank
+    /// 
+    /// With [`AddressingMode::OneBased`], register 1 is stored at protocol
+    /// address 0. Servers always use protocol addresses, and Enron 32-bit
+    /// registers, event log and archives are not affected.
+    pub fn with_addressing(mut self, mode: AddressingMode) -> Self {
+        self.addressing = mode;
+        self
+    }
+
+    /// Get the addressing mode
+    pub fn addressing(&self) -> AddressingMode {
+        self.addressing
+    }
+
+    /// Handle to the same data using protocol (0-based) addresses
+    pub fn protocol_view(&self) -> Self {
+        self.clone().with_addressing(AddressingMode::ZeroBased)
+    }
+
     /// Read coils starting at address (function code 0x01)
     pub fn read_coils(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
-        let coils = self.coils.read().map_err(|_| ModbusError::internal("Failed to lock coils"))?;
-        let mut result = Vec::with_capacity(quantity as usize);
-        
-        for i in 0..quantity {
-            let addr = address.wrapping_add(i);
-            result.push(coils.get(&addr).copied().unwrap_or(false));
-        }
-        
-        Ok(result)
+        self.load(RegisterTable::Coil, self.addressing.to_wire(address)?, quantity)
     }
     
     /// Alias for read_coils using function code naming
@@ -61,32 +176,17 @@ impl ModbusRegisterBank {
     
     /// Write single coil (function code 0x05)
     pub fn write_05(&self, address: u16, value: bool) -> ModbusResult<()> {
-        let mut coils = self.coils.write().map_err(|_| ModbusError::internal("Failed to lock coils"))?;
-        coils.insert(address, value);
-        Ok(())
+        self.store(RegisterTable::Coil, self.addressing.to_wire(address)?, &[value])
     }
     
     /// Write multiple coils (function code 0x0F)
     pub fn write_0f(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
-        let mut coils = self.coils.write().map_err(|_| ModbusError::internal("Failed to lock coils"))?;
-        for (i, &value) in values.iter().enumerate() {
-            let addr = address.wrapping_add(i as u16);
-            coils.insert(addr, value);
-        }
-        Ok(())
+        self.store(RegisterTable::Coil, self.addressing.to_wire(address)?, values)
     }
     
     /// Read discrete inputs starting at address (function code 0x02)
     pub fn read_discrete_inputs(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
-        let inputs = self.discrete_inputs.read().map_err(|_| ModbusError::internal("Failed to lock discrete inputs"))?;
-        let mut result = Vec::with_capacity(quantity as usize);
-        
-        for i in 0..quantity {
-            let addr = address.wrapping_add(i);
-            result.push(inputs.get(&addr).copied().unwrap_or(false));
-        }
-        
-        Ok(result)
+        self.load(RegisterTable::DiscreteInput, self.addressing.to_wire(address)?, quantity)
     }
     
     /// Alias for read_discrete_inputs using function code naming
@@ -96,15 +196,7 @@ impl ModbusRegisterBank {
     
     /// Read holding registers starting at address (function code 0x03)
     pub fn read_holding_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
-        let registers = self.holding_registers.read().map_err(|_| ModbusError::internal("Failed to lock holding registers"))?;
-        let mut result = Vec::with_capacity(quantity as usize);
-        
-        for i in 0..quantity {
-            let addr = address.wrapping_add(i);
-            result.push(registers.get(&addr).copied().unwrap_or(0));
-        }
-        
-        Ok(result)
+        self.load(RegisterTable::HoldingRegister, self.addressing.to_wire(address)?, quantity)
     }
     
     /// Alias for read_holding_registers using function code naming
@@ -114,62 +206,414 @@ impl ModbusRegisterBank {
     
     /// Write single register (function code 0x06)
     pub fn write_06(&self, address: u16, value: u16) -> ModbusResult<()> {
-        let mut registers = self.holding_registers.write().map_err(|_| ModbusError::internal("Failed to lock holding registers"))?;
-        registers.insert(address, value);
-        Ok(())
+        self.store(RegisterTable::HoldingRegister, self.addressing.to_wire(address)?, &[value])
     }
     
     /// Write multiple registers (function code 0x10)
     pub fn write_10(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
-        let mut registers = self.holding_registers.write().map_err(|_| ModbusError::internal("Failed to lock holding registers"))?;
-        for (i, &value) in values.iter().enumerate() {
-            let addr = address.wrapping_add(i as u16);
-            registers.insert(addr, value);
-        }
-        Ok(())
+        self.store(RegisterTable::HoldingRegister, self.addressing.to_wire(address)?, values)
     }
     
     /// Read input registers starting at address (function code 0x04)
     pub fn read_input_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
-        let registers = self.input_registers.read().map_err(|_| ModbusError::internal("Failed to lock input registers"))?;
-        let mut result = Vec::with_capacity(quantity as usize);
-        
-        for i in 0..quantity {
-            let addr = address.wrapping_add(i);
-            result.push(registers.get(&addr).copied().unwrap_or(0));
-        }
-        
-        Ok(result)
+        self.load(RegisterTable::InputRegister, self.addressing.to_wire(address)?, quantity)
     }
     
     /// Alias for read_input_registers using function code naming
     pub fn read_04(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
         self.read_input_registers(address, quantity)
     }
+
+    /// Read coils into `out`, one per element
+    pub fn read_coils_into(&self, address: u16, out: &mut [bool]) -> ModbusResult<()> {
+        self.load_into(RegisterTable::Coil, self.addressing.to_wire(address)?, out)
+    }
+
+    /// Read discrete inputs into `out`, one per element
+    pub fn read_discrete_inputs_into(&self, address: u16, out: &mut [bool]) -> ModbusResult<()> {
+        self.load_into(RegisterTable::DiscreteInput, self.addressing.to_wire(address)?, out)
+    }
+
+    /// Read holding registers into `out`, one per element
+    /// 
+    /// Unlike [`read_holding_registers`](Self::read_holding_registers) this
+    /// does not allocate.
+    pub fn read_holding_registers_into(&self, address: u16, out: &mut [u16]) -> ModbusResult<()> {
+        self.load_into(RegisterTable::HoldingRegister, self.addressing.to_wire(address)?, out)
+    }
+
+    /// Read input registers into `out`, one per element
+    pub fn read_input_registers_into(&self, address: u16, out: &mut [u16]) -> ModbusResult<()> {
+        self.load_into(RegisterTable::InputRegister, self.addressing.to_wire(address)?, out)
+    }
     
     /// Set input register value (for simulation/testing)
     pub fn set_input_register(&self, address: u16, value: u16) -> ModbusResult<()> {
-        let mut registers = self.input_registers.write().map_err(|_| ModbusError::internal("Failed to lock input registers"))?;
-        registers.insert(address, value);
-        Ok(())
+        self.store(RegisterTable::InputRegister, self.addressing.to_wire(address)?, &[value])
     }
     
     /// Set discrete input value (for simulation/testing)
     pub fn set_discrete_input(&self, address: u16, value: bool) -> ModbusResult<()> {
-        let mut inputs = self.discrete_inputs.write().map_err(|_| ModbusError::internal("Failed to lock discrete inputs"))?;
-        inputs.insert(address, value);
+        self.store(RegisterTable::DiscreteInput, self.addressing.to_wire(address)?, &[value])
+    }
+
+    /// Set consecutive input register values (for simulation/testing)
+    pub fn set_input_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.store(RegisterTable::InputRegister, self.addressing.to_wire(address)?, values)
+    }
+
+    /// Set consecutive discrete input values (for simulation/testing)
+    pub fn set_discrete_inputs(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.store(RegisterTable::DiscreteInput, self.addressing.to_wire(address)?, values)
+    }
+
+    /// Handle to the same data that attributes its writes to `context`
+    /// 
+    /// Servers use this to tag writes with the unit id and peer address of
+    /// the request.
+    pub fn with_write_context(mut self, context: WriteContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Origin attributed to writes made through this handle
+    pub fn write_context(&self) -> WriteContext {
+        self.context
+    }
+
+    /// Subscribe to writes to `range` of `table`
+    /// 
+    /// Addresses are protocol addresses. Every write is reported, including
+    /// writes that store the values already present.
+    pub fn subscribe_changes(&self, table: RegisterTable, range: RangeInclusive<u16>) -> ChangeSubscription {
+        self.observers.subscribe(table, range)
+    }
+
+    /// Run `callback` after every write to `range` of `table`
+    /// 
+    /// The callback runs on the writing task, so it should return quickly.
+    pub fn on_change<F>(&self, table: RegisterTable, range: RangeInclusive<u16>, callback: F) -> ObserverId
+    where
+        F: Fn(&ChangeEvent) + Send + Sync + 'static,
+    {
+        self.observers.add_callback(table, range, Arc::new(callback))
+    }
+
+    /// Check writes to `range` of `table` before they are applied
+    /// 
+    /// The veto receives the pending write and returns `false` to reject it;
+    /// rejected writes fail with [`ModbusError::InvalidData`], which servers
+    /// answer with exception 0x03 (Illegal Data Value). The veto runs while
+    /// the table is locked and must not access the bank.
+    pub fn add_write_veto<F>(&self, table: RegisterTable, range: RangeInclusive<u16>, veto: F) -> ObserverId
+    where
+        F: Fn(&ChangeEvent) -> bool + Send + Sync + 'static,
+    {
+        self.observers.add_veto(table, range, Arc::new(veto))
+    }
+
+    /// Remove a callback or veto
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the observer existed
+    pub fn remove_observer(&self, id: ObserverId) -> bool {
+        self.observers.remove(id)
+    }
+
+    /// Store values into consecutive protocol addresses of a table
+    fn store<T: StoredValue>(&self, table: RegisterTable, address: u16, values: &[T]) -> ModbusResult<()>
+    where
+        Self: TableAccess<T>,
+    {
+        if let Some(address_map) = &self.address_map {
+            address_map.check_write(table, address, &Self::point_value(values.to_vec()), self.context.is_remote())?;
+        }
+
+        let mut event = None;
+        self.table(table).write(address, values, self.observers.is_active(), |old| {
+            let pending = ChangeEvent {
+                table,
+                address,
+                old: Self::point_value(old),
+                new: Self::point_value(values.to_vec()),
+                context: self.context,
+                long: false,
+            };
+            self.observers.check(&pending)?;
+            event = Some(pending);
+            Ok(())
+        })?;
+
+        if let Some(event) = event {
+            self.observers.notify(event);
+        }
         Ok(())
     }
+
+    /// Read `quantity` values from consecutive protocol addresses of a table
+    /// 
+    /// Unset addresses read as the default value.
+    fn load<T: StoredValue>(&self, table: RegisterTable, address: u16, quantity: u16) -> ModbusResult<Vec<T>>
+    where
+        Self: TableAccess<T>,
+    {
+        let mut values = vec![T::default(); quantity as usize];
+        self.load_into(table, address, &mut values)?;
+        Ok(values)
+    }
+
+    /// Fill `out` from consecutive protocol addresses of a table
+    fn load_into<T: StoredValue>(&self, table: RegisterTable, address: u16, out: &mut [T]) -> ModbusResult<()>
+    where
+        Self: TableAccess<T>,
+    {
+        if let Some(address_map) = &self.address_map {
+            let quantity = u16::try_from(out.len()).map_err(|_| ModbusError::invalid_address(address, u16::MAX))?;
+            address_map.check_read(table, address, quantity, self.context.is_remote())?;
+        }
+        self.table(table).read_into(address, out)
+    }
+
+    /// Read from any table at a parsed address
+    /// 
+    /// The table and offset come from the address, e.g. `"30010".parse()`,
+    /// independently of the bank's addressing mode.
+    pub fn read_at(&self, address: DataAddress, quantity: u16) -> ModbusResult<PointValue> {
+        let offset = address.address;
+        Ok(match address.table {
+            RegisterTable::Coil => PointValue::Bits(self.load(RegisterTable::Coil, offset, quantity)?),
+            RegisterTable::DiscreteInput => PointValue::Bits(self.load(RegisterTable::DiscreteInput, offset, quantity)?),
+            RegisterTable::HoldingRegister => PointValue::Registers(self.load(RegisterTable::HoldingRegister, offset, quantity)?),
+            RegisterTable::InputRegister => PointValue::Registers(self.load(RegisterTable::InputRegister, offset, quantity)?),
+        })
+    }
+
+    /// Write to any table at a parsed address, including the read-only tables
+    pub fn write_at(&self, address: DataAddress, value: &PointValue) -> ModbusResult<()> {
+        let offset = address.address;
+        match (address.table, value) {
+            (table, PointValue::Bits(bits)) if table.is_bit_table() => self.store(table, offset, bits),
+            (table, PointValue::Registers(registers)) if !table.is_bit_table() => self.store(table, offset, registers),
+            (table, _) => Err(ModbusError::invalid_data(format!("Data does not match the {} table", table))),
+        }
+    }
     
+    /// Read a typed value from holding registers
+    pub fn get_holding_value<V: RegisterValue>(&self, address: u16, order: RegisterOrder) -> ModbusResult<V> {
+        V::from_registers(&self.read_holding_registers(address, V::REGISTERS)?, order)
+    }
+
+    /// Write a typed value to holding registers
+    pub fn set_holding_value<V: RegisterValue>(&self, address: u16, value: V, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(address, &value.to_registers(order))
+    }
+
+    /// Read a typed value from input registers
+    pub fn get_input_value<V: RegisterValue>(&self, address: u16, order: RegisterOrder) -> ModbusResult<V> {
+        V::from_registers(&self.read_input_registers(address, V::REGISTERS)?, order)
+    }
+
+    /// Set a typed value in input registers (for simulation/testing)
+    pub fn set_input_value<V: RegisterValue>(&self, address: u16, value: V, order: RegisterOrder) -> ModbusResult<()> {
+        self.store(RegisterTable::InputRegister, self.addressing.to_wire(address)?, &value.to_registers(order))
+    }
+
+    /// Read a fixed-length string from holding registers
+    pub fn get_holding_string(&self, address: u16, registers: u16, order: RegisterOrder) -> ModbusResult<String> {
+        codec::decode_string(&self.read_holding_registers(address, registers)?, order)
+    }
+
+    /// Write a string into exactly `registers` holding registers, padded with NUL
+    pub fn set_holding_string(&self, address: u16, value: &str, registers: u16, order: RegisterOrder) -> ModbusResult<()> {
+        self.write_10(address, &codec::encode_string(value, registers, order)?)
+    }
+
+    /// Read Enron 32-bit registers
+    pub fn read_enron_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u32>> {
+        if quantity == 0 || quantity > ENRON_MAX_READ_VALUES {
+            return Err(ModbusError::invalid_data(format!("Invalid 32-bit register quantity: {}", quantity)));
+        }
+        let registers = self.long_registers.read().map_err(|_| ModbusError::internal("Failed to lock 32-bit registers"))?;
+        Ok((0..quantity).map(|i| registers.get(&address.wrapping_add(i)).copied().unwrap_or(0)).collect())
+    }
+
+    /// Write Enron 32-bit registers
+    /// 
+    /// Like the 16-bit tables, the write is checked against the address map
+    /// and write vetoes and reported to observers, as a holding register
+    /// write with [`ChangeEvent::long`] set.
+    pub fn write_enron_registers(&self, address: u16, values: &[u32]) -> ModbusResult<()> {
+        let quantity = u16::try_from(values.len()).map_err(|_| ModbusError::invalid_address(address, u16::MAX))?;
+        if let Some(address_map) = &self.address_map {
+            address_map.check_write_access(RegisterTable::HoldingRegister, address, quantity, self.context.is_remote())?;
+        }
+
+        let mut registers = self.long_registers.write().map_err(|_| ModbusError::internal("Failed to lock 32-bit registers"))?;
+        let mut event = None;
+        if self.observers.is_active() {
+            let old: Vec<u32> = (0..quantity).map(|i| registers.get(&address.wrapping_add(i)).copied().unwrap_or(0)).collect();
+            let split = |values: &[u32]| PointValue::Registers(values.iter().flat_map(|v| [(v >> 16) as u16, *v as u16]).collect());
+            let pending = ChangeEvent {
+                table: RegisterTable::HoldingRegister,
+                address,
+                old: split(&old),
+                new: split(values),
+                context: self.context,
+                long: true,
+            };
+            self.observers.check(&pending)?;
+            event = Some(pending);
+        }
+        for (i, &value) in values.iter().enumerate() {
+            registers.insert(address.wrapping_add(i as u16), value);
+        }
+        drop(registers);
+
+        if let Some(event) = event {
+            self.observers.notify(event);
+        }
+        Ok(())
+    }
+
+    /// Read an Enron 32-bit register as a float
+    pub fn get_enron_f32(&self, address: u16) -> ModbusResult<f32> {
+        Ok(f32::from_bits(self.read_enron_registers(address, 1)?[0]))
+    }
+
+    /// Write a float into an Enron 32-bit register
+    pub fn set_enron_f32(&self, address: u16, value: f32) -> ModbusResult<()> {
+        self.write_enron_registers(address, &[value.to_bits()])
+    }
+
+    /// Append an event to the Enron event log
+    pub fn push_enron_event(&self, event: EnronEvent) -> ModbusResult<()> {
+        let mut log = self.enron_events.write().map_err(|_| ModbusError::internal("Failed to lock event log"))?;
+        log.events.push_back(event);
+        Ok(())
+    }
+
+    /// Number of unacknowledged Enron events
+    pub fn pending_enron_events(&self) -> usize {
+        self.enron_events.read().map(|log| log.events.len()).unwrap_or(0)
+    }
+
+    /// Read the oldest unacknowledged events, as an event log read does
+    /// 
+    /// Returns at most 12 events; they stay pending until acknowledged.
+    pub fn read_enron_events(&self) -> ModbusResult<Vec<EnronEvent>> {
+        let mut log = self.enron_events.write().map_err(|_| ModbusError::internal("Failed to lock event log"))?;
+        log.delivered = log.events.len().min(ENRON_MAX_EVENTS_PER_READ);
+        Ok(log.events.iter().take(log.delivered).copied().collect())
+    }
+
+    /// Remove the events delivered by the last event log read
+    /// 
+    /// # Returns
+    /// 
+    /// Number of events acknowledged
+    pub fn acknowledge_enron_events(&self) -> ModbusResult<usize> {
+        let mut log = self.enron_events.write().map_err(|_| ModbusError::internal("Failed to lock event log"))?;
+        let count = std::mem::take(&mut log.delivered);
+        log.events.drain(..count);
+        Ok(count)
+    }
+
+    /// Store an Enron archive record
+    pub fn set_enron_archive_record(&self, register: u16, index: u16, values: Vec<f32>) -> ModbusResult<()> {
+        if values.is_empty() || values.len() > ENRON_MAX_READ_VALUES as usize {
+            return Err(ModbusError::invalid_data(format!("Archive records hold 1 to {} values", ENRON_MAX_READ_VALUES)));
+        }
+        let mut archives = self.enron_archives.write().map_err(|_| ModbusError::internal("Failed to lock archives"))?;
+        archives.entry(register).or_default().insert(index, values);
+        Ok(())
+    }
+
+    /// Get an Enron archive record
+    pub fn enron_archive_record(&self, register: u16, index: u16) -> Option<Vec<f32>> {
+        let archives = self.enron_archives.read().ok()?;
+        archives.get(&register)?.get(&index).cloned()
+    }
+
+    /// Take an image of the four tables
+    /// 
+    /// Each table is copied consistently; writes to different tables made
+    /// while the snapshot is taken may or may not be included.
+    pub fn snapshot(&self) -> ModbusResult<BankSnapshot> {
+        Ok(BankSnapshot {
+            coils: Run::from_entries(self.coils.entries()?),
+            discrete_inputs: Run::from_entries(self.discrete_inputs.entries()?),
+            holding_registers: Run::from_entries(self.holding_registers.entries()?),
+            input_registers: Run::from_entries(self.input_registers.entries()?),
+        })
+    }
+
+    /// Replace the contents of the four tables with a snapshot
+    /// 
+    /// Addresses missing from the snapshot are cleared. Restoring bypasses
+    /// the address map and does not notify observers.
+    pub fn restore(&self, snapshot: &BankSnapshot) -> ModbusResult<()> {
+        let coils = Run::to_entries(&snapshot.coils)?;
+        let discrete_inputs = Run::to_entries(&snapshot.discrete_inputs)?;
+        let holding_registers = Run::to_entries(&snapshot.holding_registers)?;
+        let input_registers = Run::to_entries(&snapshot.input_registers)?;
+
+        self.coils.replace_all(&coils)?;
+        self.discrete_inputs.replace_all(&discrete_inputs)?;
+        self.holding_registers.replace_all(&holding_registers)?;
+        self.input_registers.replace_all(&input_registers)
+    }
+
+    /// Start updating simulated points of the bank on a tokio interval
+    /// 
+    /// See [`Simulation::start`]; the simulation stops when the returned
+    /// handle is dropped.
+    pub fn start_simulation(&self, config: SimulationConfig) -> ModbusResult<Simulation> {
+        Simulation::start(self, config)
+    }
+
     /// Get register bank statistics
     pub fn get_stats(&self) -> RegisterBankStats {
         RegisterBankStats {
-            coils_count: self.coils.read().unwrap().len(),
-            discrete_inputs_count: self.discrete_inputs.read().unwrap().len(),
-            holding_registers_count: self.holding_registers.read().unwrap().len(),
-            input_registers_count: self.input_registers.read().unwrap().len(),
+            coils_count: self.coils.len(),
+            discrete_inputs_count: self.discrete_inputs.len(),
+            holding_registers_count: self.holding_registers.len(),
+            input_registers_count: self.input_registers.len(),
+        }
+    }
+}
+
+
+/// Access to the tables holding one value type
+trait TableAccess<T: StoredValue> {
+    fn table(&self, table: RegisterTable) -> &Table<T>;
+    fn point_value(values: Vec<T>) -> PointValue;
+}
+
+impl TableAccess<bool> for ModbusRegisterBank {
+    fn table(&self, table: RegisterTable) -> &Table<bool> {
+        match table {
+            RegisterTable::DiscreteInput => &self.discrete_inputs,
+            _ => &self.coils,
+        }
+    }
+
+    fn point_value(values: Vec<bool>) -> PointValue {
+        PointValue::Bits(values)
+    }
+}
+
+impl TableAccess<u16> for ModbusRegisterBank {
+    fn table(&self, table: RegisterTable) -> &Table<u16> {
+        match table {
+            RegisterTable::InputRegister => &self.input_registers,
+            _ => &self.holding_registers,
         }
     }
+
+    fn point_value(values: Vec<u16>) -> PointValue {
+        PointValue::Registers(values)
+    }
 }
 
 impl Default for ModbusRegisterBank {
@@ -220,4 +664,154 @@ mod tests {
         let registers = bank.read_03(100, 3).unwrap();
         assert_eq!(registers, vec![100, 200, 300]);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_typed_values() {
+        let bank = ModbusRegisterBank::new();
+
+        bank.set_holding_value(0, 3.5f32, RegisterOrder::CDAB).unwrap();
+        assert_eq!(bank.read_03(0, 2).unwrap(), vec![0x0000, 0x4060]);
+        assert_eq!(bank.get_holding_value::<f32>(0, RegisterOrder::CDAB).unwrap(), 3.5);
+
+        bank.set_input_value(10, -7i64, RegisterOrder::DCBA).unwrap();
+        assert_eq!(bank.get_input_value::<i64>(10, RegisterOrder::DCBA).unwrap(), -7);
+
+        bank.set_holding_string(20, "ABC", 2, RegisterOrder::ABCD).unwrap();
+        assert_eq!(bank.read_03(20, 2).unwrap(), vec![0x4142, 0x4300]);
+        assert_eq!(bank.get_holding_string(20, 2, RegisterOrder::ABCD).unwrap(), "ABC");
+    }
+
+    #[test]
+    fn test_one_based_addressing() {
+        let bank = ModbusRegisterBank::new().with_addressing(AddressingMode::OneBased);
+        bank.write_06(1, 0x1234).unwrap();
+        bank.set_input_register(10, 7).unwrap();
+        assert!(bank.write_06(0, 1).is_err());
+
+        // Register 1 is protocol address 0
+        let protocol = bank.protocol_view();
+        assert_eq!(protocol.read_03(0, 1).unwrap(), vec![0x1234]);
+        assert_eq!(bank.read_at("40001".parse().unwrap(), 1).unwrap(), PointValue::Registers(vec![0x1234]));
+        assert_eq!(bank.read_at("30010".parse().unwrap(), 1).unwrap(), PointValue::Registers(vec![7]));
+
+        bank.write_at("%M5".parse().unwrap(), &PointValue::Bits(vec![true])).unwrap();
+        assert_eq!(bank.read_coils(6, 1).unwrap(), vec![true]);
+        assert!(bank.write_at("%M5".parse().unwrap(), &PointValue::Registers(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_address_map() {
+        use crate::address_map::{AddressMap, ValueConstraint};
+        use crate::tags::Access;
+
+        let map = AddressMap::new()
+            .block(RegisterTable::HoldingRegister, 0..=99, Access::ReadWrite)
+            .block(RegisterTable::InputRegister, 0..=9, Access::Read)
+            .constraint(ValueConstraint::one_of(20, vec![0, 1]));
+        let bank = ModbusRegisterBank::new().with_address_map(map).unwrap();
+
+        assert!(matches!(bank.read_03(95, 10), Err(ModbusError::InvalidAddress { start: 95, count: 10 })));
+        assert!(bank.read_coils(0, 1).is_err());
+        assert!(bank.write_06(20, 2).is_err());
+        bank.write_06(20, 1).unwrap();
+
+        // Access modes only bind Modbus requests
+        bank.set_input_register(3, 7).unwrap();
+        let remote = bank.clone().with_write_context(WriteContext::remote(1, None));
+        assert_eq!(remote.read_04(3, 1).unwrap(), vec![7]);
+        assert!(remote.set_input_register(3, 8).is_err());
+
+        let sized = ModbusRegisterBank::new().with_default_sizes();
+        assert!(sized.read_03(9999, 1).is_ok());
+        assert!(sized.read_03(9999, 2).is_err());
+        assert!(ModbusRegisterBank::new().read_03(60000, 10).is_ok());
+    }
+
+    #[test]
+    fn test_dense_backend() {
+        let bank = ModbusRegisterBank::with_backend(StorageBackend::Dense);
+        assert_eq!(bank.storage_backend(), StorageBackend::Dense);
+        bank.write_10(100, &[1, 2, 3]).unwrap();
+        bank.write_0f(7, &[true, true]).unwrap();
+
+        let mut registers = [0u16; 4];
+        bank.read_holding_registers_into(99, &mut registers).unwrap();
+        assert_eq!(registers, [0, 1, 2, 3]);
+        assert_eq!(bank.read_01(6, 4).unwrap(), vec![false, true, true, false]);
+
+        // Observers and clones work the same as with the sparse backend
+        let mut changes = bank.subscribe_changes(RegisterTable::HoldingRegister, 0..=u16::MAX);
+        bank.clone().write_06(101, 20).unwrap();
+        assert_eq!(changes.try_recv().unwrap().old, PointValue::Registers(vec![2]));
+        assert_eq!(bank.get_stats().holding_registers_count, 3);
+
+        // Snapshots move data between backends; restoring clears everything else
+        let sparse = ModbusRegisterBank::new();
+        sparse.write_06(5000, 1).unwrap();
+        sparse.restore(&bank.snapshot().unwrap()).unwrap();
+        assert_eq!(sparse.read_03(100, 3).unwrap(), vec![1, 20, 3]);
+        assert_eq!(sparse.read_03(5000, 1).unwrap(), vec![0]);
+        assert_eq!(sparse.snapshot().unwrap(), bank.snapshot().unwrap());
+    }
+
+    #[test]
+    fn test_change_observers() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let bank = ModbusRegisterBank::new();
+        let mut subscription = bank.subscribe_changes(RegisterTable::HoldingRegister, 10..=19);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let callback = bank.on_change(RegisterTable::Coil, 0..=u16::MAX, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        bank.add_write_veto(RegisterTable::HoldingRegister, 15..=15, |event| event.new != PointValue::Registers(vec![0]));
+
+        bank.write_10(8, &[1, 2, 3]).unwrap();
+        let event = subscription.try_recv().unwrap();
+        assert_eq!((event.address, event.old, event.new), (10, PointValue::Registers(vec![0]), PointValue::Registers(vec![3])));
+        assert_eq!(event.context, WriteContext::local());
+
+        // A vetoed write leaves the table untouched and reports nothing
+        assert!(matches!(bank.write_10(14, &[7, 0]), Err(ModbusError::InvalidData { .. })));
+        assert_eq!(bank.read_03(14, 2).unwrap(), vec![0, 0]);
+        assert!(subscription.try_recv().is_err());
+
+        // Clones share observers; write contexts are per handle
+        let remote = bank.clone().with_write_context(WriteContext::remote(3, None));
+        remote.write_05(1, true).unwrap();
+        remote.write_06(12, 9).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(subscription.try_recv().unwrap().context.unit_id, Some(3));
+
+        assert!(bank.remove_observer(callback));
+        bank.write_05(2, true).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_enron_storage() {
+        let bank = ModbusRegisterBank::new().with_enron(EnronConfig::default());
+        assert!(bank.enron().is_some());
+
+        bank.set_enron_f32(7001, 12.5).unwrap();
+        bank.write_enron_registers(5001, &[70000]).unwrap();
+        assert_eq!(bank.get_enron_f32(7001).unwrap(), 12.5);
+        assert_eq!(bank.read_enron_registers(5001, 2).unwrap(), vec![70000, 0]);
+        // 32-bit registers do not alias the 16-bit holding registers
+        assert_eq!(bank.read_03(5001, 2).unwrap(), vec![0, 0]);
+
+        let event = EnronEvent { status: 1, register: 7001, time: 120000.0, date: 101826.0, previous_value: 1.0, current_value: 2.0 };
+        for _ in 0..14 {
+            bank.push_enron_event(event).unwrap();
+        }
+        assert_eq!(bank.read_enron_events().unwrap().len(), 12);
+        assert_eq!(bank.acknowledge_enron_events().unwrap(), 12);
+        assert_eq!(bank.pending_enron_events(), 2);
+        assert_eq!(bank.acknowledge_enron_events().unwrap(), 0);
+
+        bank.set_enron_archive_record(700, 1, vec![101826.0, 130000.0, 4.5]).unwrap();
+        assert_eq!(bank.enron_archive_record(700, 1).unwrap()[2], 4.5);
+        assert!(bank.enron_archive_record(700, 2).is_none());
+    }
+}
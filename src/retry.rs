@@ -0,0 +1,268 @@
+//! # Request Retry Policies
+//!
+//! This module provides configurable retry behaviour for Modbus client requests,
+//! so applications no longer need to wrap every `read_03` in their own retry loop.
+//!
+//! A [`RetryPolicy`] decides three things:
+//!
+//! - **How often**: the maximum number of attempts per request
+//! - **How long to wait**: exponential backoff with optional random jitter
+//! - **What to retry**: which error classes are considered transient ([`RetryOn`])
+//!
+//! Write requests are never retried unless explicitly enabled, because a write
+//! whose response was lost may already have been applied by the device.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusTcpClient, ModbusClient, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let mut client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//!
+//! client.set_retry_policy(
+//!     RetryPolicy::new(3)
+//!         .with_backoff(Duration::from_millis(50), Duration::from_secs(1))
+//!         .with_jitter(0.2)
+//! );
+//!
+//! // Transient timeouts are now retried transparently
+//! let values = client.read_03(1, 0, 10).await?;
+//! println!("Retries so far: {}", client.get_stats().retries);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+use rand::Rng;
+
+use crate::error::ModbusError;
+
+/// Error classes that a [`RetryPolicy`] treats as transient
+///
+/// The classes are disjoint: timeouts, CRC errors and exceptions are decided
+/// by their own flags only, `recoverable` covers the remaining errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOn {
+    /// Retry other errors for which [`ModbusError::is_recoverable`] returns `true`,
+    /// i.e. I/O, connection and device-not-responding errors
+    pub recoverable: bool,
+    /// Retry timeouts
+    pub timeouts: bool,
+    /// Retry CRC/LRC validation failures
+    pub crc_errors: bool,
+    /// Retry Slave Device Busy (0x06) and Acknowledge (0x05) exceptions
+    pub busy: bool,
+}
+
+impl RetryOn {
+    /// Retry nothing
+    pub fn none() -> Self {
+        Self {
+            recoverable: false,
+            timeouts: false,
+            crc_errors: false,
+            busy: false,
+        }
+    }
+
+    /// Check whether the given error belongs to one of the enabled classes
+    pub fn matches(&self, error: &ModbusError) -> bool {
+        match error {
+            ModbusError::Timeout { .. } => self.timeouts,
+            ModbusError::CrcMismatch { .. } => self.crc_errors,
+            ModbusError::Exception { code, .. } => self.busy && matches!(code, 0x05 | 0x06),
+            ModbusError::ChunkFailed { source, .. } => self.matches(source),
+            _ => self.recoverable && error.is_recoverable(),
+        }
+    }
+}
+
+impl Default for RetryOn {
+    fn default() -> Self {
+        Self {
+            recoverable: true,
+            timeouts: true,
+            crc_errors: true,
+            busy: true,
+        }
+    }
+}
+
+/// Retry policy for client requests
+///
+/// The default policy performs a single attempt, i.e. retries are disabled
+/// until a policy with `max_attempts > 1` is installed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts per request, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after every retry
+    pub multiplier: f64,
+    /// Random jitter as a fraction of the delay (0.0 - 1.0)
+    pub jitter: f64,
+    /// Error classes that are retried
+    pub retry_on: RetryOn,
+    /// Whether write requests (0x05, 0x06, 0x0F, 0x10) may be retried
+    pub retry_writes: bool,
+}
+
+impl RetryPolicy {
+    /// Create a policy with the given number of attempts and default backoff
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Create a policy that never retries
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Set initial and maximum backoff delays
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Set the backoff multiplier
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the jitter fraction (clamped to 0.0 - 1.0)
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set which error classes are retried
+    pub fn with_retry_on(mut self, retry_on: RetryOn) -> Self {
+        self.retry_on = retry_on;
+        self
+    }
+
+    /// Allow or forbid retrying write requests
+    pub fn with_retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// Decide whether a failed attempt should be retried
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error returned by the failed attempt
+    /// * `attempt` - Number of attempts made so far (1-based)
+    /// * `is_write` - Whether the request modifies device state
+    pub fn should_retry(&self, error: &ModbusError, attempt: u32, is_write: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        if is_write && !self.retry_writes {
+            return false;
+        }
+
+        self.retry_on.matches(error)
+    }
+
+    /// Delay before the given retry (1-based), without jitter
+    pub fn base_backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(32) as i32;
+        let delay_ns = self.initial_backoff.as_nanos() as f64 * self.multiplier.powi(exponent);
+        let max_ns = self.max_backoff.as_nanos() as f64;
+        Duration::from_nanos(delay_ns.min(max_ns).round() as u64)
+    }
+
+    /// Delay before the given retry (1-based), with jitter applied
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let base = self.base_backoff(retry);
+        if self.jitter <= 0.0 || base.is_zero() {
+            return base;
+        }
+
+        let factor = rand::thread_rng().gen_range(-self.jitter..=self.jitter);
+        let delay_ns = base.as_nanos() as f64 * (1.0 + factor);
+        let max_ns = self.max_backoff.as_nanos() as f64;
+        Duration::from_nanos(delay_ns.clamp(0.0, max_ns).round() as u64)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.1,
+            retry_on: RetryOn::default(),
+            retry_writes: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_classification() {
+        let policy = RetryPolicy::new(3);
+
+        assert!(policy.should_retry(&ModbusError::timeout("read", 100), 1, false));
+        assert!(policy.should_retry(&ModbusError::crc_mismatch(0x1234, 0x4321), 2, false));
+        assert!(policy.should_retry(&ModbusError::exception(0x03, 0x06), 1, false));
+        assert!(!policy.should_retry(&ModbusError::exception(0x03, 0x02), 1, false));
+
+        // Attempts exhausted
+        assert!(!policy.should_retry(&ModbusError::timeout("read", 100), 3, false));
+
+        // Writes are opt-in
+        assert!(!policy.should_retry(&ModbusError::timeout("write", 100), 1, true));
+        let policy = policy.with_retry_writes(true);
+        assert!(policy.should_retry(&ModbusError::timeout("write", 100), 1, true));
+
+        // Only selected classes
+        let policy = RetryPolicy::new(3).with_retry_on(RetryOn { crc_errors: true, ..RetryOn::none() });
+        assert!(policy.should_retry(&ModbusError::crc_mismatch(1, 2), 1, false));
+        assert!(!policy.should_retry(&ModbusError::timeout("read", 100), 1, false));
+
+        // Disabling a class wins over `recoverable`
+        let policy = RetryPolicy::new(3).with_retry_on(RetryOn { timeouts: false, busy: false, ..RetryOn::default() });
+        assert!(!policy.should_retry(&ModbusError::timeout("read", 100), 1, false));
+        assert!(!policy.should_retry(&ModbusError::exception(0x03, 0x06), 1, false));
+        assert!(policy.should_retry(&ModbusError::io("reset"), 1, false));
+        let policy = RetryPolicy::new(3).with_retry_on(RetryOn { recoverable: false, ..RetryOn::default() });
+        assert!(!policy.should_retry(&ModbusError::io("reset"), 1, false));
+        assert!(policy.should_retry(&ModbusError::timeout("read", 100), 1, false));
+    }
+
+    #[test]
+    fn test_backoff_growth() {
+        let policy = RetryPolicy::new(5)
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(40));
+        assert_eq!(policy.backoff(4), Duration::from_millis(50));
+
+        let jittered = policy.with_jitter(0.5);
+        for _ in 0..20 {
+            let delay = jittered.backoff(1);
+            assert!(delay >= Duration::from_millis(5) && delay <= Duration::from_millis(15));
+        }
+    }
+}
@@ -17,6 +17,11 @@ use tokio_serial;
 use crate::error::{ModbusError, ModbusResult};
 use crate::protocol::{ModbusRequest, ModbusResponse, ModbusFunction};
 use crate::register_bank::{ModbusRegisterBank, RegisterBankStats};
+use crate::enron::{EnronConfig, EnronEvent, ENRON_EVENT_REGISTER, ENRON_MAX_WRITE_VALUES};
+use crate::observer::WriteContext;
+use crate::store::DataStore;
+use crate::watchdog::{Watchdog, WatchdogConfig};
+use crate::fault_injection::{FaultConfig, FaultInjector, FaultPlan};
 
 /// Maximum frame size for Modbus TCP
 const MAX_TCP_FRAME_SIZE: usize = 260;
@@ -40,6 +45,8 @@ pub trait ModbusServer: Send + Sync {
     fn get_stats(&self) -> ServerStats;
     
     /// Get register bank reference
+    /// 
+    /// `None` for servers whose data store is not backed by a register bank.
     fn get_register_bank(&self) -> Option<Arc<ModbusRegisterBank>>;
 }
 
@@ -78,13 +85,17 @@ impl Default for ModbusTcpServerConfig {
 }
 
 /// Modbus TCP server implementation
-pub struct ModbusTcpServer {
+/// 
+/// Serves the data in a [`DataStore`], by default a [`ModbusRegisterBank`].
+pub struct ModbusTcpServer<S: DataStore = ModbusRegisterBank> {
     config: ModbusTcpServerConfig,
-    register_bank: Arc<ModbusRegisterBank>,
+    store: Arc<S>,
     stats: Arc<Mutex<ServerStats>>,
     shutdown_tx: Option<broadcast::Sender<()>>,
     is_running: Arc<AtomicBool>,
     start_time: Option<std::time::Instant>,
+    watchdog: Option<Arc<Watchdog>>,
+    faults: Option<Arc<FaultInjector>>,
 }
 
 impl ModbusTcpServer {
@@ -106,28 +117,126 @@ impl ModbusTcpServer {
         let register_bank = config.register_bank.clone()
             .unwrap_or_else(|| Arc::new(ModbusRegisterBank::new()));
         
-        Ok(Self {
+        Ok(Self::with_store(config, register_bank))
+    }
+    
+    /// Set custom register bank
+    pub fn set_register_bank(&mut self, register_bank: Arc<ModbusRegisterBank>) {
+        self.store = register_bank;
+    }
+
+    /// Exception code reported to the master for a failed request
+    pub(crate) fn exception_code(error: &ModbusError) -> u8 {
+        #[allow(deprecated)]
+        match error {
+            ModbusError::Exception { code, .. } => *code,
+            ModbusError::InvalidFunction { .. } | ModbusError::IllegalFunction => 0x01,
+            ModbusError::InvalidAddress { .. } => 0x02,
+            ModbusError::InvalidData { .. } | ModbusError::InvalidDataValue | ModbusError::Frame { .. } | ModbusError::InvalidFrame => 0x03,
+            _ => 0x04,
+        }
+    }
+
+    /// Serve an Enron event log, archive or 32-bit register read
+    /// 
+    /// Returns `None` for reads of ordinary 16-bit registers.
+    fn handle_enron_read(enron: &EnronConfig, address: u16, quantity: u16, register_bank: &ModbusRegisterBank) -> ModbusResult<Option<Vec<u8>>> {
+        if address == ENRON_EVENT_REGISTER {
+            // Event records are 10 registers of 16-bit fields and floats
+            let events = register_bank.read_enron_events()?;
+            let mut response = vec![0x03, (events.len() * 20) as u8];
+            for register in events.iter().flat_map(EnronEvent::to_registers) {
+                response.extend_from_slice(&register.to_be_bytes());
+            }
+            return Ok(Some(response));
+        }
+
+        let values: Vec<u32> = if enron.archive_registers.contains(&address) {
+            // The quantity field selects the archive record
+            register_bank
+                .enron_archive_record(address, quantity)
+                .ok_or_else(|| ModbusError::invalid_address(address, quantity))?
+                .iter()
+                .map(|value| value.to_bits())
+                .collect()
+        } else if enron.long_range(address, quantity)? {
+            register_bank.read_enron_registers(address, quantity)?
+        } else {
+            return Ok(None);
+        };
+
+        let mut response = vec![0x03, (values.len() * 4) as u8];
+        for value in values {
+            response.extend_from_slice(&value.to_be_bytes());
+        }
+        Ok(Some(response))
+    }
+}
+
+impl<S: DataStore> ModbusTcpServer<S> {
+    /// Create a new TCP server serving `store`
+    /// 
+    /// The `register_bank` field of the configuration is ignored.
+    pub fn with_store(config: ModbusTcpServerConfig, store: Arc<S>) -> Self {
+        Self {
             config,
-            register_bank,
+            store,
             stats: Arc::new(Mutex::new(ServerStats::default())),
             shutdown_tx: None,
             is_running: Arc::new(AtomicBool::new(false)),
             start_time: None,
-        })
+            watchdog: None,
+            faults: None,
+        }
     }
-    
-    /// Set custom register bank
-    pub fn set_register_bank(&mut self, register_bank: Arc<ModbusRegisterBank>) {
-        self.register_bank = register_bank;
+
+    /// Get the data store
+    pub fn store(&self) -> &Arc<S> {
+        &self.store
+    }
+
+    /// Replace the data store
+    pub fn set_store(&mut self, store: Arc<S>) {
+        self.store = store;
+    }
+
+    /// Apply failsafe values when the master goes silent
+    /// 
+    /// The watchdog is armed when the server starts; see [`crate::watchdog`].
+    pub fn with_watchdog(mut self, config: WatchdogConfig) -> Self {
+        self.watchdog = Some(Arc::new(Watchdog::new(config)));
+        self
+    }
+
+    /// Get the watchdog, if configured
+    pub fn watchdog(&self) -> Option<&Arc<Watchdog>> {
+        self.watchdog.as_ref()
+    }
+
+    /// Misbehave on demand to test clients; see [`crate::fault_injection`]
+    /// 
+    /// # Returns
+    /// 
+    /// The server, or an error if the configuration is invalid
+    pub fn with_faults(mut self, config: FaultConfig) -> ModbusResult<Self> {
+        self.faults = Some(Arc::new(FaultInjector::new(config)?));
+        Ok(self)
+    }
+
+    /// Get the fault injector, if configured
+    pub fn faults(&self) -> Option<&Arc<FaultInjector>> {
+        self.faults.as_ref()
     }
     
     /// Handle client connection
     async fn handle_client(
         stream: TcpStream,
-        register_bank: Arc<ModbusRegisterBank>,
+        store: Arc<S>,
         stats: Arc<Mutex<ServerStats>>,
         mut shutdown_rx: broadcast::Receiver<()>,
         request_timeout: Duration,
+        watchdog: Option<Arc<Watchdog>>,
+        faults: Option<Arc<FaultInjector>>,
     ) {
         let peer_addr = stream.peer_addr().unwrap_or_else(|_| "unknown".parse().unwrap());
         info!("📡 New client connected: {}", peer_addr);
@@ -165,30 +274,52 @@ impl ModbusTcpServer {
                             }
                             
                             // Process request
-                            match Self::handle_request(&buffer[..bytes_read], &register_bank).await {
-                                Ok(response_data) => {
-                                    if let Err(e) = stream.write_all(&response_data).await {
-                                        error!("Failed to send response to {}: {}", peer_addr, e);
-                                        break;
-                                    } else {
-                                        // Update success stats
-                                        let mut stats = stats.lock().await;
-                                        stats.successful_requests += 1;
-                                        stats.bytes_sent += response_data.len() as u64;
-                                    }
+                            let frame = &buffer[..bytes_read];
+                            let pdu = frame.get(7..).unwrap_or_default();
+                            let plan = faults.as_ref().map(|faults| faults.plan(pdu)).unwrap_or_default();
+                            if !plan.delay.is_zero() {
+                                tokio::time::sleep(plan.delay).await;
+                            }
+                            let request = async {
+                                match plan.exception {
+                                    Some(code) => Err(ModbusError::exception(pdu.first().copied().unwrap_or_default(), code)),
+                                    None => Self::handle_request(frame, &store, Some(peer_addr)).await,
                                 }
+                            };
+                            let result = match &watchdog {
+                                Some(watchdog) => watchdog.serve(pdu, request).await,
+                                None => request.await,
+                            };
+
+                            let reply = match &result {
+                                Ok(response_pdu) => Some(ModbusTcpServer::create_response(frame, response_pdu)),
                                 Err(e) => {
                                     error!("Error processing request from {}: {}", peer_addr, e);
-                                    
-                                    // Send error response if possible
-                                    if let Ok(error_response) = Self::create_error_response(&buffer[..bytes_read], 0x01) {
-                                        let _ = stream.write_all(&error_response).await;
-                                    }
-                                    
-                                    // Update error stats
-                                    let mut stats = stats.lock().await;
-                                    stats.failed_requests += 1;
+                                    ModbusTcpServer::create_error_response(frame, ModbusTcpServer::exception_code(e)).ok()
                                 }
+                            };
+                            if plan.reset {
+                                debug!("Injected connection reset for {}", peer_addr);
+                                let _ = stream.set_linger(Some(Duration::ZERO));
+                                break;
+                            }
+                            if let Some(mut reply) = reply.filter(|_| plan.sends_reply()) {
+                                plan.apply_tcp(&mut reply);
+                                if let Err(e) = stream.write_all(&reply).await {
+                                    error!("Failed to send response to {}: {}", peer_addr, e);
+                                    break;
+                                }
+                                if result.is_ok() {
+                                    stats.lock().await.bytes_sent += reply.len() as u64;
+                                }
+                            }
+
+                            // Update request stats
+                            let mut stats = stats.lock().await;
+                            if result.is_ok() {
+                                stats.successful_requests += 1;
+                            } else {
+                                stats.failed_requests += 1;
                             }
                         }
                         Ok(Err(e)) => {
@@ -208,25 +339,34 @@ impl ModbusTcpServer {
     }
     
     /// Process Modbus request
-    async fn handle_request(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_request(data: &[u8], store: &Arc<S>, peer: Option<SocketAddr>) -> ModbusResult<Vec<u8>> {
         if data.len() < 8 {
             return Err(ModbusError::frame("Invalid TCP frame length"));
         }
 
-        let function_code = data[7];
-        let pdu_data = &data[8..];
+        Self::handle_pdu(data[6], &data[7..], store, peer).await
+    }
+
+    /// Process a request PDU (function code and data) addressed to `unit_id`
+    /// 
+    /// Returns the response PDU.
+    pub(crate) async fn handle_pdu(unit_id: u8, pdu: &[u8], store: &Arc<S>, peer: Option<SocketAddr>) -> ModbusResult<Vec<u8>> {
+        let Some((&function_code, pdu_data)) = pdu.split_first() else {
+            return Err(ModbusError::frame("Empty request PDU"));
+        };
+        let store = &store.for_request(WriteContext::remote(unit_id, peer));
 
         debug!("Processing function code: 0x{:02X}", function_code);
 
         match function_code {
-            0x01 => Self::handle_read_01(pdu_data, register_bank).await,
-            0x02 => Self::handle_read_02(pdu_data, register_bank).await,
-            0x03 => Self::handle_read_03(pdu_data, register_bank).await,
-            0x04 => Self::handle_read_04(pdu_data, register_bank).await,
-            0x05 => Self::handle_write_05(pdu_data, register_bank).await,
-            0x06 => Self::handle_write_06(pdu_data, register_bank).await,
-            0x0F => Self::handle_write_0f(pdu_data, register_bank).await,
-            0x10 => Self::handle_write_10(pdu_data, register_bank).await,
+            0x01 => Self::handle_read_01(pdu_data, store).await,
+            0x02 => Self::handle_read_02(pdu_data, store).await,
+            0x03 => Self::handle_read_03(pdu_data, store).await,
+            0x04 => Self::handle_read_04(pdu_data, store).await,
+            0x05 => Self::handle_write_05(pdu_data, store).await,
+            0x06 => Self::handle_write_06(pdu_data, store).await,
+            0x0F => Self::handle_write_0f(pdu_data, store).await,
+            0x10 => Self::handle_write_10(pdu_data, store).await,
             _ => {
                 warn!("Unsupported function code: 0x{:02X}", function_code);
                 Err(ModbusError::invalid_function(function_code))
@@ -235,7 +375,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle read coils (0x01)
-    async fn handle_read_01(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_read_01(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::frame("Invalid read coils request"));
         }
@@ -243,7 +383,7 @@ impl ModbusTcpServer {
         let address = u16::from_be_bytes([data[0], data[1]]);
         let quantity = u16::from_be_bytes([data[2], data[3]]);
 
-        let coils = register_bank.read_01(address, quantity)?;
+        let coils = store.read_coils(address, quantity)?;
 
         // Pack coils into bytes
         let byte_count = (quantity + 7) / 8;
@@ -263,7 +403,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle read discrete inputs (0x02)
-    async fn handle_read_02(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_read_02(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::frame("Invalid read discrete inputs request"));
         }
@@ -271,7 +411,7 @@ impl ModbusTcpServer {
         let address = u16::from_be_bytes([data[0], data[1]]);
         let quantity = u16::from_be_bytes([data[2], data[3]]);
 
-        let inputs = register_bank.read_02(address, quantity)?;
+        let inputs = store.read_discrete_inputs(address, quantity)?;
 
         // Pack inputs into bytes
         let byte_count = (quantity + 7) / 8;
@@ -291,7 +431,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle read holding registers (0x03)
-    async fn handle_read_03(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_read_03(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -299,7 +439,15 @@ impl ModbusTcpServer {
         let address = u16::from_be_bytes([data[0], data[1]]);
         let quantity = u16::from_be_bytes([data[2], data[3]]);
 
-        let registers = register_bank.read_03(address, quantity)?;
+        if let Some(bank) = store.register_bank() {
+            if let Some(enron) = bank.enron() {
+                if let Some(response) = ModbusTcpServer::handle_enron_read(enron, address, quantity, bank)? {
+                    return Ok(response);
+                }
+            }
+        }
+
+        let registers = store.read_holding_registers(address, quantity)?;
 
         let mut response = vec![0x03, (quantity * 2) as u8];
         for &register in &registers {
@@ -310,7 +458,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle read input registers (0x04)
-    async fn handle_read_04(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_read_04(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -318,7 +466,7 @@ impl ModbusTcpServer {
         let address = u16::from_be_bytes([data[0], data[1]]);
         let quantity = u16::from_be_bytes([data[2], data[3]]);
 
-        let registers = register_bank.read_04(address, quantity)?;
+        let registers = store.read_input_registers(address, quantity)?;
 
         let mut response = vec![0x04, (quantity * 2) as u8];
         for &register in &registers {
@@ -329,7 +477,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle write single coil (0x05)
-    async fn handle_write_05(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_write_05(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -338,7 +486,15 @@ impl ModbusTcpServer {
         let value_bytes = u16::from_be_bytes([data[2], data[3]]);
         let coil_value = value_bytes == 0xFF00;
 
-        register_bank.write_05(address, coil_value)?;
+        let enron_bank = store.register_bank().filter(|bank| bank.enron().is_some());
+        if let (Some(bank), ENRON_EVENT_REGISTER) = (enron_bank, address) {
+            // Writing the event log coil acknowledges the events read last
+            if coil_value {
+                bank.acknowledge_enron_events()?;
+            }
+        } else {
+            store.write_coils(address, &[coil_value])?;
+        }
 
         let mut response = vec![0x05];
         response.extend_from_slice(&address.to_be_bytes());
@@ -347,7 +503,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle write single register (0x06)
-    async fn handle_write_06(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_write_06(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 4 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -355,7 +511,7 @@ impl ModbusTcpServer {
         let address = u16::from_be_bytes([data[0], data[1]]);
         let value = u16::from_be_bytes([data[2], data[3]]);
 
-        register_bank.write_06(address, value)?;
+        store.write_holding_registers(address, &[value])?;
 
         let mut response = vec![0x06];
         response.extend_from_slice(&address.to_be_bytes());
@@ -364,7 +520,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle write multiple coils (0x0F)
-    async fn handle_write_0f(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_write_0f(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 5 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -385,7 +541,7 @@ impl ModbusTcpServer {
             coils.push(bit_value);
         }
 
-        register_bank.write_0f(address, &coils)?;
+        store.write_coils(address, &coils)?;
 
         let mut response = vec![0x0F];
         response.extend_from_slice(&address.to_be_bytes());
@@ -394,7 +550,7 @@ impl ModbusTcpServer {
     }
     
     /// Handle write multiple registers (0x10)
-    async fn handle_write_10(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
+    async fn handle_write_10(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
         if data.len() < 5 {
             return Err(ModbusError::InvalidFrame);
         }
@@ -403,6 +559,30 @@ impl ModbusTcpServer {
         let quantity = u16::from_be_bytes([data[2], data[3]]);
         let byte_count = data[4] as usize;
 
+        // `store` is request-scoped, so the bank applies the address map,
+        // vetoes and observers with the request's write context
+        if let Some((bank, enron)) = store.register_bank().and_then(|bank| Some((bank, bank.enron()?))) {
+            if enron.long_range(address, quantity)? {
+                if quantity == 0 || quantity > ENRON_MAX_WRITE_VALUES {
+                    return Err(ModbusError::invalid_data(format!("Invalid 32-bit register quantity: {}", quantity)));
+                }
+                if data.len() < 5 + byte_count || byte_count != (quantity as usize * 4) {
+                    return Err(ModbusError::frame("Invalid 32-bit register write"));
+                }
+
+                let values: Vec<u32> = data[5..5 + byte_count]
+                    .chunks(4)
+                    .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect();
+                bank.write_enron_registers(address, &values)?;
+
+                let mut response = vec![0x10];
+                response.extend_from_slice(&address.to_be_bytes());
+                response.extend_from_slice(&quantity.to_be_bytes());
+                return Ok(response);
+            }
+        }
+
         if data.len() < 5 + byte_count || byte_count != (quantity as usize * 2) {
             return Err(ModbusError::InvalidFrame);
         }
@@ -414,14 +594,28 @@ impl ModbusTcpServer {
             registers.push(value);
         }
 
-        register_bank.write_10(address, &registers)?;
+        store.write_holding_registers(address, &registers)?;
 
         let mut response = vec![0x10];
         response.extend_from_slice(&address.to_be_bytes());
         response.extend_from_slice(&quantity.to_be_bytes());
         Ok(response)
     }
-    
+}
+
+impl ModbusTcpServer {
+    /// Frame a response PDU with the MBAP header of the request
+    pub(crate) fn create_response(request: &[u8], pdu: &[u8]) -> Vec<u8> {
+        let length = (pdu.len() + 1) as u16; // unit_id + PDU
+        let mut response = Vec::with_capacity(MBAP_HEADER_SIZE + 1 + pdu.len());
+        response.extend_from_slice(&request[..2]);
+        response.extend_from_slice(&0u16.to_be_bytes());
+        response.extend_from_slice(&length.to_be_bytes());
+        response.push(request[6]);
+        response.extend_from_slice(pdu);
+        response
+    }
+
     /// Create error response
     fn create_error_response(request: &[u8], exception_code: u8) -> ModbusResult<Vec<u8>> {
         if request.len() < MBAP_HEADER_SIZE + 2 {
@@ -451,7 +645,7 @@ impl ModbusTcpServer {
 }
 
 #[async_trait]
-impl ModbusServer for ModbusTcpServer {
+impl<S: DataStore> ModbusServer for ModbusTcpServer<S> {
     async fn start(&mut self) -> ModbusResult<()> {
         if self.is_running.load(Ordering::Relaxed) {
             return Err(ModbusError::protocol("Server is already running"));
@@ -472,11 +666,17 @@ impl ModbusServer for ModbusTcpServer {
         info!("   - Max connections: {}", self.config.max_connections);
         info!("   - Request timeout: {:?}", self.config.request_timeout);
         
-        let register_bank = self.register_bank.clone();
+        let store = self.store.clone();
         let stats = self.stats.clone();
         let request_timeout = self.config.request_timeout;
         let is_running_flag = self.is_running.clone();
         let mut shutdown_rx = shutdown_tx.subscribe();
+        let watchdog = self.watchdog.clone();
+        if let Some(watchdog) = &watchdog {
+            let (watchdog, store, shutdown_rx) = (watchdog.clone(), store.clone(), shutdown_tx.subscribe());
+            tokio::spawn(async move { watchdog.run(store.as_ref(), shutdown_rx).await });
+        }
+        let faults = self.faults.clone();
         
         // Set is_running to true only after successfully binding and before starting the listen loop
         self.is_running.store(true, Ordering::Relaxed);
@@ -489,12 +689,14 @@ impl ModbusServer for ModbusTcpServer {
                             Ok((stream, addr)) => {
                                 debug!("Accepted connection from {}", addr);
                                 
-                                let register_bank = register_bank.clone();
+                                let store = store.clone();
                                 let stats = stats.clone();
                                 let shutdown_rx = shutdown_tx.subscribe();
+                                let watchdog = watchdog.clone();
+                                let faults = faults.clone();
                                 
                                 tokio::spawn(async move {
-                                    Self::handle_client(stream, register_bank, stats, shutdown_rx, request_timeout).await;
+                                    Self::handle_client(stream, store, stats, shutdown_rx, request_timeout, watchdog, faults).await;
                                 });
                             }
                             Err(e) => {
@@ -539,12 +741,13 @@ impl ModbusServer for ModbusTcpServer {
             stats.uptime_seconds = start_time.elapsed().as_secs();
         }
         
-        stats.register_bank_stats = Some(self.register_bank.get_stats());
+        stats.register_bank_stats = self.store.register_bank().map(ModbusRegisterBank::get_stats);
         stats
     }
     
     fn get_register_bank(&self) -> Option<Arc<ModbusRegisterBank>> {
-        Some(self.register_bank.clone())
+        // Clones of a register bank share its data
+        self.store.register_bank().map(|bank| Arc::new(bank.clone()))
     }
 }
 
@@ -577,13 +780,17 @@ impl Default for ModbusRtuServerConfig {
 }
 
 /// Modbus RTU server implementation
-pub struct ModbusRtuServer {
+/// 
+/// Serves the data in a [`DataStore`], by default a [`ModbusRegisterBank`].
+pub struct ModbusRtuServer<S: DataStore = ModbusRegisterBank> {
     config: ModbusRtuServerConfig,
-    register_bank: Arc<ModbusRegisterBank>,
+    store: Arc<S>,
     stats: Arc<Mutex<ServerStats>>,
     shutdown_tx: Option<broadcast::Sender<()>>,
     is_running: Arc<AtomicBool>,
     start_time: Option<std::time::Instant>,
+    watchdog: Option<Arc<Watchdog>>,
+    faults: Option<Arc<FaultInjector>>,
 }
 
 impl ModbusRtuServer {
@@ -603,19 +810,12 @@ impl ModbusRtuServer {
         let register_bank = config.register_bank.clone()
             .unwrap_or_else(|| Arc::new(ModbusRegisterBank::new()));
         
-        Ok(Self {
-            config,
-            register_bank,
-            stats: Arc::new(Mutex::new(ServerStats::default())),
-            shutdown_tx: None,
-            is_running: Arc::new(AtomicBool::new(false)),
-            start_time: None,
-        })
+        Ok(Self::with_store(config, register_bank))
     }
     
     /// Set custom register bank
     pub fn set_register_bank(&mut self, register_bank: Arc<ModbusRegisterBank>) {
-        self.register_bank = register_bank;
+        self.store = register_bank;
     }
     
     /// Calculate CRC for RTU frames
@@ -625,6 +825,75 @@ impl ModbusRtuServer {
         CRC_MODBUS.checksum(data)
     }
     
+    /// Create RTU error response
+    fn create_rtu_error_response(slave_id: u8, function_code: u8, exception_code: u8) -> ModbusResult<Vec<u8>> {
+        let mut response = Vec::new();
+        response.push(slave_id);
+        response.push(function_code | 0x80); // Set exception bit
+        response.push(exception_code);
+        
+        let crc = Self::calculate_crc(&response);
+        response.extend_from_slice(&crc.to_le_bytes());
+        
+        Ok(response)
+    }
+}
+
+impl<S: DataStore> ModbusRtuServer<S> {
+    /// Create a new RTU server serving `store`
+    /// 
+    /// The `register_bank` field of the configuration is ignored.
+    pub fn with_store(config: ModbusRtuServerConfig, store: Arc<S>) -> Self {
+        Self {
+            config,
+            store,
+            stats: Arc::new(Mutex::new(ServerStats::default())),
+            shutdown_tx: None,
+            is_running: Arc::new(AtomicBool::new(false)),
+            start_time: None,
+            watchdog: None,
+            faults: None,
+        }
+    }
+
+    /// Get the data store
+    pub fn store(&self) -> &Arc<S> {
+        &self.store
+    }
+
+    /// Replace the data store
+    pub fn set_store(&mut self, store: Arc<S>) {
+        self.store = store;
+    }
+
+    /// Apply failsafe values when the master goes silent
+    /// 
+    /// The watchdog is armed when the server starts; see [`crate::watchdog`].
+    pub fn with_watchdog(mut self, config: WatchdogConfig) -> Self {
+        self.watchdog = Some(Arc::new(Watchdog::new(config)));
+        self
+    }
+
+    /// Get the watchdog, if configured
+    pub fn watchdog(&self) -> Option<&Arc<Watchdog>> {
+        self.watchdog.as_ref()
+    }
+
+    /// Misbehave on demand to test clients; see [`crate::fault_injection`]
+    /// 
+    /// # Returns
+    /// 
+    /// The server, or an error if the configuration is invalid
+    pub fn with_faults(mut self, config: FaultConfig) -> ModbusResult<Self> {
+        self.faults = Some(Arc::new(FaultInjector::new(config)?));
+        Ok(self)
+    }
+
+    /// Get the fault injector, if configured
+    pub fn faults(&self) -> Option<&Arc<FaultInjector>> {
+        self.faults.as_ref()
+    }
+    
     /// Handle RTU request
     async fn handle_request(&mut self, data: &[u8]) -> ModbusResult<Vec<u8>> {
         if data.len() < 3 {
@@ -640,15 +909,16 @@ impl ModbusRtuServer {
             return Err(ModbusError::device_not_responding(slave_id));
         }
 
+        let store = self.store.for_request(WriteContext::remote(slave_id, None));
         let response_pdu = match function_code {
-            0x01 => Self::handle_read_01(pdu_data, &self.register_bank).await?,
-            0x02 => Self::handle_read_02(pdu_data, &self.register_bank).await?,
-            0x03 => Self::handle_read_03(pdu_data, &self.register_bank).await?,
-            0x04 => Self::handle_read_04(pdu_data, &self.register_bank).await?,
-            0x05 => Self::handle_write_05(pdu_data, &self.register_bank).await?,
-            0x06 => Self::handle_write_06(pdu_data, &self.register_bank).await?,
-            0x0F => Self::handle_write_0f(pdu_data, &self.register_bank).await?,
-            0x10 => Self::handle_write_10(pdu_data, &self.register_bank).await?,
+            0x01 => Self::handle_read_01(pdu_data, &store).await?,
+            0x02 => Self::handle_read_02(pdu_data, &store).await?,
+            0x03 => Self::handle_read_03(pdu_data, &store).await?,
+            0x04 => Self::handle_read_04(pdu_data, &store).await?,
+            0x05 => Self::handle_write_05(pdu_data, &store).await?,
+            0x06 => Self::handle_write_06(pdu_data, &store).await?,
+            0x0F => Self::handle_write_0f(pdu_data, &store).await?,
+            0x10 => Self::handle_write_10(pdu_data, &store).await?,
             _ => {
                 return Err(ModbusError::invalid_function(function_code));
             }
@@ -661,26 +931,15 @@ impl ModbusRtuServer {
         Ok(response)
     }
     
-    /// Create RTU error response
-    fn create_rtu_error_response(slave_id: u8, function_code: u8, exception_code: u8) -> ModbusResult<Vec<u8>> {
-        let mut response = Vec::new();
-        response.push(slave_id);
-        response.push(function_code | 0x80); // Set exception bit
-        response.push(exception_code);
-        
-        let crc = Self::calculate_crc(&response);
-        response.extend_from_slice(&crc.to_le_bytes());
-        
-        Ok(response)
-    }
-    
     /// Handle RTU communication loop
     async fn handle_rtu_communication(
         mut port: tokio_serial::SerialStream,
-        register_bank: Arc<ModbusRegisterBank>,
+        store: Arc<S>,
         stats: Arc<Mutex<ServerStats>>,
         mut shutdown_rx: broadcast::Receiver<()>,
         frame_gap: Duration,
+        watchdog: Option<Arc<Watchdog>>,
+        faults: Option<Arc<FaultInjector>>,
     ) {
         info!("🔌 RTU server communication started");
         
@@ -706,8 +965,10 @@ impl ModbusRtuServer {
                                 Self::process_accumulated_frame(
                                     &frame_buffer,
                                     &mut port,
-                                    &register_bank,
-                                    &stats
+                                    &store,
+                                    &stats,
+                                    watchdog.as_deref(),
+                                    faults.as_deref(),
                                 ).await;
                                 frame_buffer.clear();
                             }
@@ -736,8 +997,10 @@ impl ModbusRtuServer {
                                 Self::process_accumulated_frame(
                                     &frame_buffer,
                                     &mut port,
-                                    &register_bank,
-                                    &stats
+                                    &store,
+                                    &stats,
+                                    watchdog.as_deref(),
+                                    faults.as_deref(),
                                 ).await;
                                 frame_buffer.clear();
                             }
@@ -754,8 +1017,10 @@ impl ModbusRtuServer {
     async fn process_accumulated_frame(
         frame: &[u8],
         port: &mut tokio_serial::SerialStream,
-        register_bank: &Arc<ModbusRegisterBank>,
+        store: &Arc<S>,
         stats: &Arc<Mutex<ServerStats>>,
+        watchdog: Option<&Watchdog>,
+        faults: Option<&FaultInjector>,
     ) {
         // Update request stats
         {
@@ -766,62 +1031,99 @@ impl ModbusRtuServer {
         // Create a temporary server instance for processing
         let mut temp_server = ModbusRtuServer {
             config: ModbusRtuServerConfig::default(),
-            register_bank: register_bank.clone(),
+            store: store.clone(),
             stats: stats.clone(),
             shutdown_tx: None,
             is_running: Arc::new(AtomicBool::new(false)),
             start_time: None,
+            watchdog: None,
+            faults: None,
         };
 
-        match temp_server.handle_request(frame).await {
+        let pdu = frame.get(1..frame.len().saturating_sub(2)).unwrap_or_default();
+        // Frames for other slaves are not ours to misbehave on
+        let plan = match faults {
+            Some(faults) if matches!(frame.first(), Some(0 | 1)) => faults.plan(pdu),
+            _ => FaultPlan::default(),
+        };
+        if !plan.delay.is_zero() {
+            tokio::time::sleep(plan.delay).await;
+        }
+        let request = async {
+            match plan.exception {
+                Some(code) => Err(ModbusError::exception(pdu.first().copied().unwrap_or_default(), code)),
+                None => temp_server.handle_request(frame).await,
+            }
+        };
+        let result = match watchdog {
+            Some(watchdog) => watchdog.serve(pdu, request).await,
+            None => request.await,
+        };
+        if !plan.sends_reply() {
+            debug!("Injected fault: reply dropped");
+            return;
+        }
+        match result {
             Ok(response) => {
                 // Calculate CRC for response
                 let mut response_with_crc = response;
-                let crc = Self::calculate_crc(&response_with_crc);
+                let crc = ModbusRtuServer::calculate_crc(&response_with_crc);
                 response_with_crc.extend_from_slice(&crc.to_le_bytes());
+                plan.apply_rtu(&mut response_with_crc);
                 
                 if let Err(e) = port.write_all(&response_with_crc).await {
                     error!("Failed to write response: {}", e);
                 }
             }
+            Err(ModbusError::DeviceNotResponding { .. }) => {
+                // Addressed to another slave
+            }
             Err(e) => {
                 error!("Error processing request: {}", e);
-                // Send exception response if needed
+                // Broadcast requests are never answered
+                if frame.len() >= 2 && frame[0] != 0 {
+                    if let Ok(mut response) = ModbusRtuServer::create_rtu_error_response(frame[0], frame[1], ModbusTcpServer::exception_code(&e)) {
+                        plan.apply_rtu(&mut response);
+                        if let Err(e) = port.write_all(&response).await {
+                            error!("Failed to write exception response: {}", e);
+                        }
+                    }
+                }
             }
         }
     }
     
     // Reuse the same handler methods from TCP server
-    async fn handle_read_01(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_read_01(data, register_bank).await
+    async fn handle_read_01(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_read_01(data, store).await
     }
     
-    async fn handle_read_02(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_read_02(data, register_bank).await
+    async fn handle_read_02(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_read_02(data, store).await
     }
     
-    async fn handle_read_03(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_read_03(data, register_bank).await
+    async fn handle_read_03(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_read_03(data, store).await
     }
     
-    async fn handle_read_04(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_read_04(data, register_bank).await
+    async fn handle_read_04(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_read_04(data, store).await
     }
     
-    async fn handle_write_05(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_write_05(data, register_bank).await
+    async fn handle_write_05(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_write_05(data, store).await
     }
     
-    async fn handle_write_06(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_write_06(data, register_bank).await
+    async fn handle_write_06(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_write_06(data, store).await
     }
     
-    async fn handle_write_0f(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_write_0f(data, register_bank).await
+    async fn handle_write_0f(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_write_0f(data, store).await
     }
     
-    async fn handle_write_10(data: &[u8], register_bank: &Arc<ModbusRegisterBank>) -> ModbusResult<Vec<u8>> {
-        ModbusTcpServer::handle_write_10(data, register_bank).await
+    async fn handle_write_10(data: &[u8], store: &Arc<S>) -> ModbusResult<Vec<u8>> {
+        ModbusTcpServer::<S>::handle_write_10(data, store).await
     }
 }
 
@@ -829,7 +1131,7 @@ impl ModbusRtuServer {
 /// 
 /// Note: This is a placeholder for future implementation
 #[async_trait]
-impl ModbusServer for ModbusRtuServer {
+impl<S: DataStore> ModbusServer for ModbusRtuServer<S> {
     async fn start(&mut self) -> ModbusResult<()> {
         if self.is_running.load(Ordering::Relaxed) {
             return Err(ModbusError::protocol("RTU Server is already running"));
@@ -860,14 +1162,20 @@ impl ModbusServer for ModbusRtuServer {
         info!("   - Parity: {:?}", self.config.parity);
         info!("   - Timeout: {:?}", self.config.timeout);
         
-        let register_bank = self.register_bank.clone();
+        let store = self.store.clone();
         let stats = self.stats.clone();
         let frame_gap = self.config.frame_gap;
         let is_running_flag = self.is_running.clone();
         let shutdown_rx = shutdown_tx.subscribe();
+        let watchdog = self.watchdog.clone();
+        if let Some(watchdog) = &watchdog {
+            let (watchdog, store, shutdown_rx) = (watchdog.clone(), store.clone(), shutdown_tx.subscribe());
+            tokio::spawn(async move { watchdog.run(store.as_ref(), shutdown_rx).await });
+        }
+        let faults = self.faults.clone();
         
         tokio::spawn(async move {
-            Self::handle_rtu_communication(port, register_bank, stats, shutdown_rx, frame_gap).await;
+            Self::handle_rtu_communication(port, store, stats, shutdown_rx, frame_gap, watchdog, faults).await;
             
             is_running_flag.store(false, Ordering::Relaxed);
         });
@@ -899,12 +1207,13 @@ impl ModbusServer for ModbusRtuServer {
             stats.uptime_seconds = start_time.elapsed().as_secs();
         }
         
-        stats.register_bank_stats = Some(self.register_bank.get_stats());
+        stats.register_bank_stats = self.store.register_bank().map(ModbusRegisterBank::get_stats);
         stats
     }
     
     fn get_register_bank(&self) -> Option<Arc<ModbusRegisterBank>> {
-        Some(self.register_bank.clone())
+        // Clones of a register bank share its data
+        self.store.register_bank().map(|bank| Arc::new(bank.clone()))
     }
 }
 
@@ -1055,4 +1364,181 @@ mod tests {
         let registers = register_bank.read_holding_registers(0, 1).unwrap();
         assert_eq!(registers, vec![0x1234]);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_write_observers() {
+        use crate::planner::PointValue;
+        use crate::protocol::RegisterTable;
+
+        let register_bank = Arc::new(ModbusRegisterBank::new());
+        let mut changes = register_bank.subscribe_changes(RegisterTable::HoldingRegister, 0..=99);
+        register_bank.add_write_veto(RegisterTable::HoldingRegister, 0..=99, |event| {
+            event.new.as_registers().is_some_and(|values| values.iter().all(|&value| value < 1000))
+        });
+        let peer: SocketAddr = "192.168.1.20:40000".parse().unwrap();
+
+        // Write single register 5 = 500 from unit 7
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x07, 0x06, 0x00, 0x05, 0x01, 0xF4];
+        ModbusTcpServer::handle_request(&frame, &register_bank, Some(peer)).await.unwrap();
+        let event = changes.recv().await.unwrap();
+        assert_eq!((event.address, event.new), (5, PointValue::Registers(vec![500])));
+        assert_eq!((event.context.unit_id, event.context.peer), (Some(7), Some(peer)));
+
+        // 5000 is vetoed and answered with Illegal Data Value
+        let frame = [0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x07, 0x06, 0x00, 0x05, 0x13, 0x88];
+        let error = ModbusTcpServer::handle_request(&frame, &register_bank, Some(peer)).await.unwrap_err();
+        assert_eq!(ModbusTcpServer::exception_code(&error), 0x03);
+        assert_eq!(ModbusTcpServer::create_error_response(&frame, 0x03).unwrap()[7..], [0x86, 0x03]);
+        assert_eq!(register_bank.read_03(5, 1).unwrap(), vec![500]);
+        assert_eq!(ModbusTcpServer::exception_code(&ModbusError::invalid_function(0x2B)), 0x01);
+    }
+
+    #[tokio::test]
+    async fn test_address_map_exceptions() {
+        use crate::address_map::{AddressMap, ValueConstraint};
+        use crate::protocol::RegisterTable;
+        use crate::tags::Access;
+
+        let map = AddressMap::new()
+            .block(RegisterTable::HoldingRegister, 0..=9, Access::ReadWrite)
+            .block(RegisterTable::HoldingRegister, 10..=19, Access::Read)
+            .constraint(ValueConstraint::range(5, 0, 100));
+        let register_bank = Arc::new(ModbusRegisterBank::new().with_address_map(map).unwrap());
+        register_bank.write_06(10, 42).unwrap();
+
+        // Read of 15..=24 runs past the map: Illegal Data Address
+        let frame = [0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x0F, 0x00, 0x0A];
+        let error = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap_err();
+        assert_eq!(ModbusTcpServer::exception_code(&error), 0x02);
+
+        // Write to the read-only block: Illegal Data Address
+        let frame = [0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x0A, 0x00, 0x01];
+        let error = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap_err();
+        assert_eq!(ModbusTcpServer::exception_code(&error), 0x02);
+        assert_eq!(register_bank.read_03(10, 1).unwrap(), vec![42]);
+
+        // 500 violates the constraint on register 5: Illegal Data Value
+        let frame = [0x00, 0x03, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x05, 0x01, 0xF4];
+        let error = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap_err();
+        assert_eq!(ModbusTcpServer::exception_code(&error), 0x03);
+
+        let frame = [0x00, 0x04, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x0A, 0x00, 0x01];
+        assert!(ModbusTcpServer::handle_request(&frame, &register_bank, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_enron_requests() {
+        use crate::enron::{EnronConfig, EnronEvent};
+        use crate::planner::PointValue;
+        use crate::protocol::RegisterTable;
+
+        let register_bank = Arc::new(ModbusRegisterBank::new().with_enron(EnronConfig::default()));
+        let mbap = [0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+        // Write two floats in the 7000 range: quantity counts 32-bit values
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x10, 0x1B, 0x59, 0x00, 0x02, 0x08]);
+        frame.extend_from_slice(&1.5f32.to_be_bytes());
+        frame.extend_from_slice(&(-2.0f32).to_be_bytes());
+        ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        assert_eq!(register_bank.get_enron_f32(7002).unwrap(), -2.0);
+
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x03, 0x1B, 0x59, 0x00, 0x02]);
+        let response = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        assert_eq!(response[1], 8);
+        assert_eq!(&response[2..6], &1.5f32.to_be_bytes());
+
+        // Read and acknowledge the event log
+        let event = EnronEvent { status: 1, register: 7001, time: 120000.0, date: 101826.0, previous_value: 0.0, current_value: 1.5 };
+        register_bank.push_enron_event(event).unwrap();
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x03, 0x00, 0x20, 0x00, 0x01]);
+        let response = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        assert_eq!(response[1], 20);
+
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x05, 0x00, 0x20, 0xFF, 0x00]);
+        ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        assert_eq!(register_bank.pending_enron_events(), 0);
+
+        // Archive records are selected by the quantity field
+        register_bank.set_enron_archive_record(700, 3, vec![101826.0, 130000.0]).unwrap();
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x03, 0x02, 0xBC, 0x00, 0x03]);
+        let response = ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        assert_eq!(response[1], 8);
+        frame[11] = 0x04;
+        assert!(ModbusTcpServer::handle_request(&frame, &register_bank, None).await.is_err());
+
+        // 32-bit writes of no values or more than 61 are rejected
+        for quantity in [0u16, 62] {
+            let mut frame = mbap.to_vec();
+            frame.extend_from_slice(&[0x10, 0x1B, 0x59]);
+            frame.extend_from_slice(&quantity.to_be_bytes());
+            frame.push((quantity * 4) as u8);
+            frame.resize(frame.len() + quantity as usize * 4, 0);
+            assert!(ModbusTcpServer::handle_request(&frame, &register_bank, None).await.is_err());
+        }
+
+        // 32-bit writes go through vetoes and observers like 16-bit ones
+        let mut changes = register_bank.subscribe_changes(RegisterTable::HoldingRegister, 7002..=7002);
+        register_bank.add_write_veto(RegisterTable::HoldingRegister, 7001..=7001, |event| {
+            event.new != PointValue::Registers(vec![0, 0])
+        });
+        let mut frame = mbap.to_vec();
+        frame.extend_from_slice(&[0x10, 0x1B, 0x59, 0x00, 0x02, 0x08]);
+        frame.extend_from_slice(&[0; 4]);
+        frame.extend_from_slice(&3.0f32.to_be_bytes());
+        assert!(ModbusTcpServer::handle_request(&frame, &register_bank, None).await.is_err());
+        assert_eq!(register_bank.get_enron_f32(7002).unwrap(), -2.0);
+
+        frame[13..17].copy_from_slice(&1.0f32.to_be_bytes());
+        ModbusTcpServer::handle_request(&frame, &register_bank, None).await.unwrap();
+        let event = changes.try_recv().unwrap();
+        let bits = 3.0f32.to_bits();
+        assert_eq!((event.address, event.long), (7002, true));
+        assert_eq!(event.new, PointValue::Registers(vec![(bits >> 16) as u16, bits as u16]));
+        assert_eq!(event.context.unit_id, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_injected_faults() {
+        use crate::fault_injection::{FaultRule, ServerFault};
+
+        let faults = FaultConfig::new()
+            .rule(FaultRule::new(ServerFault::WrongTransactionId).for_addresses(100..=100))
+            .rule(FaultRule::new(ServerFault::busy()).for_function(0x06))
+            .rule(FaultRule::new(ServerFault::ResetConnection).for_function(0x04));
+        let mut server = ModbusTcpServer::new("127.0.0.1:5023").unwrap().with_faults(faults).unwrap();
+        server.store().write_10(0, &[0x1234]).unwrap();
+        server.start().await.unwrap();
+
+        let mut stream = TcpStream::connect("127.0.0.1:5023").await.unwrap();
+        async fn exchange(stream: &mut TcpStream, request: &[u8]) -> Vec<u8> {
+            stream.write_all(request).await.unwrap();
+            let mut reply = vec![0u8; MAX_TCP_FRAME_SIZE];
+            let len = stream.read(&mut reply).await.unwrap_or(0);
+            reply.truncate(len);
+            reply
+        }
+
+        // Replies carry the MBAP header of the request
+        let reply = exchange(&mut stream, &[0x00, 0x07, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x01]).await;
+        assert_eq!(reply, vec![0x00, 0x07, 0x00, 0x00, 0x00, 0x05, 0x01, 0x03, 0x02, 0x12, 0x34]);
+
+        let reply = exchange(&mut stream, &[0x00, 0x08, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x63, 0x00, 0x02]).await;
+        assert_eq!(reply[..2], [0x00, 0x09]);
+
+        // Busy without processing the write
+        let reply = exchange(&mut stream, &[0x00, 0x0A, 0x00, 0x00, 0x00, 0x06, 0x01, 0x06, 0x00, 0x00, 0x00, 0x01]).await;
+        assert_eq!(reply[7..], [0x86, 0x06]);
+        assert_eq!(server.store().read_03(0, 1).unwrap(), vec![0x1234]);
+
+        let reply = exchange(&mut stream, &[0x00, 0x0B, 0x00, 0x00, 0x00, 0x06, 0x01, 0x04, 0x00, 0x00, 0x00, 0x01]).await;
+        assert!(reply.is_empty());
+        assert_eq!(server.faults().unwrap().injected(), 3);
+
+        server.stop().await.unwrap();
+    }
+}
@@ -0,0 +1,828 @@
+//! # Register Bank Simulation
+//!
+//! Hardware-in-the-loop tests need a device whose measurements move. A
+//! [`Simulation`] updates points of a [`ModbusRegisterBank`] on a tokio
+//! interval:
+//!
+//! - **Generators** produce a value from the time since start: sine waves,
+//!   ramps, square waves, uniform noise, counters and constants
+//! - **Expressions** compute a value from other points, e.g.
+//!   `power = voltage * current`
+//!
+//! Points are [`PointDef`]s, so data type, word order and scaling work as in a
+//! point map. A point with neither a generator nor an expression is an input
+//! to expressions: its value is read from the bank on every tick, so it can be
+//! a setpoint written by the Modbus master. Its `initial` value is written
+//! when the simulation is created.
+//!
+//! Expressions support numbers, point names, `+ - * /`, parentheses and the
+//! functions `abs`, `sqrt`, `min` and `max`. Points referenced by an
+//! expression are evaluated first; cycles are rejected.
+//!
+//! ## Example Configuration (YAML)
+//!
+//! ```yaml
+//! tick_ms: 100
+//! seed: 7
+//! points:
+//!   - name: voltage
+//!     table: input
+//!     address: 0
+//!     data_type: f32
+//!     generator: { kind: sine, amplitude: 10, offset: 230, period: 1 }
+//!   - name: current
+//!     table: input
+//!     address: 2
+//!     data_type: f32
+//!     generator: { kind: noise, min: 9.5, max: 10.5 }
+//!   - name: power
+//!     table: input
+//!     address: 4
+//!     data_type: f32
+//!     expression: voltage * current * power_factor
+//!   - name: power_factor
+//!     table: holding
+//!     address: 0
+//!     scale: 0.01
+//!     initial: 0.95
+//!   - name: uptime
+//!     table: input
+//!     address: 6
+//!     data_type: u32
+//!     generator: { kind: counter }
+//! ```
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::ModbusRegisterBank;
+//! use voltage_modbus::simulation::SimulationConfig;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let bank = ModbusRegisterBank::new();
+//! let simulation = bank.start_simulation(SimulationConfig::from_file("meter_sim.yaml")?)?;
+//!
+//! // ... serve the bank ...
+//!
+//! simulation.stop();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::path::Path;
+use std::time::Duration;
+
+use log::warn;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::address::DataAddress;
+use crate::error::{ModbusError, ModbusResult};
+use crate::register_bank::ModbusRegisterBank;
+use crate::tags::{PointDef, TagValue};
+
+fn default_tick_ms() -> u64 {
+    100
+}
+
+fn default_step() -> f64 {
+    1.0
+}
+
+fn default_duty() -> f64 {
+    0.5
+}
+
+/// Source of a simulated value over time
+///
+/// Periods are in seconds; `t` below is the time since the simulation started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Generator {
+    /// Fixed value
+    Constant {
+        /// Value
+        value: f64,
+    },
+    /// `offset + amplitude × sin(2π t / period + phase)`, phase in degrees
+    Sine {
+        /// Peak deviation from the offset
+        amplitude: f64,
+        /// Period in seconds
+        period: f64,
+        /// Center value
+        #[serde(default)]
+        offset: f64,
+        /// Phase shift in degrees
+        #[serde(default)]
+        phase: f64,
+    },
+    /// Sawtooth rising linearly from `from` to `to` once per period
+    Ramp {
+        /// Value at the start of each period
+        from: f64,
+        /// Value approached at the end of each period
+        to: f64,
+        /// Period in seconds
+        period: f64,
+    },
+    /// `high` for the first `duty` fraction of each period, `low` otherwise
+    Square {
+        /// Value outside the pulse
+        low: f64,
+        /// Value during the pulse
+        high: f64,
+        /// Period in seconds
+        period: f64,
+        /// Fraction of the period spent high
+        #[serde(default = "default_duty")]
+        duty: f64,
+    },
+    /// Uniformly distributed random value, drawn on every tick
+    Noise {
+        /// Lower bound
+        min: f64,
+        /// Upper bound
+        max: f64,
+    },
+    /// Value increased by `step` on every tick, restarting at `start` after `max`
+    Counter {
+        /// First value
+        #[serde(default)]
+        start: f64,
+        /// Increment per tick
+        #[serde(default = "default_step")]
+        step: f64,
+        /// Largest value before wrapping
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+}
+
+impl Generator {
+    /// Sine wave around `offset`
+    pub fn sine(amplitude: f64, period: Duration, offset: f64) -> Self {
+        Generator::Sine { amplitude, period: period.as_secs_f64(), offset, phase: 0.0 }
+    }
+
+    /// Ramp from `from` to `to`, repeating every `period`
+    pub fn ramp(from: f64, to: f64, period: Duration) -> Self {
+        Generator::Ramp { from, to, period: period.as_secs_f64() }
+    }
+
+    /// Uniform noise between `min` and `max`
+    pub fn noise(min: f64, max: f64) -> Self {
+        Generator::Noise { min, max }
+    }
+
+    /// Counter from 0 in steps of 1
+    pub fn counter() -> Self {
+        Generator::Counter { start: 0.0, step: 1.0, max: None }
+    }
+
+    fn validate(&self, name: &str) -> ModbusResult<()> {
+        let invalid = |reason: &str| Err(ModbusError::configuration(format!("Point '{}': {}", name, reason)));
+        match *self {
+            Generator::Sine { period, .. } | Generator::Ramp { period, .. } | Generator::Square { period, .. }
+                if !(period.is_finite() && period > 0.0) =>
+            {
+                invalid("period must be positive")
+            }
+            Generator::Square { duty, .. } if !(0.0..=1.0).contains(&duty) => invalid("duty must be between 0 and 1"),
+            Generator::Noise { min, max } if !(min.is_finite() && max.is_finite() && min <= max) => {
+                invalid("noise bounds must be finite with min <= max")
+            }
+            Generator::Counter { start, max: Some(max), .. } if max < start => invalid("counter max is below its start"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Value at `t` seconds; `counter` holds the state of counters between ticks
+    fn value(&self, t: f64, counter: &mut Option<f64>, rng: &mut StdRng) -> f64 {
+        match *self {
+            Generator::Constant { value } => value,
+            Generator::Sine { amplitude, period, offset, phase } => {
+                offset + amplitude * (2.0 * PI * t / period + phase.to_radians()).sin()
+            }
+            Generator::Ramp { from, to, period } => from + (to - from) * (t % period) / period,
+            Generator::Square { low, high, period, duty } => if (t % period) / period < duty { high } else { low },
+            Generator::Noise { min, max } => rng.gen_range(min..=max),
+            Generator::Counter { start, step, max } => {
+                let next = match *counter {
+                    None => start,
+                    Some(previous) => match max {
+                        Some(max) if previous + step > max => start,
+                        _ => previous + step,
+                    },
+                };
+                *counter = Some(next);
+                next
+            }
+        }
+    }
+}
+
+/// One point of a simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedPoint {
+    /// Location and encoding of the value
+    #[serde(flatten)]
+    pub point: PointDef,
+    /// Generator producing the value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<Generator>,
+    /// Expression computing the value from other points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+}
+
+impl SimulatedPoint {
+    /// Point driven by a generator
+    pub fn generated(point: PointDef, generator: Generator) -> Self {
+        Self { point, generator: Some(generator), expression: None }
+    }
+
+    /// Point computed from other points
+    pub fn computed(point: PointDef, expression: impl Into<String>) -> Self {
+        Self { point, generator: None, expression: Some(expression.into()) }
+    }
+
+    /// Point only read by expressions
+    pub fn input(point: PointDef) -> Self {
+        Self { point, generator: None, expression: None }
+    }
+}
+
+/// Simulation configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    /// Update interval in milliseconds
+    #[serde(default = "default_tick_ms")]
+    pub tick_ms: u64,
+    /// Seed of the noise generators, for reproducible runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Simulated points
+    pub points: Vec<SimulatedPoint>,
+}
+
+impl SimulationConfig {
+    /// Simulate `points`, updating every 100 ms
+    pub fn new(points: Vec<SimulatedPoint>) -> Self {
+        Self { tick_ms: default_tick_ms(), seed: None, points }
+    }
+
+    /// Set the update interval
+    pub fn with_tick(mut self, tick: Duration) -> Self {
+        self.tick_ms = tick.as_millis() as u64;
+        self
+    }
+
+    /// Seed the noise generators
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Update interval
+    pub fn tick(&self) -> Duration {
+        Duration::from_millis(self.tick_ms)
+    }
+
+    /// Parse a configuration from YAML
+    pub fn from_yaml_str(yaml: &str) -> ModbusResult<Self> {
+        let config: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| ModbusError::configuration(format!("Invalid simulation: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a configuration from JSON
+    pub fn from_json_str(json: &str) -> ModbusResult<Self> {
+        let config: Self = serde_json::from_str(json)
+            .map_err(|e| ModbusError::configuration(format!("Invalid simulation: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load a configuration from a file; `.json` files are parsed as JSON, anything else as YAML
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ModbusError::configuration(format!("Cannot read {}: {}", path.display(), e)))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&content),
+            _ => Self::from_yaml_str(&content),
+        }
+    }
+
+    /// Serialize the configuration to YAML
+    pub fn to_yaml_string(&self) -> ModbusResult<String> {
+        serde_yaml::to_string(self).map_err(|e| ModbusError::internal(format!("Cannot serialize simulation: {}", e)))
+    }
+
+    /// Check points, generators and expressions
+    pub fn validate(&self) -> ModbusResult<()> {
+        if self.tick_ms == 0 {
+            return Err(ModbusError::configuration("Simulation tick must be positive"));
+        }
+        Program::compile(&self.points).map(|_| ())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Abs,
+    Sqrt,
+    Min,
+    Max,
+}
+
+/// Parsed expression; points are indices into the simulation's points
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Point(usize),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Point(index) => values[*index],
+            Expr::Neg(operand) => -operand.eval(values),
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (left.eval(values), right.eval(values));
+                match op {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    _ => left / right,
+                }
+            }
+            Expr::Call(function, args) => {
+                let mut args = args.iter().map(|arg| arg.eval(values));
+                match function {
+                    Function::Abs => args.next().unwrap_or_default().abs(),
+                    Function::Sqrt => args.next().unwrap_or_default().sqrt(),
+                    Function::Min => args.fold(f64::INFINITY, f64::min),
+                    Function::Max => args.fold(f64::NEG_INFINITY, f64::max),
+                }
+            }
+        }
+    }
+
+    fn points(&self, out: &mut Vec<usize>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Point(index) => out.push(*index),
+            Expr::Neg(operand) => operand.points(out),
+            Expr::Binary(_, left, right) => {
+                left.points(out);
+                right.points(out);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|arg| arg.points(out)),
+        }
+    }
+}
+
+/// Recursive descent parser for expressions
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    names: &'a HashMap<&'a str, usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(text: &'a str, names: &'a HashMap<&'a str, usize>) -> ModbusResult<Expr> {
+        let mut parser = Parser { text, pos: 0, names };
+        let expr = parser.sum()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(expr),
+            Some(c) => Err(parser.error(&format!("unexpected '{}'", c))),
+        }
+    }
+
+    fn sum(&mut self) -> ModbusResult<Expr> {
+        let mut expr = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.next_symbol() {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.product()?));
+        }
+        Ok(expr)
+    }
+
+    fn product(&mut self) -> ModbusResult<Expr> {
+        let mut expr = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.next_symbol() {
+            self.pos += 1;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> ModbusResult<Expr> {
+        match self.next_symbol() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some('(') => {
+                self.pos += 1;
+                let expr = self.sum()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => self.name(),
+            Some(c) => Err(self.error(&format!("unexpected '{}'", c))),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn number(&mut self) -> ModbusResult<Expr> {
+        let text = self.take_while(|c| c.is_ascii_digit() || c == '.');
+        text.parse().map(Expr::Number).map_err(|_| self.error(&format!("invalid number '{}'", text)))
+    }
+
+    fn name(&mut self) -> ModbusResult<Expr> {
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if self.next_symbol() != Some('(') {
+            return self.names.get(name).map(|&index| Expr::Point(index)).ok_or_else(|| self.error(&format!("unknown point '{}'", name)));
+        }
+
+        let (function, arity) = match name {
+            "abs" => (Function::Abs, Some(1)),
+            "sqrt" => (Function::Sqrt, Some(1)),
+            "min" => (Function::Min, None),
+            "max" => (Function::Max, None),
+            _ => return Err(self.error(&format!("unknown function '{}'", name))),
+        };
+        self.pos += 1;
+        let mut args = vec![self.sum()?];
+        while self.next_symbol() == Some(',') {
+            self.pos += 1;
+            args.push(self.sum()?);
+        }
+        self.expect(')')?;
+        if arity.is_some_and(|arity| arity != args.len()) {
+            return Err(self.error(&format!("{} takes one argument", name)));
+        }
+        Ok(Expr::Call(function, args))
+    }
+
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&accept) {
+            self.pos += self.peek().map_or(0, char::len_utf8);
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn expect(&mut self, symbol: char) -> ModbusResult<()> {
+        if self.next_symbol() != Some(symbol) {
+            return Err(self.error(&format!("expected '{}'", symbol)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// Next character after whitespace
+    fn next_symbol(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.peek()
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn error(&self, reason: &str) -> ModbusError {
+        ModbusError::configuration(format!("Invalid expression '{}': {} at offset {}", self.text, reason, self.pos))
+    }
+}
+
+/// How a point gets its value
+#[derive(Debug)]
+enum Source {
+    Generator(Generator),
+    Expression(Expr),
+    Input,
+}
+
+/// Validated points with expressions in evaluation order
+#[derive(Debug)]
+struct Program {
+    points: Vec<PointDef>,
+    sources: Vec<Source>,
+    /// Expression points, each after the expression points it references
+    order: Vec<usize>,
+}
+
+impl Program {
+    fn compile(points: &[SimulatedPoint]) -> ModbusResult<Self> {
+        let mut names = HashMap::new();
+        for (i, simulated) in points.iter().enumerate() {
+            simulated.point.validate()?;
+            if names.insert(simulated.point.name.as_str(), i).is_some() {
+                return Err(ModbusError::configuration(format!("Duplicate point name '{}'", simulated.point.name)));
+            }
+        }
+
+        let mut sources = Vec::with_capacity(points.len());
+        for simulated in points {
+            let source = match (&simulated.generator, &simulated.expression) {
+                (Some(generator), None) => {
+                    generator.validate(&simulated.point.name)?;
+                    Source::Generator(generator.clone())
+                }
+                (None, Some(expression)) => Source::Expression(Parser::parse(expression, &names)?),
+                (None, None) => Source::Input,
+                (Some(_), Some(_)) => {
+                    return Err(ModbusError::configuration(format!(
+                        "Point '{}' has both a generator and an expression",
+                        simulated.point.name
+                    )))
+                }
+            };
+            sources.push(source);
+        }
+
+        let mut program = Self { points: points.iter().map(|simulated| simulated.point.clone()).collect(), sources, order: Vec::new() };
+        let mut state = vec![Visit::New; points.len()];
+        for i in 0..points.len() {
+            program.visit(i, &mut state)?;
+        }
+        Ok(program)
+    }
+
+    /// Depth-first topological sort of the expression points
+    fn visit(&mut self, index: usize, state: &mut [Visit]) -> ModbusResult<()> {
+        match state[index] {
+            Visit::Done => return Ok(()),
+            Visit::Active => {
+                return Err(ModbusError::configuration(format!("Expression of point '{}' depends on itself", self.points[index].name)))
+            }
+            Visit::New => {}
+        }
+
+        if let Source::Expression(expr) = &self.sources[index] {
+            state[index] = Visit::Active;
+            let mut dependencies = Vec::new();
+            expr.points(&mut dependencies);
+            for dependency in dependencies {
+                self.visit(dependency, state)?;
+            }
+            self.order.push(index);
+        }
+        state[index] = Visit::Done;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    Active,
+    Done,
+}
+
+/// Simulation state, updated explicitly
+///
+/// [`Simulation`] drives it from a tokio interval; tests can call
+/// [`update`](Self::update) with chosen times instead.
+#[derive(Debug)]
+pub struct Simulator {
+    bank: ModbusRegisterBank,
+    program: Program,
+    counters: Vec<Option<f64>>,
+    rng: StdRng,
+}
+
+impl Simulator {
+    /// Compile `config` and write the initial values of its input points
+    ///
+    /// # Returns
+    ///
+    /// The simulator, or a configuration error
+    pub fn new(bank: &ModbusRegisterBank, config: &SimulationConfig) -> ModbusResult<Self> {
+        let program = Program::compile(&config.points)?;
+        let bank = bank.protocol_view();
+        for (point, source) in program.points.iter().zip(&program.sources) {
+            if let (Source::Input, Some(initial)) = (source, point.initial) {
+                bank.write_at(DataAddress::new(point.table, point.address), &point.encode(initial)?)?;
+            }
+        }
+
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let counters = vec![None; program.points.len()];
+        Ok(Self { bank, program, counters, rng })
+    }
+
+    /// Evaluate every point at `elapsed` since start and write the results
+    ///
+    /// A point that cannot be written (e.g. a value out of range of its data
+    /// type) does not stop the others.
+    ///
+    /// # Returns
+    ///
+    /// The first error encountered, if any
+    pub fn update(&mut self, elapsed: Duration) -> ModbusResult<()> {
+        let t = elapsed.as_secs_f64();
+        let program = &self.program;
+        let mut values = vec![0.0; program.points.len()];
+        let mut result = Ok(());
+
+        for (i, (point, source)) in program.points.iter().zip(&program.sources).enumerate() {
+            match source {
+                Source::Generator(generator) => values[i] = generator.value(t, &mut self.counters[i], &mut self.rng),
+                Source::Input => {
+                    let address = DataAddress::new(point.table, point.address);
+                    match self.bank.read_at(address, point.length()).and_then(|data| point.decode(&data)) {
+                        Ok(value) => values[i] = value.as_f64(),
+                        Err(e) => result = result.and(Err(e)),
+                    }
+                }
+                Source::Expression(_) => {}
+            }
+        }
+        for &i in &program.order {
+            if let Source::Expression(expr) = &program.sources[i] {
+                values[i] = expr.eval(&values);
+            }
+        }
+
+        for (i, (point, source)) in program.points.iter().zip(&program.sources).enumerate() {
+            if matches!(source, Source::Input) {
+                continue;
+            }
+            let written = point
+                .encode(TagValue::Number(values[i]))
+                .and_then(|data| self.bank.write_at(DataAddress::new(point.table, point.address), &data));
+            if let Err(e) = written {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+/// Running simulation of one register bank
+///
+/// Dropping it stops the updates.
+#[derive(Debug)]
+pub struct Simulation {
+    task: JoinHandle<()>,
+}
+
+impl Simulation {
+    /// Start updating `bank` every tick of `config`
+    ///
+    /// The first update happens immediately. Must be called within a tokio
+    /// runtime.
+    ///
+    /// # Returns
+    ///
+    /// The running simulation, or a configuration error
+    pub fn start(bank: &ModbusRegisterBank, config: SimulationConfig) -> ModbusResult<Self> {
+        config.validate()?;
+        let mut simulator = Simulator::new(bank, &config)?;
+        let mut interval = tokio::time::interval(config.tick());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let task = tokio::spawn(async move {
+            let start = Instant::now();
+            loop {
+                interval.tick().await;
+                if let Err(e) = simulator.update(start.elapsed()) {
+                    warn!("Simulation update failed: {}", e);
+                }
+            }
+        });
+        Ok(Self { task })
+    }
+
+    /// Stop updating
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RegisterTable;
+    use crate::tags::DataType;
+
+    const CONFIG: &str = r#"
+seed: 7
+points:
+  - name: voltage
+    table: input
+    address: 0
+    data_type: f32
+    generator: { kind: sine, amplitude: 10, offset: 230, period: 1 }
+  - name: current
+    table: input
+    address: 2
+    data_type: f64
+    generator: { kind: noise, min: 9.5, max: 10.5 }
+  - name: power
+    table: input
+    address: 6
+    data_type: f64
+    expression: voltage * current * power_factor
+  - name: power_factor
+    table: holding
+    address: 0
+    scale: 0.01
+    initial: 0.5
+  - name: ticks
+    table: input
+    address: 10
+    generator: { kind: counter, start: 1, max: 2 }
+  - name: alarm
+    table: discrete
+    address: 0
+    generator: { kind: square, low: 0, high: 1, period: 2 }
+"#;
+
+    #[test]
+    fn test_generators_and_expressions() {
+        let config = SimulationConfig::from_yaml_str(CONFIG).unwrap();
+        assert_eq!(config.tick(), Duration::from_millis(100));
+        assert_eq!(SimulationConfig::from_yaml_str(&config.to_yaml_string().unwrap()).unwrap(), config);
+
+        let bank = ModbusRegisterBank::new();
+        let mut simulator = Simulator::new(&bank, &config).unwrap();
+        assert_eq!(bank.read_03(0, 1).unwrap(), vec![50]);
+
+        let map = crate::tags::PointMap::new(config.points.iter().map(|simulated| simulated.point.clone()).collect()).unwrap();
+        let mut ticks = Vec::new();
+        for elapsed in [Duration::from_millis(250), Duration::from_millis(1250), Duration::from_millis(2250)] {
+            simulator.update(elapsed).unwrap();
+            let voltage = map.read_bank(&bank, "voltage").unwrap().as_f64();
+            let current = map.read_bank(&bank, "current").unwrap().as_f64();
+            assert!((voltage - 240.0).abs() < 1e-3);
+            assert!((9.5..=10.5).contains(&current));
+            assert!((map.read_bank(&bank, "power").unwrap().as_f64() - voltage * current * 0.5).abs() < 1e-2);
+            ticks.push(bank.read_04(10, 1).unwrap()[0]);
+        }
+        assert_eq!(ticks, vec![1, 2, 1]);
+        assert_eq!(bank.read_02(0, 1).unwrap(), vec![true]);
+    }
+
+    #[test]
+    fn test_invalid_configurations() {
+        let point = |name: &str, address| PointDef::new(name, RegisterTable::InputRegister, address).with_data_type(DataType::F32);
+        let cycle = SimulationConfig::new(vec![
+            SimulatedPoint::computed(point("a", 0), "b + 1"),
+            SimulatedPoint::computed(point("b", 2), "max(a, 2)"),
+        ]);
+        assert!(cycle.validate().unwrap_err().to_string().contains("depends on itself"));
+
+        let unknown = SimulationConfig::new(vec![SimulatedPoint::computed(point("a", 0), "2 * (b")]);
+        assert!(unknown.validate().unwrap_err().to_string().contains("unknown point 'b'"));
+
+        let period = SimulationConfig::new(vec![SimulatedPoint::generated(point("a", 0), Generator::ramp(0.0, 1.0, Duration::ZERO))]);
+        assert!(period.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_simulation_runs_on_interval() {
+        let bank = ModbusRegisterBank::new();
+        let config = SimulationConfig::new(vec![SimulatedPoint::generated(
+            PointDef::new("count", RegisterTable::InputRegister, 0),
+            Generator::counter(),
+        )])
+        .with_tick(Duration::from_millis(10));
+
+        let simulation = bank.start_simulation(config).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        simulation.stop();
+        assert!(bank.read_04(0, 1).unwrap()[0] >= 3);
+    }
+}
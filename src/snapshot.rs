@@ -0,0 +1,323 @@
+//! # Register Bank Snapshots
+//!
+//! A [`BankSnapshot`] is a full image of the four tables of a
+//! [`ModbusRegisterBank`](crate::register_bank::ModbusRegisterBank), taken
+//! with [`snapshot`](crate::register_bank::ModbusRegisterBank::snapshot) and
+//! applied with [`restore`](crate::register_bank::ModbusRegisterBank::restore).
+//! Each table is stored as runs of consecutive addresses; addresses that were
+//! never written are left out.
+//!
+//! Snapshots are saved in one of three formats, chosen by file extension:
+//!
+//! - **JSON** (`.json`) and **YAML** (`.yaml`, `.yml`), for inspection and
+//!   hand-editing
+//! - **Binary** (anything else), compact and protected by a CRC-32
+//!
+//! ## Example Snapshot (YAML)
+//!
+//! ```yaml
+//! coils:
+//!   - address: 0
+//!     values: [true, false, true]
+//! holding_registers:
+//!   - address: 100
+//!     values: [2300, 50]
+//! ```
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::ModbusRegisterBank;
+//! use voltage_modbus::snapshot::{BankSnapshot, SnapshotFormat};
+//!
+//! let bank = ModbusRegisterBank::new();
+//! bank.write_10(100, &[2300, 50]).unwrap();
+//!
+//! let bytes = bank.snapshot().unwrap().encode(SnapshotFormat::Binary).unwrap();
+//! let restored = ModbusRegisterBank::new();
+//! restored.restore(&BankSnapshot::decode(SnapshotFormat::Binary, &bytes).unwrap()).unwrap();
+//! assert_eq!(restored.read_03(100, 2).unwrap(), vec![2300, 50]);
+//! ```
+
+use std::path::Path;
+
+use crc::{Crc, CRC_32_ISO_HDLC};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ModbusError, ModbusResult};
+
+/// Magic bytes at the start of a binary snapshot
+const MAGIC: &[u8; 8] = b"VMBSNAP1";
+
+/// Checksum of binary snapshots
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+/// Number of addresses in a Modbus table
+const TABLE_SIZE: usize = 0x10000;
+
+/// Snapshot file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SnapshotFormat {
+    /// JSON document
+    Json,
+    /// YAML document
+    Yaml,
+    /// Compact binary image with CRC-32
+    Binary,
+}
+
+impl SnapshotFormat {
+    /// Format for a file: `.json`, `.yaml`/`.yml`, otherwise binary
+    pub fn for_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("json") => SnapshotFormat::Json,
+            Some("yaml") | Some("yml") => SnapshotFormat::Yaml,
+            _ => SnapshotFormat::Binary,
+        }
+    }
+}
+
+/// Values of consecutive addresses
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Run<T> {
+    /// First protocol address
+    pub address: u16,
+    /// Values from `address` on
+    pub values: Vec<T>,
+}
+
+impl<T: Copy> Run<T> {
+    /// Group address-ordered entries into runs of consecutive addresses
+    pub(crate) fn from_entries(entries: Vec<(u16, T)>) -> Vec<Self> {
+        let mut runs: Vec<Self> = Vec::new();
+        for (address, value) in entries {
+            match runs.last_mut() {
+                Some(run) if run.address as usize + run.values.len() == address as usize => run.values.push(value),
+                _ => runs.push(Self { address, values: vec![value] }),
+            }
+        }
+        runs
+    }
+
+    /// Expand runs into entries, rejecting runs past the end of the table
+    pub(crate) fn to_entries(runs: &[Self]) -> ModbusResult<Vec<(u16, T)>> {
+        let mut entries = Vec::new();
+        for run in runs {
+            if run.address as usize + run.values.len() > TABLE_SIZE {
+                return Err(ModbusError::invalid_data(format!("Snapshot run at {} runs past the end of the table", run.address)));
+            }
+            entries.extend(run.values.iter().enumerate().map(|(i, &value)| (run.address + i as u16, value)));
+        }
+        Ok(entries)
+    }
+}
+
+/// Full image of a register bank's four tables
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BankSnapshot {
+    /// Coil runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub coils: Vec<Run<bool>>,
+    /// Discrete input runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discrete_inputs: Vec<Run<bool>>,
+    /// Holding register runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holding_registers: Vec<Run<u16>>,
+    /// Input register runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_registers: Vec<Run<u16>>,
+}
+
+impl BankSnapshot {
+    /// Serialize in the given format
+    pub fn encode(&self, format: SnapshotFormat) -> ModbusResult<Vec<u8>> {
+        match format {
+            SnapshotFormat::Json => serde_json::to_vec_pretty(self)
+                .map_err(|e| ModbusError::internal(format!("Cannot serialize snapshot: {}", e))),
+            SnapshotFormat::Yaml => serde_yaml::to_string(self)
+                .map(String::into_bytes)
+                .map_err(|e| ModbusError::internal(format!("Cannot serialize snapshot: {}", e))),
+            SnapshotFormat::Binary => Ok(self.to_bytes()),
+        }
+    }
+
+    /// Parse a snapshot in the given format
+    pub fn decode(format: SnapshotFormat, data: &[u8]) -> ModbusResult<Self> {
+        match format {
+            SnapshotFormat::Json => serde_json::from_slice(data)
+                .map_err(|e| ModbusError::invalid_data(format!("Invalid snapshot: {}", e))),
+            SnapshotFormat::Yaml => serde_yaml::from_slice(data)
+                .map_err(|e| ModbusError::invalid_data(format!("Invalid snapshot: {}", e))),
+            SnapshotFormat::Binary => Self::from_bytes(data),
+        }
+    }
+
+    /// Write the snapshot to a file, in the format given by its extension
+    ///
+    /// The file is replaced atomically, so a crash leaves either the old or
+    /// the new snapshot.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> ModbusResult<()> {
+        let path = path.as_ref();
+        let data = self.encode(SnapshotFormat::for_path(path))?;
+        let io_error = |e: std::io::Error| ModbusError::io(format!("Cannot write {}: {}", path.display(), e));
+
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let file = {
+            use std::io::Write;
+            let mut file = std::fs::File::create(&temp).map_err(io_error)?;
+            file.write_all(&data).map_err(io_error)?;
+            file
+        };
+        file.sync_all().map_err(io_error)?;
+        std::fs::rename(&temp, path).map_err(io_error)
+    }
+
+    /// Read a snapshot from a file, in the format given by its extension
+    pub fn load<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| ModbusError::io(format!("Cannot read {}: {}", path.display(), e)))?;
+        Self::decode(SnapshotFormat::for_path(path), &data)
+    }
+
+    /// Encode as a binary image
+    ///
+    /// Layout: magic `VMBSNAP1`, then per table (coils, discrete inputs,
+    /// holding registers, input registers) a u32 run count and the runs as
+    /// u16 address, u32 length and values (bits packed LSB first, registers as
+    /// u16), then a CRC-32 of everything before it. Integers are little-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        encode_runs(&mut data, &self.coils, encode_bits);
+        encode_runs(&mut data, &self.discrete_inputs, encode_bits);
+        encode_runs(&mut data, &self.holding_registers, encode_words);
+        encode_runs(&mut data, &self.input_registers, encode_words);
+        let crc = CRC32.checksum(&data);
+        data.extend_from_slice(&crc.to_le_bytes());
+        data
+    }
+
+    /// Decode a binary image
+    pub fn from_bytes(data: &[u8]) -> ModbusResult<Self> {
+        if data.len() < MAGIC.len() + 4 || &data[..MAGIC.len()] != MAGIC {
+            return Err(ModbusError::invalid_data("Not a binary register bank snapshot"));
+        }
+        let (body, crc) = data.split_at(data.len() - 4);
+        let expected = u32::from_le_bytes([crc[0], crc[1], crc[2], crc[3]]);
+        if CRC32.checksum(body) != expected {
+            return Err(ModbusError::invalid_data("Snapshot checksum mismatch"));
+        }
+
+        let mut reader = Reader { data: &body[MAGIC.len()..] };
+        let snapshot = Self {
+            coils: reader.runs(decode_bits)?,
+            discrete_inputs: reader.runs(decode_bits)?,
+            holding_registers: reader.runs(decode_words)?,
+            input_registers: reader.runs(decode_words)?,
+        };
+        if !reader.data.is_empty() {
+            return Err(ModbusError::invalid_data("Trailing data in snapshot"));
+        }
+        Ok(snapshot)
+    }
+}
+
+fn encode_runs<T>(data: &mut Vec<u8>, runs: &[Run<T>], encode: fn(&mut Vec<u8>, &[T])) {
+    data.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+    for run in runs {
+        data.extend_from_slice(&run.address.to_le_bytes());
+        data.extend_from_slice(&(run.values.len() as u32).to_le_bytes());
+        encode(data, &run.values);
+    }
+}
+
+fn encode_bits(data: &mut Vec<u8>, values: &[bool]) {
+    data.extend(values.chunks(8).map(|chunk| chunk.iter().enumerate().fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << i))));
+}
+
+fn encode_words(data: &mut Vec<u8>, values: &[u16]) {
+    data.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+}
+
+fn decode_bits(reader: &mut Reader<'_>, len: usize) -> ModbusResult<Vec<bool>> {
+    let bytes = reader.take(len.div_ceil(8))?;
+    Ok((0..len).map(|i| bytes[i / 8] & (1 << (i % 8)) != 0).collect())
+}
+
+fn decode_words(reader: &mut Reader<'_>, len: usize) -> ModbusResult<Vec<u16>> {
+    let bytes = reader.take(len * 2)?;
+    Ok(bytes.chunks(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect())
+}
+
+/// Cursor over a binary snapshot
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> ModbusResult<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(ModbusError::invalid_data("Truncated snapshot"));
+        }
+        let (head, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> ModbusResult<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> ModbusResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn runs<T>(&mut self, decode: fn(&mut Self, usize) -> ModbusResult<Vec<T>>) -> ModbusResult<Vec<Run<T>>> {
+        let count = self.u32()?;
+        let mut runs = Vec::new();
+        for _ in 0..count {
+            let address = self.u16()?;
+            let len = self.u32()? as usize;
+            if address as usize + len > TABLE_SIZE {
+                return Err(ModbusError::invalid_data(format!("Snapshot run at {} runs past the end of the table", address)));
+            }
+            runs.push(Run { address, values: decode(self, len)? });
+        }
+        Ok(runs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_round_trip() {
+        let snapshot = BankSnapshot {
+            coils: Run::from_entries(vec![(0, true), (1, false), (2, true), (9, true)]),
+            discrete_inputs: Vec::new(),
+            holding_registers: vec![Run { address: 65534, values: vec![1, 2] }],
+            input_registers: vec![Run { address: 0, values: (0..300).collect() }],
+        };
+        assert_eq!(snapshot.coils.len(), 2);
+        assert_eq!(Run::to_entries(&snapshot.holding_registers).unwrap(), vec![(65534, 1), (65535, 2)]);
+
+        for format in [SnapshotFormat::Json, SnapshotFormat::Yaml, SnapshotFormat::Binary] {
+            let data = snapshot.encode(format).unwrap();
+            assert_eq!(BankSnapshot::decode(format, &data).unwrap(), snapshot, "{:?}", format);
+        }
+
+        let mut data = snapshot.to_bytes();
+        data[12] ^= 1;
+        assert!(BankSnapshot::from_bytes(&data).is_err());
+        assert!(BankSnapshot::from_bytes(&data[..10]).is_err());
+
+        let overflowing = BankSnapshot { holding_registers: vec![Run { address: 65535, values: vec![1, 2] }], ..Default::default() };
+        assert!(BankSnapshot::from_bytes(&overflowing.to_bytes()).is_err());
+        assert_eq!(SnapshotFormat::for_path("bank.yml"), SnapshotFormat::Yaml);
+        assert_eq!(SnapshotFormat::for_path("bank.img"), SnapshotFormat::Binary);
+    }
+}
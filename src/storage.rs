@@ -0,0 +1,338 @@
+//! # Register Bank Storage
+//!
+//! [`ModbusRegisterBank`](crate::register_bank::ModbusRegisterBank) keeps each
+//! data table in one of two backends, chosen with [`StorageBackend`]:
+//!
+//! - **Sparse** (default): a locked `HashMap`, small for devices that only
+//!   populate a few addresses
+//! - **Dense**: all 65536 addresses in a flat array (registers) or bitset
+//!   (coils and discrete inputs) of atomics
+//!
+//! Dense tables are guarded by a sequence lock: readers never block, they copy
+//! the requested range and retry if a writer published in the meantime, so a
+//! multi-register write is never observed half-applied. Writers are serialized
+//! by a mutex. Reads can go straight into a caller buffer, e.g.
+//! [`read_holding_registers_into`](crate::register_bank::ModbusRegisterBank::read_holding_registers_into).
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use voltage_modbus::{ModbusRegisterBank, StorageBackend};
+//!
+//! let bank = ModbusRegisterBank::with_backend(StorageBackend::Dense);
+//! bank.write_10(100, &[1, 2, 3]).unwrap();
+//!
+//! let mut buffer = [0u16; 3];
+//! bank.read_holding_registers_into(100, &mut buffer).unwrap();
+//! assert_eq!(buffer, [1, 2, 3]);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hint;
+use std::sync::atomic::{fence, AtomicU16, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ModbusError, ModbusResult};
+
+/// Number of addresses in a Modbus table
+const TABLE_SIZE: usize = 0x10000;
+
+/// How a register bank stores its tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// Locked hash map holding only the addresses written so far
+    #[default]
+    Sparse,
+    /// Lock-free readable array of all 65536 addresses
+    Dense,
+}
+
+/// Value types a table can hold
+pub(crate) trait StoredValue: Copy + Default + PartialEq + Send + Sync + 'static {
+    /// Dense array of this value type
+    type Dense: DenseArray<Self>;
+}
+
+impl StoredValue for bool {
+    type Dense = DenseBits;
+}
+
+impl StoredValue for u16 {
+    type Dense = DenseWords;
+}
+
+/// Fixed array of all addresses of a table, accessed through atomics
+pub(crate) trait DenseArray<T>: Send + Sync {
+    fn new() -> Self;
+    fn get(&self, index: usize) -> T;
+    fn set(&self, index: usize, value: T);
+}
+
+/// 65536 registers
+pub(crate) struct DenseWords(Box<[AtomicU16]>);
+
+impl DenseArray<u16> for DenseWords {
+    fn new() -> Self {
+        Self((0..TABLE_SIZE).map(|_| AtomicU16::new(0)).collect())
+    }
+
+    fn get(&self, index: usize) -> u16 {
+        self.0[index].load(Ordering::Relaxed)
+    }
+
+    fn set(&self, index: usize, value: u16) {
+        self.0[index].store(value, Ordering::Relaxed);
+    }
+}
+
+/// 65536 bits packed into 64-bit words
+pub(crate) struct DenseBits(Box<[AtomicU64]>);
+
+impl DenseArray<bool> for DenseBits {
+    fn new() -> Self {
+        Self((0..TABLE_SIZE / 64).map(|_| AtomicU64::new(0)).collect())
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.0[index / 64].load(Ordering::Relaxed) & (1 << (index % 64)) != 0
+    }
+
+    fn set(&self, index: usize, value: bool) {
+        let mask = 1 << (index % 64);
+        if value {
+            self.0[index / 64].fetch_or(mask, Ordering::Relaxed);
+        } else {
+            self.0[index / 64].fetch_and(!mask, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Dense array guarded by a sequence lock
+pub(crate) struct DenseTable<T: StoredValue> {
+    values: T::Dense,
+    /// Odd while a write is being published
+    sequence: AtomicU64,
+    writer: Mutex<()>,
+}
+
+impl<T: StoredValue> DenseTable<T> {
+    fn new() -> Self {
+        Self { values: T::Dense::new(), sequence: AtomicU64::new(0), writer: Mutex::new(()) }
+    }
+
+    fn read_into(&self, address: u16, out: &mut [T]) {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before & 1 == 0 {
+                for (i, value) in out.iter_mut().enumerate() {
+                    *value = self.values.get(index(address, i));
+                }
+                fence(Ordering::Acquire);
+                if self.sequence.load(Ordering::Relaxed) == before {
+                    return;
+                }
+            }
+            hint::spin_loop();
+        }
+    }
+
+    fn lock(&self) -> ModbusResult<MutexGuard<'_, ()>> {
+        self.writer.lock().map_err(|_| ModbusError::internal("Failed to lock dense table"))
+    }
+
+    /// Publish values; the caller holds the writer lock
+    fn publish(&self, address: u16, values: &[T]) {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        self.sequence.store(sequence + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (i, &value) in values.iter().enumerate() {
+            self.values.set(index(address, i), value);
+        }
+        self.sequence.store(sequence + 2, Ordering::Release);
+    }
+}
+
+/// Array index of the `offset`-th address after `address`, wrapping like the sparse backend
+fn index(address: u16, offset: usize) -> usize {
+    (address as usize + offset) % TABLE_SIZE
+}
+
+/// Storage of one data table
+pub(crate) enum Table<T: StoredValue> {
+    Sparse(RwLock<HashMap<u16, T>>),
+    Dense(Box<DenseTable<T>>),
+}
+
+impl<T: StoredValue> Table<T> {
+    pub(crate) fn new(backend: StorageBackend) -> Self {
+        match backend {
+            StorageBackend::Sparse => Table::Sparse(RwLock::new(HashMap::new())),
+            StorageBackend::Dense => Table::Dense(Box::new(DenseTable::new())),
+        }
+    }
+
+    /// Fill `out` with consecutive values; unset addresses read as the default
+    pub(crate) fn read_into(&self, address: u16, out: &mut [T]) -> ModbusResult<()> {
+        match self {
+            Table::Sparse(map) => {
+                let map = map.read().map_err(|_| ModbusError::internal("Failed to lock table"))?;
+                for (i, value) in out.iter_mut().enumerate() {
+                    *value = map.get(&address.wrapping_add(i as u16)).copied().unwrap_or_default();
+                }
+            }
+            Table::Dense(table) => table.read_into(address, out),
+        }
+        Ok(())
+    }
+
+    /// Write consecutive values as one step for concurrent readers
+    ///
+    /// With `with_old`, `check` first receives the values being replaced while
+    /// other writers are excluded; an error from it cancels the write.
+    pub(crate) fn write<F>(&self, address: u16, values: &[T], with_old: bool, check: F) -> ModbusResult<()>
+    where
+        F: FnOnce(Vec<T>) -> ModbusResult<()>,
+    {
+        match self {
+            Table::Sparse(map) => {
+                let mut map = map.write().map_err(|_| ModbusError::internal("Failed to lock table"))?;
+                if with_old {
+                    let old = (0..values.len()).map(|i| map.get(&address.wrapping_add(i as u16)).copied().unwrap_or_default()).collect();
+                    check(old)?;
+                }
+                for (i, &value) in values.iter().enumerate() {
+                    map.insert(address.wrapping_add(i as u16), value);
+                }
+            }
+            Table::Dense(table) => {
+                let _writer = table.lock()?;
+                if with_old {
+                    let mut old = vec![T::default(); values.len()];
+                    table.read_into(address, &mut old);
+                    check(old)?;
+                }
+                table.publish(address, values);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stored points in address order
+    ///
+    /// Sparse tables return the addresses written; dense tables the
+    /// addresses holding a non-default value. Like a write, this waits for
+    /// the write in progress, so a write that passed its check is included.
+    pub(crate) fn entries(&self) -> ModbusResult<Vec<(u16, T)>> {
+        match self {
+            Table::Sparse(map) => {
+                let map = map.read().map_err(|_| ModbusError::internal("Failed to lock table"))?;
+                let mut entries: Vec<(u16, T)> = map.iter().map(|(&address, &value)| (address, value)).collect();
+                entries.sort_unstable_by_key(|&(address, _)| address);
+                Ok(entries)
+            }
+            Table::Dense(table) => {
+                let _writer = table.lock()?;
+                let mut values = vec![T::default(); TABLE_SIZE];
+                table.read_into(0, &mut values);
+                Ok((0..=u16::MAX).zip(values).filter(|&(_, value)| value != T::default()).collect())
+            }
+        }
+    }
+
+    /// Replace the whole table with `entries`
+    pub(crate) fn replace_all(&self, entries: &[(u16, T)]) -> ModbusResult<()> {
+        match self {
+            Table::Sparse(map) => {
+                let mut map = map.write().map_err(|_| ModbusError::internal("Failed to lock table"))?;
+                map.clear();
+                map.extend(entries.iter().copied());
+            }
+            Table::Dense(table) => {
+                let _writer = table.lock()?;
+                let mut values = vec![T::default(); TABLE_SIZE];
+                for &(address, value) in entries {
+                    values[address as usize] = value;
+                }
+                table.publish(0, &values);
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of stored points
+    ///
+    /// Sparse tables count the addresses written; dense tables count the
+    /// addresses holding a non-default value.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Table::Sparse(map) => map.read().map(|map| map.len()).unwrap_or(0),
+            Table::Dense(table) => (0..TABLE_SIZE).filter(|&i| table.values.get(i) != T::default()).count(),
+        }
+    }
+
+    pub(crate) fn backend(&self) -> StorageBackend {
+        match self {
+            Table::Sparse(_) => StorageBackend::Sparse,
+            Table::Dense(_) => StorageBackend::Dense,
+        }
+    }
+}
+
+impl<T: StoredValue> fmt::Debug for Table<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Table").field("backend", &self.backend()).field("len", &self.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_backends_agree() {
+        for backend in [StorageBackend::Sparse, StorageBackend::Dense] {
+            let registers = Table::<u16>::new(backend);
+            registers.write(65534, &[1, 2, 3], false, |_| Ok(())).unwrap();
+            let mut out = [9u16; 4];
+            registers.read_into(65533, &mut out).unwrap();
+            assert_eq!(out, [0, 1, 2, 3], "{:?}", backend);
+            assert_eq!(registers.len(), 3);
+
+            let coils = Table::<bool>::new(backend);
+            coils.write(63, &[true, false, true], false, |_| Ok(())).unwrap();
+            let result = coils.write(63, &[false], true, |old| {
+                assert_eq!(old, vec![true]);
+                Err(ModbusError::invalid_data("rejected"))
+            });
+            assert!(result.is_err());
+            let mut out = [false; 3];
+            coils.read_into(63, &mut out).unwrap();
+            assert_eq!(out, [true, false, true]);
+        }
+    }
+
+    #[test]
+    fn test_dense_writes_are_atomic() {
+        let table = Arc::new(Table::<u16>::new(StorageBackend::Dense));
+        let writer = {
+            let table = table.clone();
+            std::thread::spawn(move || {
+                for value in 0..20_000u16 {
+                    table.write(0, &[value; 125], false, |_| Ok(())).unwrap();
+                }
+            })
+        };
+
+        let mut out = [0u16; 125];
+        while !writer.is_finished() {
+            table.read_into(0, &mut out).unwrap();
+            assert!(out.iter().all(|&value| value == out[0]));
+        }
+        writer.join().unwrap();
+    }
+}
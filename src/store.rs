@@ -0,0 +1,233 @@
+//! # Data Stores
+//!
+//! Servers read and write their data through the [`DataStore`] trait, so the
+//! data can live wherever the application keeps it: shared memory, a
+//! key-value store or a PLC process image. [`ModbusRegisterBank`] is the
+//! default in-memory store; with the `mmap` feature,
+//! [`MmapStore`](crate::mmap::MmapStore) shares a memory-mapped image with
+//! other local processes.
+//!
+//! A store implements reads and writes of the four tables. Reads and writes
+//! at a [`DataAddress`] and batches of them have default implementations in
+//! terms of those.
+//!
+//! ## Usage Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::sync::{Arc, Mutex};
+//! use voltage_modbus::{DataStore, ModbusResult, ModbusTcpServer, ModbusTcpServerConfig};
+//!
+//! /// Holding registers only, kept in the application's own map
+//! #[derive(Default)]
+//! struct Setpoints(Mutex<HashMap<u16, u16>>);
+//!
+//! impl DataStore for Setpoints {
+//!     fn read_coils(&self, _address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+//!         Ok(vec![false; quantity as usize])
+//!     }
+//!     fn read_discrete_inputs(&self, _address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+//!         Ok(vec![false; quantity as usize])
+//!     }
+//!     fn read_holding_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+//!         let map = self.0.lock().unwrap();
+//!         Ok((0..quantity).map(|i| map.get(&(address + i)).copied().unwrap_or(0)).collect())
+//!     }
+//!     fn read_input_registers(&self, _address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+//!         Ok(vec![0; quantity as usize])
+//!     }
+//!     fn write_coils(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+//!         Err(voltage_modbus::ModbusError::invalid_address(address, values.len() as u16))
+//!     }
+//!     fn write_discrete_inputs(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+//!         Err(voltage_modbus::ModbusError::invalid_address(address, values.len() as u16))
+//!     }
+//!     fn write_holding_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+//!         let mut map = self.0.lock().unwrap();
+//!         for (i, &value) in values.iter().enumerate() {
+//!             map.insert(address + i as u16, value);
+//!         }
+//!         Ok(())
+//!     }
+//!     fn write_input_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+//!         Err(voltage_modbus::ModbusError::invalid_address(address, values.len() as u16))
+//!     }
+//! }
+//!
+//! let server = ModbusTcpServer::with_store(ModbusTcpServerConfig::default(), Arc::new(Setpoints::default()));
+//! server.store().write_holding_registers(0, &[42]).unwrap();
+//! ```
+
+use std::sync::Arc;
+
+use crate::address::DataAddress;
+use crate::error::{ModbusError, ModbusResult};
+use crate::observer::WriteContext;
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::register_bank::ModbusRegisterBank;
+
+/// Storage behind a Modbus server
+///
+/// Addresses are protocol (0-based) addresses when the store is used by a
+/// server. Errors map to exception responses: [`ModbusError::InvalidAddress`]
+/// to 0x02 and [`ModbusError::InvalidData`] to 0x03.
+pub trait DataStore: Send + Sync + 'static {
+    /// Read coils
+    fn read_coils(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>>;
+
+    /// Read discrete inputs
+    fn read_discrete_inputs(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>>;
+
+    /// Read holding registers
+    fn read_holding_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>>;
+
+    /// Read input registers
+    fn read_input_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>>;
+
+    /// Write coils
+    fn write_coils(&self, address: u16, values: &[bool]) -> ModbusResult<()>;
+
+    /// Write discrete inputs (not reachable by Modbus requests)
+    fn write_discrete_inputs(&self, address: u16, values: &[bool]) -> ModbusResult<()>;
+
+    /// Write holding registers
+    fn write_holding_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()>;
+
+    /// Write input registers (not reachable by Modbus requests)
+    fn write_input_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()>;
+
+    /// Read from any table
+    fn read_at(&self, address: DataAddress, quantity: u16) -> ModbusResult<PointValue> {
+        let offset = address.address;
+        Ok(match address.table {
+            RegisterTable::Coil => PointValue::Bits(self.read_coils(offset, quantity)?),
+            RegisterTable::DiscreteInput => PointValue::Bits(self.read_discrete_inputs(offset, quantity)?),
+            RegisterTable::HoldingRegister => PointValue::Registers(self.read_holding_registers(offset, quantity)?),
+            RegisterTable::InputRegister => PointValue::Registers(self.read_input_registers(offset, quantity)?),
+        })
+    }
+
+    /// Write to any table
+    fn write_at(&self, address: DataAddress, value: &PointValue) -> ModbusResult<()> {
+        let offset = address.address;
+        match (address.table, value) {
+            (RegisterTable::Coil, PointValue::Bits(bits)) => self.write_coils(offset, bits),
+            (RegisterTable::DiscreteInput, PointValue::Bits(bits)) => self.write_discrete_inputs(offset, bits),
+            (RegisterTable::HoldingRegister, PointValue::Registers(registers)) => self.write_holding_registers(offset, registers),
+            (RegisterTable::InputRegister, PointValue::Registers(registers)) => self.write_input_registers(offset, registers),
+            (table, _) => Err(ModbusError::invalid_data(format!("Data does not match the {} table", table))),
+        }
+    }
+
+    /// Read several ranges
+    ///
+    /// # Returns
+    ///
+    /// One value per range, in order, or the first error
+    fn read_batch(&self, reads: &[(DataAddress, u16)]) -> ModbusResult<Vec<PointValue>> {
+        reads.iter().map(|&(address, quantity)| self.read_at(address, quantity)).collect()
+    }
+
+    /// Write several ranges in order
+    ///
+    /// The default implementation stops at the first error, leaving earlier
+    /// writes applied; stores that can apply a batch atomically override it.
+    fn write_batch(&self, writes: &[(DataAddress, PointValue)]) -> ModbusResult<()> {
+        writes.iter().try_for_each(|(address, value)| self.write_at(*address, value))
+    }
+
+    /// Store serving one request from `context`
+    ///
+    /// Stores that track where writes come from return a handle attributing
+    /// writes to the requesting master; the default serves from `self`.
+    fn for_request(self: &Arc<Self>, _context: WriteContext) -> Arc<Self>
+    where
+        Self: Sized,
+    {
+        self.clone()
+    }
+
+    /// Register bank behind the store
+    ///
+    /// Servers use it for Enron requests and register bank statistics.
+    fn register_bank(&self) -> Option<&ModbusRegisterBank> {
+        None
+    }
+}
+
+impl DataStore for ModbusRegisterBank {
+    fn read_coils(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        ModbusRegisterBank::read_coils(self, address, quantity)
+    }
+
+    fn read_discrete_inputs(&self, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        ModbusRegisterBank::read_discrete_inputs(self, address, quantity)
+    }
+
+    fn read_holding_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        ModbusRegisterBank::read_holding_registers(self, address, quantity)
+    }
+
+    fn read_input_registers(&self, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        ModbusRegisterBank::read_input_registers(self, address, quantity)
+    }
+
+    fn write_coils(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.write_0f(address, values)
+    }
+
+    fn write_discrete_inputs(&self, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.set_discrete_inputs(address, values)
+    }
+
+    fn write_holding_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.write_10(address, values)
+    }
+
+    fn write_input_registers(&self, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.set_input_registers(address, values)
+    }
+
+    fn read_at(&self, address: DataAddress, quantity: u16) -> ModbusResult<PointValue> {
+        ModbusRegisterBank::read_at(self, address, quantity)
+    }
+
+    fn write_at(&self, address: DataAddress, value: &PointValue) -> ModbusResult<()> {
+        ModbusRegisterBank::write_at(self, address, value)
+    }
+
+    fn for_request(self: &Arc<Self>, context: WriteContext) -> Arc<Self> {
+        Arc::new(self.protocol_view().with_write_context(context))
+    }
+
+    fn register_bank(&self) -> Option<&ModbusRegisterBank> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_bank_store() {
+        let bank = Arc::new(ModbusRegisterBank::new());
+        let holding = DataAddress::new(RegisterTable::HoldingRegister, 10);
+        let inputs = DataAddress::new(RegisterTable::DiscreteInput, 3);
+
+        bank.write_batch(&[
+            (holding, PointValue::Registers(vec![1, 2])),
+            (inputs, PointValue::Bits(vec![true])),
+        ])
+        .unwrap();
+        let values = bank.read_batch(&[(holding, 2), (inputs, 1)]).unwrap();
+        assert_eq!(values, vec![PointValue::Registers(vec![1, 2]), PointValue::Bits(vec![true])]);
+        assert!(DataStore::write_at(bank.as_ref(), holding, &PointValue::Bits(vec![true])).is_err());
+
+        // Requests are attributed to the master and use protocol addresses
+        let request = bank.for_request(WriteContext::remote(4, None));
+        assert_eq!(request.write_context().unit_id, Some(4));
+        assert!(request.register_bank().is_some());
+    }
+}
@@ -0,0 +1,1320 @@
+//! # SunSpec Devices
+//!
+//! SunSpec devices publish a chain of information models in holding registers:
+//! the `SunS` marker at a standard base address, followed by models made of a
+//! model ID, a length and `length` registers of data, terminated by ID `0xFFFF`.
+//!
+//! This module:
+//!
+//! - **Discovers** the marker at the standard base addresses (40000, 50000, 0)
+//!   and walks the model chain ([`SunSpecDevice::discover`])
+//! - **Decodes** common models with scale factors applied ([`decode_model`]):
+//!   1 (common), 101–103 and 111–113 (inverters), 120–124 (nameplate, settings,
+//!   status, controls, storage), 160 (MPPT), 201–204 (meters) and 802 (battery)
+//! - **Writes** control points, scaling the value with the device's scale
+//!   factor ([`SunSpecDevice::write_point`])
+//! - **Emulates** a SunSpec device on a [`ModbusRegisterBank`] for tests
+//!   ([`SunSpecEmulator`])
+//!
+//! Points holding the SunSpec "not implemented" value decode to `None`.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusTcpClient, SunSpecDevice};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let mut client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//! let device = SunSpecDevice::discover(&mut client, 1).await?;
+//!
+//! let common = device.read_model(&mut client, 1).await?;
+//! println!("Manufacturer: {:?}", common.string("Mn"));
+//!
+//! let inverter = device.read_model(&mut client, 103).await?;
+//! println!("AC power: {:?} W", inverter.number("W"));
+//!
+//! // Limit output to 50 %
+//! device.write_point(&mut client, 123, "WMaxLimPct", 50.0).await?;
+//! device.write_point(&mut client, 123, "WMaxLim_Ena", 1.0).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::client::{ChunkLimits, ModbusClient};
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::SlaveId;
+use crate::register_bank::ModbusRegisterBank;
+use crate::transport::TransportStats;
+
+/// `SunS` marker registers
+pub const SUNSPEC_MARKER: [u16; 2] = [0x5375, 0x6E53];
+
+/// Standard base addresses searched for the marker, in order
+pub const SUNSPEC_BASE_ADDRESSES: [u16; 3] = [40000, 50000, 0];
+
+/// Model ID terminating the model chain
+pub const END_MODEL_ID: u16 = 0xFFFF;
+
+/// Upper bound on the number of models walked, guarding against corrupt chains
+const MAX_MODELS: usize = 256;
+
+/// SunSpec point types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunSpecType {
+    Uint16,
+    Int16,
+    Uint32,
+    Int32,
+    Uint64,
+    Acc16,
+    Acc32,
+    Acc64,
+    Enum16,
+    Bitfield16,
+    Bitfield32,
+    /// Scale factor: power of ten applied to the points referring to it
+    Sunssf,
+    Float32,
+    /// Fixed-length string of the given number of registers
+    String(u16),
+    Pad,
+}
+
+impl SunSpecType {
+    /// Number of registers occupied by the type
+    pub fn registers(self) -> u16 {
+        match self {
+            SunSpecType::Uint32 | SunSpecType::Int32 | SunSpecType::Acc32
+            | SunSpecType::Bitfield32 | SunSpecType::Float32 => 2,
+            SunSpecType::Uint64 | SunSpecType::Acc64 => 4,
+            SunSpecType::String(len) => len,
+            _ => 1,
+        }
+    }
+}
+
+/// Point definition within a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SunSpecPoint {
+    /// SunSpec point name, e.g. `"W"`
+    pub name: &'static str,
+    /// Point type
+    pub ty: SunSpecType,
+    /// Name of the scale factor point, if scaled
+    pub scale_factor: Option<&'static str>,
+    /// Whether the point may be written
+    pub writable: bool,
+}
+
+use self::SunSpecType as T;
+
+const fn pt(name: &'static str, ty: SunSpecType) -> SunSpecPoint {
+    SunSpecPoint { name, ty, scale_factor: None, writable: false }
+}
+
+const fn sf(name: &'static str, ty: SunSpecType, scale_factor: &'static str) -> SunSpecPoint {
+    SunSpecPoint { name, ty, scale_factor: Some(scale_factor), writable: false }
+}
+
+const fn rw(name: &'static str, ty: SunSpecType) -> SunSpecPoint {
+    SunSpecPoint { name, ty, scale_factor: None, writable: true }
+}
+
+const fn rw_sf(name: &'static str, ty: SunSpecType, scale_factor: &'static str) -> SunSpecPoint {
+    SunSpecPoint { name, ty, scale_factor: Some(scale_factor), writable: true }
+}
+
+const COMMON: &[SunSpecPoint] = &[
+    pt("Mn", T::String(16)),
+    pt("Md", T::String(16)),
+    pt("Opt", T::String(8)),
+    pt("Vr", T::String(8)),
+    pt("SN", T::String(16)),
+    rw("DA", T::Uint16),
+    pt("Pad", T::Pad),
+];
+
+const INVERTER: &[SunSpecPoint] = &[
+    sf("A", T::Uint16, "A_SF"),
+    sf("AphA", T::Uint16, "A_SF"),
+    sf("AphB", T::Uint16, "A_SF"),
+    sf("AphC", T::Uint16, "A_SF"),
+    pt("A_SF", T::Sunssf),
+    sf("PPVphAB", T::Uint16, "V_SF"),
+    sf("PPVphBC", T::Uint16, "V_SF"),
+    sf("PPVphCA", T::Uint16, "V_SF"),
+    sf("PhVphA", T::Uint16, "V_SF"),
+    sf("PhVphB", T::Uint16, "V_SF"),
+    sf("PhVphC", T::Uint16, "V_SF"),
+    pt("V_SF", T::Sunssf),
+    sf("W", T::Int16, "W_SF"),
+    pt("W_SF", T::Sunssf),
+    sf("Hz", T::Uint16, "Hz_SF"),
+    pt("Hz_SF", T::Sunssf),
+    sf("VA", T::Int16, "VA_SF"),
+    pt("VA_SF", T::Sunssf),
+    sf("VAr", T::Int16, "VAr_SF"),
+    pt("VAr_SF", T::Sunssf),
+    sf("PF", T::Int16, "PF_SF"),
+    pt("PF_SF", T::Sunssf),
+    sf("WH", T::Acc32, "WH_SF"),
+    pt("WH_SF", T::Sunssf),
+    sf("DCA", T::Uint16, "DCA_SF"),
+    pt("DCA_SF", T::Sunssf),
+    sf("DCV", T::Uint16, "DCV_SF"),
+    pt("DCV_SF", T::Sunssf),
+    sf("DCW", T::Int16, "DCW_SF"),
+    pt("DCW_SF", T::Sunssf),
+    sf("TmpCab", T::Int16, "Tmp_SF"),
+    sf("TmpSnk", T::Int16, "Tmp_SF"),
+    sf("TmpTrns", T::Int16, "Tmp_SF"),
+    sf("TmpOt", T::Int16, "Tmp_SF"),
+    pt("Tmp_SF", T::Sunssf),
+    pt("St", T::Enum16),
+    pt("StVnd", T::Enum16),
+    pt("Evt1", T::Bitfield32),
+    pt("Evt2", T::Bitfield32),
+    pt("EvtVnd1", T::Bitfield32),
+    pt("EvtVnd2", T::Bitfield32),
+    pt("EvtVnd3", T::Bitfield32),
+    pt("EvtVnd4", T::Bitfield32),
+];
+
+const INVERTER_FLOAT: &[SunSpecPoint] = &[
+    pt("A", T::Float32),
+    pt("AphA", T::Float32),
+    pt("AphB", T::Float32),
+    pt("AphC", T::Float32),
+    pt("PPVphAB", T::Float32),
+    pt("PPVphBC", T::Float32),
+    pt("PPVphCA", T::Float32),
+    pt("PhVphA", T::Float32),
+    pt("PhVphB", T::Float32),
+    pt("PhVphC", T::Float32),
+    pt("W", T::Float32),
+    pt("Hz", T::Float32),
+    pt("VA", T::Float32),
+    pt("VAr", T::Float32),
+    pt("PF", T::Float32),
+    pt("WH", T::Float32),
+    pt("DCA", T::Float32),
+    pt("DCV", T::Float32),
+    pt("DCW", T::Float32),
+    pt("TmpCab", T::Float32),
+    pt("TmpSnk", T::Float32),
+    pt("TmpTrns", T::Float32),
+    pt("TmpOt", T::Float32),
+    pt("St", T::Enum16),
+    pt("StVnd", T::Enum16),
+    pt("Evt1", T::Bitfield32),
+    pt("Evt2", T::Bitfield32),
+    pt("EvtVnd1", T::Bitfield32),
+    pt("EvtVnd2", T::Bitfield32),
+    pt("EvtVnd3", T::Bitfield32),
+    pt("EvtVnd4", T::Bitfield32),
+];
+
+const NAMEPLATE: &[SunSpecPoint] = &[
+    pt("DERTyp", T::Enum16),
+    sf("WRtg", T::Uint16, "WRtg_SF"),
+    pt("WRtg_SF", T::Sunssf),
+    sf("VARtg", T::Uint16, "VARtg_SF"),
+    pt("VARtg_SF", T::Sunssf),
+    sf("VArRtgQ1", T::Int16, "VArRtg_SF"),
+    sf("VArRtgQ2", T::Int16, "VArRtg_SF"),
+    sf("VArRtgQ3", T::Int16, "VArRtg_SF"),
+    sf("VArRtgQ4", T::Int16, "VArRtg_SF"),
+    pt("VArRtg_SF", T::Sunssf),
+    sf("ARtg", T::Uint16, "ARtg_SF"),
+    pt("ARtg_SF", T::Sunssf),
+    sf("PFRtgQ1", T::Int16, "PFRtg_SF"),
+    sf("PFRtgQ2", T::Int16, "PFRtg_SF"),
+    sf("PFRtgQ3", T::Int16, "PFRtg_SF"),
+    sf("PFRtgQ4", T::Int16, "PFRtg_SF"),
+    pt("PFRtg_SF", T::Sunssf),
+    sf("WHRtg", T::Uint16, "WHRtg_SF"),
+    pt("WHRtg_SF", T::Sunssf),
+    sf("AhrRtg", T::Uint16, "AhrRtg_SF"),
+    pt("AhrRtg_SF", T::Sunssf),
+    sf("MaxChaRte", T::Uint16, "MaxChaRte_SF"),
+    pt("MaxChaRte_SF", T::Sunssf),
+    sf("MaxDisChaRte", T::Uint16, "MaxDisChaRte_SF"),
+    pt("MaxDisChaRte_SF", T::Sunssf),
+    pt("Pad", T::Pad),
+];
+
+const SETTINGS: &[SunSpecPoint] = &[
+    rw_sf("WMax", T::Uint16, "WMax_SF"),
+    rw_sf("VRef", T::Uint16, "VRef_SF"),
+    rw_sf("VRefOfs", T::Int16, "VRefOfs_SF"),
+    rw_sf("VMax", T::Uint16, "VMinMax_SF"),
+    rw_sf("VMin", T::Uint16, "VMinMax_SF"),
+    rw_sf("VAMax", T::Uint16, "VAMax_SF"),
+    rw_sf("VArMaxQ1", T::Int16, "VArMax_SF"),
+    rw_sf("VArMaxQ2", T::Int16, "VArMax_SF"),
+    rw_sf("VArMaxQ3", T::Int16, "VArMax_SF"),
+    rw_sf("VArMaxQ4", T::Int16, "VArMax_SF"),
+    rw_sf("WGra", T::Uint16, "WGra_SF"),
+    rw_sf("PFMinQ1", T::Int16, "PFMin_SF"),
+    rw_sf("PFMinQ2", T::Int16, "PFMin_SF"),
+    rw_sf("PFMinQ3", T::Int16, "PFMin_SF"),
+    rw_sf("PFMinQ4", T::Int16, "PFMin_SF"),
+    rw("VArAct", T::Enum16),
+    rw("ClcTotVA", T::Enum16),
+    rw_sf("MaxRmpRte", T::Uint16, "MaxRmpRte_SF"),
+    rw_sf("ECPNomHz", T::Uint16, "ECPNomHz_SF"),
+    rw("ConnPh", T::Enum16),
+    pt("WMax_SF", T::Sunssf),
+    pt("VRef_SF", T::Sunssf),
+    pt("VRefOfs_SF", T::Sunssf),
+    pt("VMinMax_SF", T::Sunssf),
+    pt("VAMax_SF", T::Sunssf),
+    pt("VArMax_SF", T::Sunssf),
+    pt("WGra_SF", T::Sunssf),
+    pt("PFMin_SF", T::Sunssf),
+    pt("MaxRmpRte_SF", T::Sunssf),
+    pt("ECPNomHz_SF", T::Sunssf),
+];
+
+const STATUS: &[SunSpecPoint] = &[
+    pt("PVConn", T::Bitfield16),
+    pt("StorConn", T::Bitfield16),
+    pt("ECPConn", T::Bitfield16),
+    pt("ActWh", T::Acc64),
+    pt("ActVAh", T::Acc64),
+    pt("ActVArhQ1", T::Acc64),
+    pt("ActVArhQ2", T::Acc64),
+    pt("ActVArhQ3", T::Acc64),
+    pt("ActVArhQ4", T::Acc64),
+    sf("VArAval", T::Int16, "VArAval_SF"),
+    pt("VArAval_SF", T::Sunssf),
+    sf("WAval", T::Uint16, "WAval_SF"),
+    pt("WAval_SF", T::Sunssf),
+    pt("StSetLimMsk", T::Bitfield32),
+    pt("StActCtl", T::Bitfield32),
+    pt("TmSrc", T::String(4)),
+    pt("Tms", T::Uint32),
+    pt("RtSt", T::Bitfield16),
+    sf("Ris", T::Uint16, "Ris_SF"),
+    pt("Ris_SF", T::Sunssf),
+];
+
+const CONTROLS: &[SunSpecPoint] = &[
+    rw("Conn_WinTms", T::Uint16),
+    rw("Conn_RvrtTms", T::Uint16),
+    rw("Conn", T::Enum16),
+    rw_sf("WMaxLimPct", T::Uint16, "WMaxLimPct_SF"),
+    rw("WMaxLimPct_WinTms", T::Uint16),
+    rw("WMaxLimPct_RvrtTms", T::Uint16),
+    rw("WMaxLimPct_RmpTms", T::Uint16),
+    rw("WMaxLim_Ena", T::Enum16),
+    rw_sf("OutPFSet", T::Int16, "OutPFSet_SF"),
+    rw("OutPFSet_WinTms", T::Uint16),
+    rw("OutPFSet_RvrtTms", T::Uint16),
+    rw("OutPFSet_RmpTms", T::Uint16),
+    rw("OutPFSet_Ena", T::Enum16),
+    rw_sf("VArWMaxPct", T::Int16, "VArPct_SF"),
+    rw_sf("VArMaxPct", T::Int16, "VArPct_SF"),
+    rw_sf("VArAvalPct", T::Int16, "VArPct_SF"),
+    rw("VArPct_WinTms", T::Uint16),
+    rw("VArPct_RvrtTms", T::Uint16),
+    rw("VArPct_RmpTms", T::Uint16),
+    rw("VArPct_Mod", T::Enum16),
+    rw("VArPct_Ena", T::Enum16),
+    pt("WMaxLimPct_SF", T::Sunssf),
+    pt("OutPFSet_SF", T::Sunssf),
+    pt("VArPct_SF", T::Sunssf),
+];
+
+const STORAGE: &[SunSpecPoint] = &[
+    rw_sf("WChaMax", T::Uint16, "WChaMax_SF"),
+    rw_sf("WChaGra", T::Uint16, "WChaDisChaGra_SF"),
+    rw_sf("WDisChaGra", T::Uint16, "WChaDisChaGra_SF"),
+    rw("StorCtl_Mod", T::Bitfield16),
+    rw_sf("VAChaMax", T::Uint16, "VAChaMax_SF"),
+    rw_sf("MinRsvPct", T::Uint16, "MinRsvPct_SF"),
+    sf("ChaState", T::Uint16, "ChaState_SF"),
+    sf("StorAval", T::Uint16, "StorAval_SF"),
+    sf("InBatV", T::Uint16, "InBatV_SF"),
+    pt("ChaSt", T::Enum16),
+    rw_sf("OutWRte", T::Int16, "InOutWRte_SF"),
+    rw_sf("InWRte", T::Int16, "InOutWRte_SF"),
+    rw("InOutWRte_WinTms", T::Uint16),
+    rw("InOutWRte_RvrtTms", T::Uint16),
+    rw("InOutWRte_RmpTms", T::Uint16),
+    rw("ChaGriSet", T::Enum16),
+    pt("WChaMax_SF", T::Sunssf),
+    pt("WChaDisChaGra_SF", T::Sunssf),
+    pt("VAChaMax_SF", T::Sunssf),
+    pt("MinRsvPct_SF", T::Sunssf),
+    pt("ChaState_SF", T::Sunssf),
+    pt("StorAval_SF", T::Sunssf),
+    pt("InBatV_SF", T::Sunssf),
+    pt("InOutWRte_SF", T::Sunssf),
+];
+
+const MPPT: &[SunSpecPoint] = &[
+    pt("DCA_SF", T::Sunssf),
+    pt("DCV_SF", T::Sunssf),
+    pt("DCW_SF", T::Sunssf),
+    pt("DCWH_SF", T::Sunssf),
+    pt("Evt", T::Bitfield32),
+    pt("N", T::Uint16),
+    pt("TmsPer", T::Uint16),
+];
+
+const MPPT_MODULE: &[SunSpecPoint] = &[
+    pt("ID", T::Uint16),
+    pt("IDStr", T::String(8)),
+    sf("DCA", T::Uint16, "DCA_SF"),
+    sf("DCV", T::Uint16, "DCV_SF"),
+    sf("DCW", T::Uint16, "DCW_SF"),
+    sf("DCWH", T::Acc32, "DCWH_SF"),
+    pt("Tms", T::Uint32),
+    pt("Tmp", T::Int16),
+    pt("DCSt", T::Enum16),
+    pt("DCEvt", T::Bitfield32),
+];
+
+const METER: &[SunSpecPoint] = &[
+    sf("A", T::Int16, "A_SF"),
+    sf("AphA", T::Int16, "A_SF"),
+    sf("AphB", T::Int16, "A_SF"),
+    sf("AphC", T::Int16, "A_SF"),
+    pt("A_SF", T::Sunssf),
+    sf("PhV", T::Int16, "V_SF"),
+    sf("PhVphA", T::Int16, "V_SF"),
+    sf("PhVphB", T::Int16, "V_SF"),
+    sf("PhVphC", T::Int16, "V_SF"),
+    sf("PPV", T::Int16, "V_SF"),
+    sf("PPVphAB", T::Int16, "V_SF"),
+    sf("PPVphBC", T::Int16, "V_SF"),
+    sf("PPVphCA", T::Int16, "V_SF"),
+    pt("V_SF", T::Sunssf),
+    sf("Hz", T::Int16, "Hz_SF"),
+    pt("Hz_SF", T::Sunssf),
+    sf("W", T::Int16, "W_SF"),
+    sf("WphA", T::Int16, "W_SF"),
+    sf("WphB", T::Int16, "W_SF"),
+    sf("WphC", T::Int16, "W_SF"),
+    pt("W_SF", T::Sunssf),
+    sf("VA", T::Int16, "VA_SF"),
+    sf("VAphA", T::Int16, "VA_SF"),
+    sf("VAphB", T::Int16, "VA_SF"),
+    sf("VAphC", T::Int16, "VA_SF"),
+    pt("VA_SF", T::Sunssf),
+    sf("VAR", T::Int16, "VAR_SF"),
+    sf("VARphA", T::Int16, "VAR_SF"),
+    sf("VARphB", T::Int16, "VAR_SF"),
+    sf("VARphC", T::Int16, "VAR_SF"),
+    pt("VAR_SF", T::Sunssf),
+    sf("PF", T::Int16, "PF_SF"),
+    sf("PFphA", T::Int16, "PF_SF"),
+    sf("PFphB", T::Int16, "PF_SF"),
+    sf("PFphC", T::Int16, "PF_SF"),
+    pt("PF_SF", T::Sunssf),
+    sf("TotWhExp", T::Acc32, "TotWh_SF"),
+    sf("TotWhExpPhA", T::Acc32, "TotWh_SF"),
+    sf("TotWhExpPhB", T::Acc32, "TotWh_SF"),
+    sf("TotWhExpPhC", T::Acc32, "TotWh_SF"),
+    sf("TotWhImp", T::Acc32, "TotWh_SF"),
+    sf("TotWhImpPhA", T::Acc32, "TotWh_SF"),
+    sf("TotWhImpPhB", T::Acc32, "TotWh_SF"),
+    sf("TotWhImpPhC", T::Acc32, "TotWh_SF"),
+    pt("TotWh_SF", T::Sunssf),
+    sf("TotVAhExp", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhExpPhA", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhExpPhB", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhExpPhC", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhImp", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhImpPhA", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhImpPhB", T::Acc32, "TotVAh_SF"),
+    sf("TotVAhImpPhC", T::Acc32, "TotVAh_SF"),
+    pt("TotVAh_SF", T::Sunssf),
+    sf("TotVArhImpQ1", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ1PhA", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ1PhB", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ1PhC", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ2", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ2PhA", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ2PhB", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhImpQ2PhC", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ3", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ3PhA", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ3PhB", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ3PhC", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ4", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ4PhA", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ4PhB", T::Acc32, "TotVArh_SF"),
+    sf("TotVArhExpQ4PhC", T::Acc32, "TotVArh_SF"),
+    pt("TotVArh_SF", T::Sunssf),
+    pt("Evt", T::Bitfield32),
+];
+
+const BATTERY: &[SunSpecPoint] = &[
+    sf("AHRtg", T::Uint16, "AHRtg_SF"),
+    sf("WHRtg", T::Uint16, "WHRtg_SF"),
+    sf("WChaRteMax", T::Uint16, "WChaDisChaMax_SF"),
+    sf("WDisChaRteMax", T::Uint16, "WChaDisChaMax_SF"),
+    sf("DisChaRte", T::Uint16, "DisChaRte_SF"),
+    rw_sf("SoCMax", T::Uint16, "SoC_SF"),
+    rw_sf("SoCMin", T::Uint16, "SoC_SF"),
+    rw_sf("SoCRsvMax", T::Uint16, "SoC_SF"),
+    rw_sf("SoCRsvMin", T::Uint16, "SoC_SF"),
+    sf("SoC", T::Uint16, "SoC_SF"),
+    sf("DoD", T::Uint16, "DoD_SF"),
+    sf("SoH", T::Uint16, "SoH_SF"),
+    pt("NCyc", T::Uint32),
+    pt("ChaSt", T::Enum16),
+    pt("LocRemCtl", T::Enum16),
+    pt("Hb", T::Uint16),
+    rw("CtrlHb", T::Uint16),
+    rw("AlmRst", T::Uint16),
+    pt("Typ", T::Enum16),
+    pt("State", T::Enum16),
+    pt("StateVnd", T::Enum16),
+    pt("WarrDt", T::Uint32),
+    pt("Evt1", T::Bitfield32),
+    pt("Evt2", T::Bitfield32),
+    pt("EvtVnd1", T::Bitfield32),
+    pt("EvtVnd2", T::Bitfield32),
+    sf("V", T::Uint16, "V_SF"),
+    sf("VMax", T::Uint16, "V_SF"),
+    sf("VMin", T::Uint16, "V_SF"),
+    sf("CellVMax", T::Uint16, "CellV_SF"),
+    pt("CellVMaxStr", T::Uint16),
+    pt("CellVMaxMod", T::Uint16),
+    sf("CellVMin", T::Uint16, "CellV_SF"),
+    pt("CellVMinStr", T::Uint16),
+    pt("CellVMinMod", T::Uint16),
+    sf("CellVAvg", T::Uint16, "CellV_SF"),
+    sf("A", T::Int16, "A_SF"),
+    sf("AChaMax", T::Uint16, "AMax_SF"),
+    sf("ADisChaMax", T::Uint16, "AMax_SF"),
+    sf("W", T::Int16, "W_SF"),
+    pt("ReqInvState", T::Enum16),
+    sf("ReqW", T::Int16, "W_SF"),
+    rw("SetOp", T::Enum16),
+    rw("SetInvState", T::Enum16),
+    pt("AHRtg_SF", T::Sunssf),
+    pt("WHRtg_SF", T::Sunssf),
+    pt("WChaDisChaMax_SF", T::Sunssf),
+    pt("DisChaRte_SF", T::Sunssf),
+    pt("SoC_SF", T::Sunssf),
+    pt("DoD_SF", T::Sunssf),
+    pt("SoH_SF", T::Sunssf),
+    pt("V_SF", T::Sunssf),
+    pt("CellV_SF", T::Sunssf),
+    pt("A_SF", T::Sunssf),
+    pt("AMax_SF", T::Sunssf),
+    pt("W_SF", T::Sunssf),
+];
+
+/// Layout of a known SunSpec model
+#[derive(Debug, Clone, Copy)]
+pub struct ModelDefinition {
+    /// Model ID
+    pub id: u16,
+    /// Short model name
+    pub name: &'static str,
+    /// Points of the fixed block
+    pub fixed: &'static [SunSpecPoint],
+    /// Points of each repeating block, empty if the model has none
+    pub repeating: &'static [SunSpecPoint],
+}
+
+impl ModelDefinition {
+    /// Look up a model supported by this module
+    pub fn get(id: u16) -> Option<Self> {
+        let (name, fixed, repeating): (_, _, &'static [SunSpecPoint]) = match id {
+            1 => ("common", COMMON, &[]),
+            101 => ("inverter_single_phase", INVERTER, &[]),
+            102 => ("inverter_split_phase", INVERTER, &[]),
+            103 => ("inverter_three_phase", INVERTER, &[]),
+            111 => ("inverter_single_phase_float", INVERTER_FLOAT, &[]),
+            112 => ("inverter_split_phase_float", INVERTER_FLOAT, &[]),
+            113 => ("inverter_three_phase_float", INVERTER_FLOAT, &[]),
+            120 => ("nameplate", NAMEPLATE, &[]),
+            121 => ("settings", SETTINGS, &[]),
+            122 => ("status", STATUS, &[]),
+            123 => ("controls", CONTROLS, &[]),
+            124 => ("storage", STORAGE, &[]),
+            160 => ("mppt", MPPT, MPPT_MODULE),
+            201 => ("meter_single_phase", METER, &[]),
+            202 => ("meter_split_phase", METER, &[]),
+            203 => ("meter_wye", METER, &[]),
+            204 => ("meter_delta", METER, &[]),
+            802 => ("battery", BATTERY, &[]),
+            _ => return None,
+        };
+        Some(Self { id, name, fixed, repeating })
+    }
+
+    /// Length of the fixed block in registers
+    pub fn fixed_length(&self) -> u16 {
+        block_length(self.fixed)
+    }
+
+    /// Length of one repeating block in registers
+    pub fn repeating_length(&self) -> u16 {
+        block_length(self.repeating)
+    }
+
+    /// Find a fixed block point and its register offset
+    pub fn fixed_point(&self, name: &str) -> Option<(u16, &'static SunSpecPoint)> {
+        find_point(self.fixed, name)
+    }
+
+    /// Find a repeating block point and its register offset within the block
+    pub fn repeating_point(&self, name: &str) -> Option<(u16, &'static SunSpecPoint)> {
+        find_point(self.repeating, name)
+    }
+}
+
+fn block_length(points: &[SunSpecPoint]) -> u16 {
+    points.iter().map(|p| p.ty.registers()).sum()
+}
+
+fn find_point(points: &'static [SunSpecPoint], name: &str) -> Option<(u16, &'static SunSpecPoint)> {
+    let mut offset = 0;
+    for point in points {
+        if point.name == name {
+            return Some((offset, point));
+        }
+        offset += point.ty.registers();
+    }
+    None
+}
+
+/// Decoded point value
+#[derive(Debug, Clone, PartialEq)]
+pub enum SunSpecValue {
+    /// Numeric value with its scale factor applied
+    Number(f64),
+    /// Enumerated value
+    Enum(u16),
+    /// Bitfield value
+    Bitfield(u32),
+    /// String value with padding removed
+    String(String),
+}
+
+impl SunSpecValue {
+    /// Numeric value, if numeric or enumerated
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SunSpecValue::Number(value) => Some(*value),
+            SunSpecValue::Enum(value) => Some(*value as f64),
+            SunSpecValue::Bitfield(value) => Some(*value as f64),
+            SunSpecValue::String(_) => None,
+        }
+    }
+}
+
+/// A decoded point
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointReading {
+    /// SunSpec point name
+    pub name: &'static str,
+    /// Value, or `None` if not implemented by the device
+    pub value: Option<SunSpecValue>,
+}
+
+/// Location of a model discovered in the model chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// Model ID
+    pub id: u16,
+    /// Address of the model ID register
+    pub address: u16,
+    /// Model length in registers, excluding ID and length
+    pub length: u16,
+}
+
+impl ModelInfo {
+    /// Address of the first data register
+    pub fn data_address(&self) -> u16 {
+        self.address + 2
+    }
+}
+
+/// A decoded model
+#[derive(Debug, Clone, PartialEq)]
+pub struct SunSpecModel {
+    /// Model ID
+    pub id: u16,
+    /// Model name, `None` for models this module does not know
+    pub name: Option<&'static str>,
+    /// Address of the model ID register
+    pub address: u16,
+    /// Raw model data, excluding ID and length
+    pub registers: Vec<u16>,
+    /// Points of the fixed block
+    pub points: Vec<PointReading>,
+    /// Points of each repeating block
+    pub blocks: Vec<Vec<PointReading>>,
+}
+
+impl SunSpecModel {
+    /// Value of a fixed block point
+    pub fn get(&self, name: &str) -> Option<&SunSpecValue> {
+        self.points.iter().find(|p| p.name == name).and_then(|p| p.value.as_ref())
+    }
+
+    /// Numeric value of a fixed block point
+    pub fn number(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(SunSpecValue::as_f64)
+    }
+
+    /// String value of a fixed block point
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(SunSpecValue::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Value of a point in a repeating block
+    pub fn block_value(&self, block: usize, name: &str) -> Option<&SunSpecValue> {
+        self.blocks.get(block)?.iter().find(|p| p.name == name).and_then(|p| p.value.as_ref())
+    }
+}
+
+/// Decode a model's data registers (excluding ID and length)
+///
+/// Unknown models are returned with their raw registers only. Points beyond
+/// the end of a short model are omitted.
+pub fn decode_model(id: u16, address: u16, registers: &[u16]) -> SunSpecModel {
+    let mut model = SunSpecModel {
+        id,
+        name: None,
+        address,
+        registers: registers.to_vec(),
+        points: Vec::new(),
+        blocks: Vec::new(),
+    };
+    let Some(definition) = ModelDefinition::get(id) else {
+        return model;
+    };
+    model.name = Some(definition.name);
+
+    let fixed_length = (definition.fixed_length() as usize).min(registers.len());
+    let fixed_scales = scale_factors(definition.fixed, &registers[..fixed_length], &HashMap::new());
+    model.points = decode_block(definition.fixed, &registers[..fixed_length], &fixed_scales);
+
+    let block_length = definition.repeating_length() as usize;
+    if block_length > 0 {
+        for block in registers[fixed_length..].chunks_exact(block_length) {
+            let scales = scale_factors(definition.repeating, block, &fixed_scales);
+            model.blocks.push(decode_block(definition.repeating, block, &scales));
+        }
+    }
+    model
+}
+
+/// Collect the scale factors of a block, falling back to `inherited` ones
+fn scale_factors(points: &[SunSpecPoint], registers: &[u16], inherited: &HashMap<&'static str, i16>) -> HashMap<&'static str, i16> {
+    let mut scales = inherited.clone();
+    let mut offset = 0usize;
+    for point in points {
+        if point.ty == SunSpecType::Sunssf {
+            if let Some(&raw) = registers.get(offset) {
+                if raw != 0x8000 {
+                    scales.insert(point.name, raw as i16);
+                }
+            }
+        }
+        offset += point.ty.registers() as usize;
+    }
+    scales
+}
+
+fn decode_block(points: &[SunSpecPoint], registers: &[u16], scales: &HashMap<&'static str, i16>) -> Vec<PointReading> {
+    let mut readings = Vec::with_capacity(points.len());
+    let mut offset = 0usize;
+    for point in points {
+        let end = offset + point.ty.registers() as usize;
+        if end > registers.len() {
+            break;
+        }
+        if point.ty != SunSpecType::Pad {
+            let mut value = decode_value(point.ty, &registers[offset..end]);
+            if let (Some(name), Some(SunSpecValue::Number(raw))) = (point.scale_factor, &value) {
+                value = scales.get(name).map(|&sf| SunSpecValue::Number(raw * 10f64.powi(sf as i32)));
+            }
+            readings.push(PointReading { name: point.name, value });
+        }
+        offset = end;
+    }
+    readings
+}
+
+/// First register of a read, which must not be empty
+fn first_register(registers: &[u16]) -> ModbusResult<u16> {
+    registers.first().copied().ok_or_else(|| ModbusError::frame("Empty register read"))
+}
+
+/// Decode one point, returning `None` for the "not implemented" value
+fn decode_value(ty: SunSpecType, registers: &[u16]) -> Option<SunSpecValue> {
+    let u32_value = || ((registers[0] as u32) << 16) | registers[1] as u32;
+    let u64_value = || registers[..4].iter().fold(0u64, |acc, &r| (acc << 16) | r as u64);
+    let number = |value: f64| Some(SunSpecValue::Number(value));
+
+    match ty {
+        T::Uint16 => (registers[0] != 0xFFFF).then(|| SunSpecValue::Number(registers[0] as f64)),
+        T::Int16 | T::Sunssf => (registers[0] != 0x8000).then(|| SunSpecValue::Number(registers[0] as i16 as f64)),
+        T::Acc16 => (registers[0] != 0).then(|| SunSpecValue::Number(registers[0] as f64)),
+        T::Enum16 => (registers[0] != 0xFFFF).then_some(SunSpecValue::Enum(registers[0])),
+        T::Bitfield16 => (registers[0] != 0xFFFF).then_some(SunSpecValue::Bitfield(registers[0] as u32)),
+        T::Uint32 => (u32_value() != 0xFFFF_FFFF).then(|| SunSpecValue::Number(u32_value() as f64)),
+        T::Int32 => (u32_value() != 0x8000_0000).then(|| SunSpecValue::Number(u32_value() as i32 as f64)),
+        T::Acc32 => (u32_value() != 0).then(|| SunSpecValue::Number(u32_value() as f64)),
+        T::Bitfield32 => (u32_value() != 0xFFFF_FFFF).then(|| SunSpecValue::Bitfield(u32_value())),
+        T::Float32 => {
+            let value = f32::from_bits(u32_value());
+            if value.is_nan() { None } else { number(value as f64) }
+        }
+        T::Uint64 => (u64_value() != u64::MAX).then(|| SunSpecValue::Number(u64_value() as f64)),
+        T::Acc64 => (u64_value() != 0).then(|| SunSpecValue::Number(u64_value() as f64)),
+        T::String(_) => {
+            let bytes: Vec<u8> = registers.iter().flat_map(|r| r.to_be_bytes()).collect();
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            let text = String::from_utf8_lossy(&bytes[..end]).trim_end().to_string();
+            (!text.is_empty()).then_some(SunSpecValue::String(text))
+        }
+        T::Pad => None,
+    }
+}
+
+/// Encode a numeric value for a point, dividing by the scale factor
+fn encode_value(point: &SunSpecPoint, value: f64, scale: i16) -> ModbusResult<Vec<u16>> {
+    let raw = value / 10f64.powi(scale as i32);
+    let out_of_range = || ModbusError::invalid_data(format!("{} out of range for point '{}'", value, point.name));
+    let integer = |min: f64, max: f64| {
+        let rounded = raw.round();
+        if rounded.is_finite() && rounded >= min && rounded <= max { Ok(rounded) } else { Err(out_of_range()) }
+    };
+
+    let registers = match point.ty {
+        T::Uint16 | T::Acc16 | T::Enum16 | T::Bitfield16 => vec![integer(0.0, u16::MAX as f64)? as u16],
+        T::Int16 | T::Sunssf => vec![integer(i16::MIN as f64, i16::MAX as f64)? as i16 as u16],
+        T::Uint32 | T::Acc32 | T::Bitfield32 => {
+            let raw = integer(0.0, u32::MAX as f64)? as u32;
+            vec![(raw >> 16) as u16, raw as u16]
+        }
+        T::Int32 => {
+            let raw = integer(i32::MIN as f64, i32::MAX as f64)? as i32 as u32;
+            vec![(raw >> 16) as u16, raw as u16]
+        }
+        T::Uint64 | T::Acc64 => {
+            let raw = integer(0.0, u64::MAX as f64)? as u64;
+            (0..4).rev().map(|i| (raw >> (16 * i)) as u16).collect()
+        }
+        T::Float32 => {
+            let bits = (raw as f32).to_bits();
+            vec![(bits >> 16) as u16, bits as u16]
+        }
+        T::String(_) | T::Pad => {
+            return Err(ModbusError::invalid_data(format!("Point '{}' is not numeric", point.name)));
+        }
+    };
+    Ok(registers)
+}
+
+/// Encode a string point, padded with NUL
+fn encode_text(point: &SunSpecPoint, value: &str) -> ModbusResult<Vec<u16>> {
+    let T::String(len) = point.ty else {
+        return Err(ModbusError::invalid_data(format!("Point '{}' is not a string", point.name)));
+    };
+    let capacity = len as usize * 2;
+    if value.len() > capacity {
+        return Err(ModbusError::invalid_data(format!("'{}' does not fit in point '{}'", value, point.name)));
+    }
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.resize(capacity, 0);
+    Ok(bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect())
+}
+
+/// Find a point and the scale factor point it refers to within a model
+fn locate_point(model_id: u16, name: &str) -> ModbusResult<(ModelDefinition, u16, &'static SunSpecPoint, Option<u16>)> {
+    let definition = ModelDefinition::get(model_id)
+        .ok_or_else(|| ModbusError::invalid_data(format!("Unsupported SunSpec model {}", model_id)))?;
+    let (offset, point) = definition.fixed_point(name)
+        .ok_or_else(|| ModbusError::invalid_data(format!("Model {} has no point '{}'", model_id, name)))?;
+    let scale_offset = point.scale_factor.and_then(|sf| definition.fixed_point(sf)).map(|(offset, _)| offset);
+    Ok((definition, offset, point, scale_offset))
+}
+
+/// A discovered SunSpec device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SunSpecDevice {
+    slave_id: SlaveId,
+    base_address: u16,
+    models: Vec<ModelInfo>,
+}
+
+impl SunSpecDevice {
+    /// Locate the `SunS` marker at the standard base addresses and walk the model chain
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client connected to the device
+    /// * `slave_id` - Slave device ID
+    ///
+    /// # Returns
+    ///
+    /// The device and its models, or a protocol error if no marker is found
+    pub async fn discover<C: ModbusClient>(client: &mut C, slave_id: SlaveId) -> ModbusResult<Self> {
+        for base in SUNSPEC_BASE_ADDRESSES {
+            match client.read_03(slave_id, base, 2).await {
+                Ok(marker) if marker == SUNSPEC_MARKER => {
+                    return Self::discover_at(client, slave_id, base).await;
+                }
+                Ok(_) => {}
+                // Devices reject unmapped base addresses with an exception
+                Err(e) if e.is_transport_error() => return Err(e),
+                Err(_) => {}
+            }
+        }
+        Err(ModbusError::protocol(format!("SunSpec marker not found on slave {}", slave_id)))
+    }
+
+    /// Walk the model chain of a device whose marker is at `base_address`
+    pub async fn discover_at<C: ModbusClient>(client: &mut C, slave_id: SlaveId, base_address: u16) -> ModbusResult<Self> {
+        let marker = client.read_03(slave_id, base_address, 2).await?;
+        if marker != SUNSPEC_MARKER {
+            return Err(ModbusError::protocol(format!("No SunSpec marker at address {}", base_address)));
+        }
+
+        let mut models = Vec::new();
+        let mut address = base_address.checked_add(2)
+            .ok_or_else(|| ModbusError::protocol("SunSpec model chain runs past the address space"))?;
+        loop {
+            let header = client.read_03(slave_id, address, 2).await?;
+            let &[id, length, ..] = header.as_slice() else {
+                return Err(ModbusError::frame(format!("Short SunSpec model header at address {}", address)));
+            };
+            if id == END_MODEL_ID {
+                break;
+            }
+            if models.len() == MAX_MODELS {
+                return Err(ModbusError::protocol("SunSpec model chain has no end marker"));
+            }
+
+            let info = ModelInfo { id, address, length };
+            address = address.checked_add(2 + info.length)
+                .ok_or_else(|| ModbusError::protocol("SunSpec model chain runs past the address space"))?;
+            models.push(info);
+        }
+
+        Ok(Self { slave_id, base_address, models })
+    }
+
+    /// Slave device ID
+    pub fn slave_id(&self) -> SlaveId {
+        self.slave_id
+    }
+
+    /// Address of the `SunS` marker
+    pub fn base_address(&self) -> u16 {
+        self.base_address
+    }
+
+    /// Models in chain order
+    pub fn models(&self) -> &[ModelInfo] {
+        &self.models
+    }
+
+    /// First model with the given ID
+    pub fn model(&self, id: u16) -> Option<&ModelInfo> {
+        self.models.iter().find(|m| m.id == id)
+    }
+
+    fn require_model(&self, id: u16) -> ModbusResult<&ModelInfo> {
+        self.model(id).ok_or_else(|| ModbusError::invalid_data(format!("Device has no SunSpec model {}", id)))
+    }
+
+    /// Read and decode the first model with the given ID
+    pub async fn read_model<C: ModbusClient>(&self, client: &mut C, id: u16) -> ModbusResult<SunSpecModel> {
+        let info = *self.require_model(id)?;
+        self.read(client, info).await
+    }
+
+    /// Read and decode every model in the chain
+    pub async fn read_all<C: ModbusClient>(&self, client: &mut C) -> ModbusResult<Vec<SunSpecModel>> {
+        let mut models = Vec::with_capacity(self.models.len());
+        for info in &self.models {
+            models.push(self.read(client, *info).await?);
+        }
+        Ok(models)
+    }
+
+    async fn read<C: ModbusClient>(&self, client: &mut C, info: ModelInfo) -> ModbusResult<SunSpecModel> {
+        let registers = client
+            .read_03_chunked(self.slave_id, info.data_address(), info.length as usize, &ChunkLimits::default())
+            .await?;
+        Ok(decode_model(info.id, info.address, &registers))
+    }
+
+    /// Write a control point, dividing the value by the device's scale factor
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client connected to the device
+    /// * `model_id` - Model containing the point
+    /// * `name` - Point name, e.g. `"WMaxLimPct"`
+    /// * `value` - Engineering value
+    pub async fn write_point<C: ModbusClient>(&self, client: &mut C, model_id: u16, name: &str, value: f64) -> ModbusResult<()> {
+        let info = *self.require_model(model_id)?;
+        let (_, offset, point, scale_offset) = locate_point(model_id, name)?;
+        if !point.writable {
+            return Err(ModbusError::invalid_data(format!("Point '{}' of model {} is read-only", name, model_id)));
+        }
+        if offset + point.ty.registers() > info.length {
+            return Err(ModbusError::invalid_data(format!("Point '{}' is beyond the device's model {}", name, model_id)));
+        }
+
+        let scale = match scale_offset {
+            Some(sf_offset) => {
+                if sf_offset >= info.length {
+                    return Err(ModbusError::invalid_data(format!("Scale factor of point '{}' is beyond the device's model {}", name, model_id)));
+                }
+                let raw = first_register(&client.read_03(self.slave_id, info.data_address() + sf_offset, 1).await?)?;
+                if raw == 0x8000 {
+                    return Err(ModbusError::invalid_data(format!("Scale factor of point '{}' is not implemented", name)));
+                }
+                raw as i16
+            }
+            None => 0,
+        };
+
+        let registers = encode_value(point, value, scale)?;
+        client.write_10(self.slave_id, info.data_address() + offset, &registers).await
+    }
+}
+
+/// A SunSpec device emulated on a register bank
+///
+/// Models are laid out after the `SunS` marker in the order they are added.
+/// The emulator answers requests directly as a [`ModbusClient`], and its bank
+/// can be served over TCP by a [`ModbusTcpServer`](crate::server::ModbusTcpServer).
+#[derive(Debug, Clone)]
+pub struct SunSpecEmulator {
+    bank: ModbusRegisterBank,
+    slave_id: SlaveId,
+    base_address: u16,
+    next_address: u16,
+    models: Vec<ModelInfo>,
+}
+
+impl SunSpecEmulator {
+    /// Create an emulator with its marker at address 40000
+    pub fn new(slave_id: SlaveId) -> Self {
+        Self::with_base_address(slave_id, SUNSPEC_BASE_ADDRESSES[0]).expect("default base address leaves room for the chain")
+    }
+
+    /// Create an emulator with its marker at `base_address`
+    ///
+    /// Fails if the marker and end model do not fit below the end of the address space.
+    pub fn with_base_address(slave_id: SlaveId, base_address: u16) -> ModbusResult<Self> {
+        let next_address = base_address.checked_add(2).filter(|next| next.checked_add(1).is_some())
+            .ok_or_else(|| ModbusError::invalid_data(format!("SunSpec base address {} leaves no room for models", base_address)))?;
+        let emulator = Self {
+            bank: ModbusRegisterBank::new(),
+            slave_id,
+            base_address,
+            next_address,
+            models: Vec::new(),
+        };
+        emulator.bank.write_10(base_address, &SUNSPEC_MARKER)?;
+        emulator.bank.write_10(next_address, &[END_MODEL_ID, 0])?;
+        Ok(emulator)
+    }
+
+    /// Register bank holding the device's registers
+    pub fn bank(&self) -> &ModbusRegisterBank {
+        &self.bank
+    }
+
+    /// Address of the `SunS` marker
+    pub fn base_address(&self) -> u16 {
+        self.base_address
+    }
+
+    /// Models in chain order
+    pub fn models(&self) -> &[ModelInfo] {
+        &self.models
+    }
+
+    /// Append a known model with zeroed data
+    ///
+    /// Models with repeating blocks get `blocks` of them; `blocks` is ignored otherwise.
+    pub fn add_model(&mut self, id: u16, blocks: u16) -> ModbusResult<ModelInfo> {
+        let definition = ModelDefinition::get(id)
+            .ok_or_else(|| ModbusError::invalid_data(format!("Unsupported SunSpec model {}", id)))?;
+        let length = definition.fixed_length() + definition.repeating_length() * blocks;
+        self.add_raw_model(id, &vec![0; length as usize])
+    }
+
+    /// Append a model with the given data registers
+    pub fn add_raw_model(&mut self, id: u16, data: &[u16]) -> ModbusResult<ModelInfo> {
+        let length = u16::try_from(data.len()).map_err(|_| ModbusError::invalid_data("Model data too long"))?;
+        let info = ModelInfo { id, address: self.next_address, length };
+        let end = self.next_address.checked_add(2 + length).filter(|end| end.checked_add(2).is_some())
+            .ok_or_else(|| ModbusError::invalid_data("Model does not fit in the address space"))?;
+
+        self.bank.write_10(info.address, &[id, length])?;
+        for (i, chunk) in data.chunks(123).enumerate() {
+            self.bank.write_10(info.data_address() + (i * 123) as u16, chunk)?;
+        }
+        self.bank.write_10(end, &[END_MODEL_ID, 0])?;
+        self.next_address = end;
+        self.models.push(info);
+        Ok(info)
+    }
+
+    /// Append a common model (1)
+    pub fn add_common(&mut self, manufacturer: &str, model: &str, serial_number: &str) -> ModbusResult<ModelInfo> {
+        let info = self.add_model(1, 0)?;
+        self.set_string(1, "Mn", manufacturer)?;
+        self.set_string(1, "Md", model)?;
+        self.set_string(1, "SN", serial_number)?;
+        self.set_raw(1, "DA", &[self.slave_id as u16])?;
+        Ok(info)
+    }
+
+    fn model_info(&self, id: u16) -> ModbusResult<ModelInfo> {
+        self.models.iter().find(|m| m.id == id).copied()
+            .ok_or_else(|| ModbusError::invalid_data(format!("Emulator has no SunSpec model {}", id)))
+    }
+
+    /// Set a fixed block point, dividing the value by the scale factor in the bank
+    pub fn set_point(&self, model_id: u16, name: &str, value: f64) -> ModbusResult<()> {
+        let info = self.model_info(model_id)?;
+        let (_, offset, point, scale_offset) = locate_point(model_id, name)?;
+        let scale = match scale_offset {
+            Some(sf_offset) => first_register(&self.bank.read_03(info.data_address() + sf_offset, 1)?)? as i16,
+            None => 0,
+        };
+        self.bank.write_10(info.data_address() + offset, &encode_value(point, value, scale)?)
+    }
+
+    /// Set a point of a repeating block
+    pub fn set_block_point(&self, model_id: u16, block: u16, name: &str, value: f64) -> ModbusResult<()> {
+        let info = self.model_info(model_id)?;
+        let definition = ModelDefinition::get(model_id)
+            .ok_or_else(|| ModbusError::invalid_data(format!("Unsupported SunSpec model {}", model_id)))?;
+        let (offset, point) = definition.repeating_point(name)
+            .ok_or_else(|| ModbusError::invalid_data(format!("Model {} has no block point '{}'", model_id, name)))?;
+        let block_address = info.data_address() + definition.fixed_length() + block * definition.repeating_length();
+
+        // Scale factors live in the block itself or in the fixed block
+        let scale = match point.scale_factor {
+            Some(sf) => match definition.repeating_point(sf) {
+                Some((sf_offset, _)) => first_register(&self.bank.read_03(block_address + sf_offset, 1)?)? as i16,
+                None => match definition.fixed_point(sf) {
+                    Some((sf_offset, _)) => first_register(&self.bank.read_03(info.data_address() + sf_offset, 1)?)? as i16,
+                    None => 0,
+                },
+            },
+            None => 0,
+        };
+        self.bank.write_10(block_address + offset, &encode_value(point, value, scale)?)
+    }
+
+    /// Set a string point
+    pub fn set_string(&self, model_id: u16, name: &str, value: &str) -> ModbusResult<()> {
+        let info = self.model_info(model_id)?;
+        let (_, offset, point, _) = locate_point(model_id, name)?;
+        self.bank.write_10(info.data_address() + offset, &encode_text(point, value)?)
+    }
+
+    /// Set the raw registers of a point, e.g. a scale factor or "not implemented" value
+    pub fn set_raw(&self, model_id: u16, name: &str, registers: &[u16]) -> ModbusResult<()> {
+        let info = self.model_info(model_id)?;
+        let (_, offset, point, _) = locate_point(model_id, name)?;
+        if registers.len() != point.ty.registers() as usize {
+            return Err(ModbusError::invalid_data(format!("Point '{}' needs {} registers", name, point.ty.registers())));
+        }
+        self.bank.write_10(info.data_address() + offset, registers)
+    }
+
+    fn check_slave(&self, slave_id: SlaveId) -> ModbusResult<()> {
+        if slave_id == self.slave_id {
+            Ok(())
+        } else {
+            Err(ModbusError::device_not_responding(slave_id))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ModbusClient for SunSpecEmulator {
+    async fn read_01(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.check_slave(slave_id)?;
+        self.bank.read_01(address, quantity)
+    }
+
+    async fn read_02(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<bool>> {
+        self.check_slave(slave_id)?;
+        self.bank.read_02(address, quantity)
+    }
+
+    async fn read_03(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.check_slave(slave_id)?;
+        self.bank.read_03(address, quantity)
+    }
+
+    async fn read_04(&mut self, slave_id: SlaveId, address: u16, quantity: u16) -> ModbusResult<Vec<u16>> {
+        self.check_slave(slave_id)?;
+        self.bank.read_04(address, quantity)
+    }
+
+    async fn write_05(&mut self, slave_id: SlaveId, address: u16, value: bool) -> ModbusResult<()> {
+        self.check_slave(slave_id)?;
+        self.bank.write_05(address, value)
+    }
+
+    async fn write_06(&mut self, slave_id: SlaveId, address: u16, value: u16) -> ModbusResult<()> {
+        self.check_slave(slave_id)?;
+        self.bank.write_06(address, value)
+    }
+
+    async fn write_0f(&mut self, slave_id: SlaveId, address: u16, values: &[bool]) -> ModbusResult<()> {
+        self.check_slave(slave_id)?;
+        self.bank.write_0f(address, values)
+    }
+
+    async fn write_10(&mut self, slave_id: SlaveId, address: u16, values: &[u16]) -> ModbusResult<()> {
+        self.check_slave(slave_id)?;
+        self.bank.write_10(address, values)
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        Ok(())
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        TransportStats::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GenericModbusClient;
+    use crate::protocol::ModbusFunction;
+    use crate::test_utils::ScriptedTransport;
+
+    #[test]
+    fn test_model_lengths() {
+        let expected = [
+            (1, 66), (101, 50), (103, 50), (111, 60), (113, 60), (120, 26), (121, 30),
+            (122, 44), (123, 24), (124, 24), (160, 8), (201, 105), (204, 105), (802, 62),
+        ];
+        for (id, length) in expected {
+            assert_eq!(ModelDefinition::get(id).unwrap().fixed_length(), length, "model {}", id);
+        }
+        assert_eq!(ModelDefinition::get(160).unwrap().repeating_length(), 20);
+        assert!(ModelDefinition::get(64000).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_discover_and_decode() {
+        let mut emulator = SunSpecEmulator::new(1);
+        emulator.add_common("Voltage", "VX-10", "SN0042").unwrap();
+        emulator.add_model(103, 0).unwrap();
+        emulator.add_raw_model(64001, &[1, 2, 3]).unwrap();
+        emulator.add_model(160, 2).unwrap();
+
+        emulator.set_raw(103, "W_SF", &[(-1i16) as u16]).unwrap();
+        emulator.set_point(103, "W", 1234.5).unwrap();
+        emulator.set_raw(103, "Hz_SF", &[(-2i16) as u16]).unwrap();
+        emulator.set_point(103, "Hz", 50.02).unwrap();
+        emulator.set_raw(103, "TmpCab", &[0x8000]).unwrap();
+        emulator.set_point(103, "St", 4.0).unwrap();
+        emulator.set_raw(160, "DCV_SF", &[(-1i16) as u16]).unwrap();
+        emulator.set_block_point(160, 1, "DCV", 612.3).unwrap();
+
+        let mut client = emulator.clone();
+        let device = SunSpecDevice::discover(&mut client, 1).await.unwrap();
+        assert_eq!(device.base_address(), 40000);
+        let ids: Vec<u16> = device.models().iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![1, 103, 64001, 160]);
+        assert_eq!(device.model(103).unwrap().address, 40000 + 2 + 2 + 66);
+
+        let common = device.read_model(&mut client, 1).await.unwrap();
+        assert_eq!(common.string("Mn"), Some("Voltage"));
+        assert_eq!(common.string("SN"), Some("SN0042"));
+        assert_eq!(common.number("DA"), Some(1.0));
+
+        let inverter = device.read_model(&mut client, 103).await.unwrap();
+        assert_eq!(inverter.name, Some("inverter_three_phase"));
+        assert_eq!(inverter.number("W"), Some(1234.5));
+        assert!((inverter.number("Hz").unwrap() - 50.02).abs() < 1e-9);
+        assert_eq!(inverter.get("TmpCab"), None);
+        assert_eq!(inverter.get("St"), Some(&SunSpecValue::Enum(4)));
+
+        let models = device.read_all(&mut client).await.unwrap();
+        assert_eq!(models[2].name, None);
+        assert_eq!(models[2].registers, vec![1, 2, 3]);
+        assert_eq!(models[3].blocks.len(), 2);
+        assert!((models[3].block_value(1, "DCV").unwrap().as_f64().unwrap() - 612.3).abs() < 1e-9);
+
+        // No marker anywhere
+        let mut empty = SunSpecEmulator::new(1);
+        empty.bank().write_10(40000, &[0, 0]).unwrap();
+        assert!(SunSpecDevice::discover(&mut empty, 1).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_controls() {
+        let mut emulator = SunSpecEmulator::with_base_address(1, 50000).unwrap();
+        emulator.add_common("Voltage", "VX-10", "SN0042").unwrap();
+        emulator.add_model(123, 0).unwrap();
+        emulator.set_raw(123, "WMaxLimPct_SF", &[(-1i16) as u16]).unwrap();
+
+        let mut client = emulator.clone();
+        let device = SunSpecDevice::discover(&mut client, 1).await.unwrap();
+        assert_eq!(device.base_address(), 50000);
+
+        device.write_point(&mut client, 123, "WMaxLimPct", 55.5).await.unwrap();
+        device.write_point(&mut client, 123, "WMaxLim_Ena", 1.0).await.unwrap();
+        let info = *device.model(123).unwrap();
+        assert_eq!(emulator.bank().read_03(info.data_address() + 3, 1).unwrap(), vec![555]);
+
+        let controls = device.read_model(&mut client, 123).await.unwrap();
+        assert_eq!(controls.number("WMaxLimPct"), Some(55.5));
+        assert_eq!(controls.get("WMaxLim_Ena"), Some(&SunSpecValue::Enum(1)));
+
+        assert!(device.write_point(&mut client, 123, "WMaxLimPct_SF", 1.0).await.is_err());
+        assert!(device.write_point(&mut client, 103, "W", 1.0).await.is_err());
+        assert!(device.write_point(&mut client, 123, "WMaxLimPct", 1e9).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_discover_rejects_bad_headers_and_addresses() {
+        assert!(SunSpecEmulator::with_base_address(1, 0xFFFE).is_err());
+        assert!(SunSpecEmulator::with_base_address(1, 0xFFFC).is_ok());
+
+        // Marker at the very end of the address space leaves no room for a header
+        let mut client = SunSpecEmulator::new(1);
+        client.bank().write_10(0xFFFE, &SUNSPEC_MARKER).unwrap();
+        assert!(SunSpecDevice::discover_at(&mut client, 1, 0xFFFE).await.is_err());
+
+        // A header read that comes back short is a frame error, not a panic
+        let transport = ScriptedTransport::new();
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &SUNSPEC_MARKER);
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[1]);
+        let mut client = GenericModbusClient::new(transport);
+        let error = SunSpecDevice::discover_at(&mut client, 1, 40000).await.unwrap_err();
+        assert!(matches!(error, ModbusError::Frame { .. }), "{:?}", error);
+    }
+}
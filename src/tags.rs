@@ -0,0 +1,624 @@
+//! # Declarative Point Maps
+//!
+//! A point map describes a device's register map as data, so applications
+//! address values by name instead of by table, address and encoding.
+//!
+//! Each point defines:
+//!
+//! - **name** and optional **description** / **units**
+//! - **table**: `coil`, `discrete`, `holding` or `input`
+//! - **address**: 0-based start address
+//! - **data_type**: `bool`, `u16`, `i16`, `u32`, `i32`, `f32`, `u64`, `i64`, `f64`
+//! - **word_order** / **byte_order**: `big` (default) or `little`
+//! - **scale** / **offset**: engineering value = raw × scale + offset
+//! - **access**: `read`, `write` or `read_write`
+//! - **initial**: value written by [`PointMap::populate_bank`]
+//!
+//! The same file drives a client ([`PointMap::read`], [`PointMap::write`],
+//! [`PointMap::read_all`]) and a simulated device ([`PointMap::populate_bank`]).
+//!
+//! ## Example Map (YAML)
+//!
+//! ```yaml
+//! slave_id: 1
+//! points:
+//!   - name: voltage
+//!     table: input
+//!     address: 0
+//!     data_type: f32
+//!     units: V
+//!     initial: 230.0
+//!   - name: power_limit
+//!     table: holding
+//!     address: 10
+//!     data_type: u16
+//!     scale: 0.1
+//!     units: kW
+//!     access: read_write
+//!   - name: breaker_closed
+//!     table: coil
+//!     address: 0
+//!     access: read_write
+//! ```
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use voltage_modbus::{ModbusTcpClient, PointMap, TagValue};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let map = PointMap::from_file("meter.yaml")?;
+//! let mut client = ModbusTcpClient::from_address("127.0.0.1:502", Duration::from_secs(1)).await?;
+//!
+//! let voltage = map.read(&mut client, 1, "voltage").await?;
+//! map.write(&mut client, 1, "power_limit", TagValue::Number(42.5)).await?;
+//! let everything = map.read_all(&mut client, 1).await?;
+//! println!("{:?} {:?}", voltage, everything);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::client::ModbusClient;
+use crate::codec::{RegisterOrder, RegisterValue};
+use crate::codec::{ByteOrder, WordOrder};
+use crate::error::{ModbusError, ModbusResult};
+use crate::planner::{PlannerConfig, PointValue, ReadPlanner, ReadPoint};
+use crate::protocol::{RegisterTable, SlaveId};
+use crate::register_bank::ModbusRegisterBank;
+
+/// Value encoding of a point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DataType {
+    /// Single bit (coils and discrete inputs)
+    Bool,
+    /// Unsigned 16-bit integer
+    #[default]
+    U16,
+    /// Signed 16-bit integer
+    I16,
+    /// Unsigned 32-bit integer
+    U32,
+    /// Signed 32-bit integer
+    I32,
+    /// IEEE 754 single precision float
+    F32,
+    /// Unsigned 64-bit integer
+    U64,
+    /// Signed 64-bit integer
+    I64,
+    /// IEEE 754 double precision float
+    F64,
+}
+
+impl DataType {
+    /// Number of registers (or bits, for `Bool`) occupied by a value
+    pub fn register_count(self) -> u16 {
+        match self {
+            DataType::Bool | DataType::U16 | DataType::I16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 => 2,
+            DataType::U64 | DataType::I64 | DataType::F64 => 4,
+        }
+    }
+
+    /// Check if the type is an integer type
+    pub fn is_integer(self) -> bool {
+        !matches!(self, DataType::Bool | DataType::F32 | DataType::F64)
+    }
+}
+
+/// Access mode of a point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    /// Read only
+    #[serde(alias = "r", alias = "ro")]
+    Read,
+    /// Write only
+    #[serde(alias = "w", alias = "wo")]
+    Write,
+    /// Read and write
+    #[serde(alias = "rw")]
+    ReadWrite,
+}
+
+impl Access {
+    /// Check if reads are allowed
+    pub fn can_read(self) -> bool {
+        matches!(self, Access::Read | Access::ReadWrite)
+    }
+
+    /// Check if writes are allowed
+    pub fn can_write(self) -> bool {
+        matches!(self, Access::Write | Access::ReadWrite)
+    }
+}
+
+/// Engineering value of a point
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TagValue {
+    /// Bit value
+    Bool(bool),
+    /// Scaled numeric value
+    Number(f64),
+}
+
+impl TagValue {
+    /// Numeric value (`true` = 1.0)
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            TagValue::Bool(value) => if *value { 1.0 } else { 0.0 },
+            TagValue::Number(value) => *value,
+        }
+    }
+
+    /// Boolean value (non-zero numbers are `true`)
+    pub fn as_bool(&self) -> bool {
+        match self {
+            TagValue::Bool(value) => *value,
+            TagValue::Number(value) => *value != 0.0,
+        }
+    }
+}
+
+impl From<bool> for TagValue {
+    fn from(value: bool) -> Self {
+        TagValue::Bool(value)
+    }
+}
+
+impl From<f64> for TagValue {
+    fn from(value: f64) -> Self {
+        TagValue::Number(value)
+    }
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+/// Definition of a single point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointDef {
+    /// Unique point name
+    pub name: String,
+    /// Data table
+    pub table: RegisterTable,
+    /// 0-based start address
+    pub address: u16,
+    /// Value encoding (`bool` for bit tables, `u16` otherwise when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<DataType>,
+    /// Word order of multi-register values
+    #[serde(default)]
+    pub word_order: WordOrder,
+    /// Byte order within each register
+    #[serde(default)]
+    pub byte_order: ByteOrder,
+    /// Scale factor applied to the raw value
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// Offset added after scaling
+    #[serde(default)]
+    pub offset: f64,
+    /// Engineering units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    /// Free-form description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Access mode (read-only for input tables, read/write otherwise when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access: Option<Access>,
+    /// Initial value used when populating a register bank
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial: Option<TagValue>,
+}
+
+impl PointDef {
+    /// Create a point with default encoding
+    pub fn new(name: impl Into<String>, table: RegisterTable, address: u16) -> Self {
+        Self {
+            name: name.into(),
+            table,
+            address,
+            data_type: None,
+            word_order: WordOrder::default(),
+            byte_order: ByteOrder::default(),
+            scale: 1.0,
+            offset: 0.0,
+            units: None,
+            description: None,
+            access: None,
+            initial: None,
+        }
+    }
+
+    /// Set the data type
+    pub fn with_data_type(mut self, data_type: DataType) -> Self {
+        self.data_type = Some(data_type);
+        self
+    }
+
+    /// Set scale and offset
+    pub fn with_scaling(mut self, scale: f64, offset: f64) -> Self {
+        self.scale = scale;
+        self.offset = offset;
+        self
+    }
+
+    /// Set the access mode
+    pub fn with_access(mut self, access: Access) -> Self {
+        self.access = Some(access);
+        self
+    }
+
+    /// Effective data type
+    pub fn data_type(&self) -> DataType {
+        self.data_type.unwrap_or(if self.table.is_bit_table() { DataType::Bool } else { DataType::U16 })
+    }
+
+    /// Combined word and byte order of the point
+    pub fn register_order(&self) -> RegisterOrder {
+        RegisterOrder::new(self.word_order, self.byte_order)
+    }
+
+    /// Effective access mode
+    pub fn access(&self) -> Access {
+        self.access.unwrap_or(if self.table.is_writable() { Access::ReadWrite } else { Access::Read })
+    }
+
+    /// Number of registers or bits occupied by the point
+    pub fn length(&self) -> u16 {
+        self.data_type().register_count()
+    }
+
+    /// Address range read for this point
+    pub fn read_point(&self) -> ReadPoint {
+        ReadPoint::new(self.table.read_function(), self.address, self.length())
+    }
+
+    /// Decode raw table data into the engineering value
+    pub fn decode(&self, data: &PointValue) -> ModbusResult<TagValue> {
+        match (self.data_type(), data) {
+            (DataType::Bool, PointValue::Bits(bits)) => bits
+                .first()
+                .map(|bit| TagValue::Bool(*bit))
+                .ok_or_else(|| ModbusError::invalid_data(format!("No data for point '{}'", self.name))),
+            (data_type, PointValue::Registers(registers)) if data_type != DataType::Bool => {
+                let raw = decode_registers(data_type, registers, self.register_order())?;
+                Ok(TagValue::Number(raw * self.scale + self.offset))
+            }
+            _ => Err(ModbusError::invalid_data(format!("Data does not match the type of point '{}'", self.name))),
+        }
+    }
+
+    /// Encode an engineering value into raw table data
+    pub fn encode(&self, value: TagValue) -> ModbusResult<PointValue> {
+        match self.data_type() {
+            DataType::Bool => Ok(PointValue::Bits(vec![value.as_bool()])),
+            data_type => {
+                if self.scale == 0.0 {
+                    return Err(ModbusError::configuration(format!("Point '{}' has a zero scale", self.name)));
+                }
+                let raw = (value.as_f64() - self.offset) / self.scale;
+                let registers = encode_registers(data_type, raw, self.register_order())
+                    .map_err(|_| ModbusError::invalid_data(format!(
+                        "Value {} out of range for point '{}' ({:?})",
+                        value.as_f64(), self.name, data_type
+                    )))?;
+                Ok(PointValue::Registers(registers))
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> ModbusResult<()> {
+        let data_type = self.data_type();
+        if self.table.is_bit_table() != (data_type == DataType::Bool) {
+            return Err(ModbusError::configuration(format!(
+                "Point '{}': data type {:?} does not fit the {} table",
+                self.name, data_type, self.table
+            )));
+        }
+        if self.address as u32 + self.length() as u32 > 0x10000 {
+            return Err(ModbusError::configuration(format!("Point '{}' exceeds the address space", self.name)));
+        }
+        if self.access().can_write() && !self.table.is_writable() {
+            return Err(ModbusError::configuration(format!(
+                "Point '{}' is writable but the {} table is read-only",
+                self.name, self.table
+            )));
+        }
+        if !self.scale.is_finite() || self.scale == 0.0 || !self.offset.is_finite() {
+            return Err(ModbusError::configuration(format!("Point '{}' has invalid scaling", self.name)));
+        }
+        Ok(())
+    }
+}
+
+/// Collection of named points describing a device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct PointMap {
+    /// Default slave ID of the device, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slave_id: Option<SlaveId>,
+    /// Point definitions
+    pub points: Vec<PointDef>,
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl PointMap {
+    /// Create a point map, validating the definitions
+    pub fn new(points: Vec<PointDef>) -> ModbusResult<Self> {
+        Self {
+            slave_id: None,
+            points,
+            index: HashMap::new(),
+        }
+        .validated()
+    }
+
+    /// Parse a point map from YAML
+    pub fn from_yaml_str(yaml: &str) -> ModbusResult<Self> {
+        let map: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| ModbusError::configuration(format!("Invalid point map: {}", e)))?;
+        map.validated()
+    }
+
+    /// Parse a point map from JSON
+    pub fn from_json_str(json: &str) -> ModbusResult<Self> {
+        let map: Self = serde_json::from_str(json)
+            .map_err(|e| ModbusError::configuration(format!("Invalid point map: {}", e)))?;
+        map.validated()
+    }
+
+    /// Load a point map from a `.yaml`/`.yml` or `.json` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ModbusResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ModbusError::configuration(format!("Cannot read {}: {}", path.display(), e)))?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&content),
+            _ => Self::from_yaml_str(&content),
+        }
+    }
+
+    /// Serialize the point map to YAML
+    pub fn to_yaml_string(&self) -> ModbusResult<String> {
+        serde_yaml::to_string(self).map_err(|e| ModbusError::internal(format!("Cannot serialize point map: {}", e)))
+    }
+
+    /// Look up a point by name
+    pub fn get(&self, name: &str) -> Option<&PointDef> {
+        self.index.get(name).map(|&i| &self.points[i])
+    }
+
+    /// Look up a point by name, failing for unknown names
+    pub fn point(&self, name: &str) -> ModbusResult<&PointDef> {
+        self.get(name)
+            .ok_or_else(|| ModbusError::configuration(format!("Unknown point '{}'", name)))
+    }
+
+    /// Read a point through a client
+    pub async fn read<C: ModbusClient + ?Sized>(&self, client: &mut C, slave_id: SlaveId, name: &str) -> ModbusResult<TagValue> {
+        let point = self.point(name)?;
+        if !point.access().can_read() {
+            return Err(ModbusError::configuration(format!("Point '{}' is write-only", name)));
+        }
+
+        let (address, length) = (point.address, point.length());
+        let data = match point.table {
+            RegisterTable::Coil => PointValue::Bits(client.read_01(slave_id, address, length).await?),
+            RegisterTable::DiscreteInput => PointValue::Bits(client.read_02(slave_id, address, length).await?),
+            RegisterTable::HoldingRegister => PointValue::Registers(client.read_03(slave_id, address, length).await?),
+            RegisterTable::InputRegister => PointValue::Registers(client.read_04(slave_id, address, length).await?),
+        };
+        point.decode(&data)
+    }
+
+    /// Write a point through a client
+    pub async fn write<C: ModbusClient + ?Sized>(&self, client: &mut C, slave_id: SlaveId, name: &str, value: TagValue) -> ModbusResult<()> {
+        let point = self.point(name)?;
+        if !point.access().can_write() {
+            return Err(ModbusError::configuration(format!("Point '{}' is read-only", name)));
+        }
+
+        match point.encode(value)? {
+            PointValue::Bits(bits) => client.write_05(slave_id, point.address, bits[0]).await,
+            PointValue::Registers(registers) if registers.len() == 1 => client.write_06(slave_id, point.address, registers[0]).await,
+            PointValue::Registers(registers) => client.write_10(slave_id, point.address, &registers).await,
+        }
+    }
+
+    /// Read every readable point, coalescing adjacent points into block requests
+    pub async fn read_all<C: ModbusClient + ?Sized>(&self, client: &mut C, slave_id: SlaveId) -> ModbusResult<HashMap<String, TagValue>> {
+        let readable: Vec<&PointDef> = self.points.iter().filter(|p| p.access().can_read()).collect();
+        let read_points: Vec<ReadPoint> = readable.iter().map(|p| p.read_point()).collect();
+
+        let plan = ReadPlanner::new(PlannerConfig::default()).plan(&read_points)?;
+        let values = plan.execute(client, slave_id).await?;
+
+        readable
+            .iter()
+            .zip(values)
+            .map(|(point, data)| Ok((point.name.clone(), point.decode(&data)?)))
+            .collect()
+    }
+
+    /// Write every point's initial value (or zero) into a register bank
+    pub fn populate_bank(&self, bank: &ModbusRegisterBank) -> ModbusResult<()> {
+        for point in &self.points {
+            self.write_bank(bank, &point.name, point.initial.unwrap_or(TagValue::Number(0.0)))?;
+        }
+        Ok(())
+    }
+
+    /// Write a point's value directly into a register bank
+    ///
+    /// Bypasses the access mode, so read-only points of a simulated device can be updated.
+    pub fn write_bank(&self, bank: &ModbusRegisterBank, name: &str, value: TagValue) -> ModbusResult<()> {
+        let point = self.point(name)?;
+        let address = point.address;
+
+        match (point.table, point.encode(value)?) {
+            (RegisterTable::Coil, PointValue::Bits(bits)) => bank.write_0f(address, &bits),
+            (RegisterTable::DiscreteInput, PointValue::Bits(bits)) => bank.set_discrete_input(address, bits[0]),
+            (RegisterTable::HoldingRegister, PointValue::Registers(registers)) => bank.write_10(address, &registers),
+            (RegisterTable::InputRegister, PointValue::Registers(registers)) => {
+                for (offset, value) in registers.into_iter().enumerate() {
+                    bank.set_input_register(address + offset as u16, value)?;
+                }
+                Ok(())
+            }
+            _ => Err(ModbusError::internal("Encoded data does not match the point table")),
+        }
+    }
+
+    /// Read a point's value directly from a register bank
+    pub fn read_bank(&self, bank: &ModbusRegisterBank, name: &str) -> ModbusResult<TagValue> {
+        let point = self.point(name)?;
+        let (address, length) = (point.address, point.length());
+        let data = match point.table {
+            RegisterTable::Coil => PointValue::Bits(bank.read_01(address, length)?),
+            RegisterTable::DiscreteInput => PointValue::Bits(bank.read_02(address, length)?),
+            RegisterTable::HoldingRegister => PointValue::Registers(bank.read_03(address, length)?),
+            RegisterTable::InputRegister => PointValue::Registers(bank.read_04(address, length)?),
+        };
+        point.decode(&data)
+    }
+
+    /// Validate the points and build the name index
+    fn validated(mut self) -> ModbusResult<Self> {
+        self.index.clear();
+        for (i, point) in self.points.iter().enumerate() {
+            point.validate()?;
+            if self.index.insert(point.name.clone(), i).is_some() {
+                return Err(ModbusError::configuration(format!("Duplicate point name '{}'", point.name)));
+            }
+        }
+        Ok(self)
+    }
+}
+
+pub(crate) fn decode_registers(data_type: DataType, registers: &[u16], order: RegisterOrder) -> ModbusResult<f64> {
+    let value = match data_type {
+        DataType::U16 => u16::from_registers(registers, order)? as f64,
+        DataType::I16 => i16::from_registers(registers, order)? as f64,
+        DataType::U32 => u32::from_registers(registers, order)? as f64,
+        DataType::I32 => i32::from_registers(registers, order)? as f64,
+        DataType::F32 => f32::from_registers(registers, order)? as f64,
+        DataType::U64 => u64::from_registers(registers, order)? as f64,
+        DataType::I64 => i64::from_registers(registers, order)? as f64,
+        DataType::F64 => f64::from_registers(registers, order)?,
+        DataType::Bool => return Err(ModbusError::invalid_data("Bool points are not stored in registers")),
+    };
+    Ok(value)
+}
+
+fn encode_registers(data_type: DataType, raw: f64, order: RegisterOrder) -> ModbusResult<Vec<u16>> {
+    let out_of_range = || ModbusError::invalid_data(format!("{} out of range for {:?}", raw, data_type));
+    let integer = |min: f64, max: f64| {
+        let rounded = raw.round();
+        if rounded.is_finite() && rounded >= min && rounded <= max { Ok(rounded) } else { Err(out_of_range()) }
+    };
+
+    let registers = match data_type {
+        DataType::U16 => (integer(0.0, u16::MAX as f64)? as u16).to_registers(order),
+        DataType::I16 => (integer(i16::MIN as f64, i16::MAX as f64)? as i16).to_registers(order),
+        DataType::U32 => (integer(0.0, u32::MAX as f64)? as u32).to_registers(order),
+        DataType::I32 => (integer(i32::MIN as f64, i32::MAX as f64)? as i32).to_registers(order),
+        DataType::U64 => (integer(0.0, u64::MAX as f64)? as u64).to_registers(order),
+        DataType::I64 => (integer(i64::MIN as f64, i64::MAX as f64)? as i64).to_registers(order),
+        DataType::F32 => (raw as f32).to_registers(order),
+        DataType::F64 => raw.to_registers(order),
+        DataType::Bool => return Err(out_of_range()),
+    };
+    Ok(registers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::GenericModbusClient;
+    use crate::protocol::ModbusFunction;
+    use crate::test_utils::ScriptedTransport;
+
+    const MAP: &str = r#"
+slave_id: 3
+points:
+  - name: voltage
+    table: input
+    address: 0
+    data_type: f32
+    word_order: little
+    units: V
+    initial: 230.5
+  - name: power_limit
+    table: holding
+    address: 10
+    scale: 0.1
+    units: kW
+    initial: 42.5
+  - name: temperature
+    table: holding
+    address: 11
+    data_type: i16
+    offset: -40
+  - name: breaker_closed
+    table: coil
+    address: 0
+    initial: true
+"#;
+
+    #[test]
+    fn test_parse_and_bank_round_trip() {
+        let map = PointMap::from_yaml_str(MAP).unwrap();
+        assert_eq!(map.slave_id, Some(3));
+        assert_eq!(map.point("voltage").unwrap().access(), Access::Read);
+        assert_eq!(map.point("breaker_closed").unwrap().data_type(), DataType::Bool);
+
+        let bank = ModbusRegisterBank::new();
+        map.populate_bank(&bank).unwrap();
+        assert_eq!(map.read_bank(&bank, "voltage").unwrap(), TagValue::Number(230.5));
+        assert_eq!(bank.read_03(10, 1).unwrap(), vec![425]);
+        assert_eq!(bank.read_01(0, 1).unwrap(), vec![true]);
+
+        // Little word order: low word first
+        let raw = 230.5f32.to_bits();
+        assert_eq!(bank.read_04(0, 2).unwrap(), vec![raw as u16, (raw >> 16) as u16]);
+
+        // Same map as JSON, and validation errors
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(PointMap::from_json_str(&json).unwrap().points, map.points);
+        assert!(PointMap::from_yaml_str("points:\n  - {name: a, table: input, address: 0, access: write}").is_err());
+        assert!(PointMap::from_yaml_str("points:\n  - {name: a, table: coil, address: 0, data_type: f32}").is_err());
+        assert!(PointMap::from_yaml_str("points:\n  - {name: a, table: coil, address: 0}\n  - {name: a, table: coil, address: 1}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_and_write_by_name() {
+        let map = PointMap::from_yaml_str(MAP).unwrap();
+        let transport = ScriptedTransport::new();
+        let mut client = GenericModbusClient::new(transport.clone());
+
+        transport.push_registers(ModbusFunction::ReadHoldingRegisters, &[425]);
+        let value = map.read(&mut client, 3, "power_limit").await.unwrap();
+        assert!((value.as_f64() - 42.5).abs() < 1e-9);
+
+        transport.push(Ok(crate::protocol::ModbusResponse::new_success(3, ModbusFunction::WriteSingleRegister, vec![])));
+        map.write(&mut client, 3, "temperature", TagValue::Number(25.0)).await.unwrap();
+        let request = transport.requests.lock().unwrap().last().cloned().unwrap();
+        assert_eq!((request.address, request.data.clone()), (11, vec![0x00, 65]));
+
+        assert!(map.write(&mut client, 3, "voltage", TagValue::Number(1.0)).await.is_err());
+        assert!(map.read(&mut client, 3, "missing").await.is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Shared helpers for unit tests
+//!
+//! Provides a scripted transport so client-layer features can be tested
+//! without sockets or serial ports.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+
+use crate::error::{ModbusError, ModbusResult};
+use crate::protocol::{ModbusFunction, ModbusRequest, ModbusResponse};
+use crate::transport::{ModbusTransport, TransportStats};
+
+/// Transport that replays queued results and records every request it sees
+///
+/// When the script is exhausted, requests fail with a timeout.
+#[derive(Clone, Default)]
+pub struct ScriptedTransport {
+    pub script: Arc<Mutex<VecDeque<ModbusResult<ModbusResponse>>>>,
+    pub requests: Arc<Mutex<Vec<ModbusRequest>>>,
+}
+
+impl ScriptedTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a result for the next request
+    pub fn push(&self, result: ModbusResult<ModbusResponse>) {
+        self.script.lock().unwrap().push_back(result);
+    }
+
+    /// Queue a successful register read response
+    pub fn push_registers(&self, function: ModbusFunction, values: &[u16]) {
+        let mut data = vec![(values.len() * 2) as u8];
+        for value in values {
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        self.push(Ok(ModbusResponse::new_success(1, function, data)));
+    }
+
+    /// Number of requests received so far
+    pub fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl ModbusTransport for ScriptedTransport {
+    async fn request(&mut self, request: &ModbusRequest) -> ModbusResult<ModbusResponse> {
+        self.requests.lock().unwrap().push(request.clone());
+        self.script
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| Err(ModbusError::timeout("scripted transport exhausted", 0)))
+    }
+
+    fn is_connected(&self) -> bool {
+        true
+    }
+
+    async fn close(&mut self) -> ModbusResult<()> {
+        Ok(())
+    }
+
+    fn get_stats(&self) -> TransportStats {
+        TransportStats {
+            requests_sent: self.request_count() as u64,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! # Server Watchdog
+//!
+//! A device must reach a safe state when its master stops talking, e.g. an
+//! inverter dropping to zero power when the EMS goes silent. A [`Watchdog`]
+//! attached to a server expires after [`WatchdogConfig::timeout`] without
+//! activity and then:
+//!
+//! - writes the failsafe values to the server's data store as one step:
+//!   requests are held off until all values are written, so no master sees
+//!   them half-applied
+//! - emits [`WatchdogEvent::Expired`]
+//!
+//! Activity is any successful write request or, with a heartbeat configured,
+//! only writes covering the heartbeat register or coil. The next activity after
+//! expiry re-arms the watchdog and emits [`WatchdogEvent::Rearmed`]; the
+//! failsafe values stay in place until the master overwrites them.
+//!
+//! The watchdog is armed when the server starts.
+//!
+//! ## Usage Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use voltage_modbus::{DataAddress, ModbusServer, ModbusTcpServer, RegisterTable};
+//! use voltage_modbus::planner::PointValue;
+//! use voltage_modbus::watchdog::{WatchdogConfig, WatchdogEvent};
+//!
+//! # async fn example() -> voltage_modbus::ModbusResult<()> {
+//! let watchdog = WatchdogConfig::new(Duration::from_secs(10))
+//!     .with_heartbeat(DataAddress::new(RegisterTable::HoldingRegister, 99))
+//!     // Power limit 0 %, breaker open
+//!     .with_failsafe(DataAddress::new(RegisterTable::HoldingRegister, 10), PointValue::Registers(vec![0]))
+//!     .with_failsafe(DataAddress::new(RegisterTable::Coil, 0), PointValue::Bits(vec![false]));
+//!
+//! let mut server = ModbusTcpServer::new("0.0.0.0:502")?.with_watchdog(watchdog);
+//! let mut events = server.watchdog().unwrap().subscribe();
+//! server.start().await?;
+//!
+//! while let Ok(event) = events.recv().await {
+//!     if let WatchdogEvent::Expired { .. } = event {
+//!         println!("Master silent, failsafe applied");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{error, warn};
+use tokio::sync::{broadcast, Notify, RwLock};
+use tokio::time::Instant;
+
+use crate::address::DataAddress;
+use crate::error::ModbusResult;
+use crate::planner::PointValue;
+use crate::protocol::RegisterTable;
+use crate::store::DataStore;
+
+/// Capacity of the watchdog event channel
+const WATCHDOG_CHANNEL_CAPACITY: usize = 16;
+
+/// Watchdog configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchdogConfig {
+    /// Time without activity before the watchdog expires
+    pub timeout: Duration,
+    /// Values written when the watchdog expires, in order
+    pub failsafe: Vec<(DataAddress, PointValue)>,
+    /// Register or coil whose writes re-arm the watchdog; any write when `None`
+    pub heartbeat: Option<DataAddress>,
+}
+
+impl WatchdogConfig {
+    /// Expire after `timeout` without writes, with no failsafe values yet
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, failsafe: Vec::new(), heartbeat: None }
+    }
+
+    /// Write `value` at `address` on expiry
+    pub fn with_failsafe(mut self, address: DataAddress, value: PointValue) -> Self {
+        self.failsafe.push((address, value));
+        self
+    }
+
+    /// Only re-arm on writes covering `address`
+    pub fn with_heartbeat(mut self, address: DataAddress) -> Self {
+        self.heartbeat = Some(address);
+        self
+    }
+}
+
+/// Watchdog state change
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogEvent {
+    /// No activity for the timeout
+    Expired {
+        /// Whether all failsafe values were written
+        failsafe_applied: bool,
+    },
+    /// Activity resumed after expiry
+    Rearmed,
+}
+
+/// Communication watchdog of a server
+#[derive(Debug)]
+pub struct Watchdog {
+    config: WatchdogConfig,
+    deadline: Mutex<Instant>,
+    expired: AtomicBool,
+    activity: Notify,
+    /// Held shared by requests and exclusively while failsafe values are written
+    gate: RwLock<()>,
+    events: broadcast::Sender<WatchdogEvent>,
+}
+
+impl Watchdog {
+    /// Create an armed watchdog
+    pub fn new(config: WatchdogConfig) -> Self {
+        let deadline = Instant::now() + config.timeout;
+        Self {
+            config,
+            deadline: Mutex::new(deadline),
+            expired: AtomicBool::new(false),
+            activity: Notify::new(),
+            gate: RwLock::new(()),
+            events: broadcast::channel(WATCHDOG_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Watchdog configuration
+    pub fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Receive expiry and re-arm events
+    pub fn subscribe(&self) -> broadcast::Receiver<WatchdogEvent> {
+        self.events.subscribe()
+    }
+
+    /// Check whether the watchdog has expired and not been re-armed since
+    pub fn is_expired(&self) -> bool {
+        self.expired.load(Ordering::Acquire)
+    }
+
+    /// Re-arm the watchdog as if the master had written
+    pub fn feed(&self) {
+        let rearmed = {
+            let mut deadline = self.lock_deadline();
+            *deadline = Instant::now() + self.config.timeout;
+            self.expired.swap(false, Ordering::AcqRel)
+        };
+        if rearmed {
+            let _ = self.events.send(WatchdogEvent::Rearmed);
+        }
+        self.activity.notify_one();
+    }
+
+    fn lock_deadline(&self) -> std::sync::MutexGuard<'_, Instant> {
+        self.deadline.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run a request, re-arming on a successful write
+    ///
+    /// `pdu` is the function code followed by the request data.
+    pub(crate) async fn serve<F>(&self, pdu: &[u8], request: F) -> ModbusResult<Vec<u8>>
+    where
+        F: Future<Output = ModbusResult<Vec<u8>>>,
+    {
+        let result = {
+            let _gate = self.gate.read().await;
+            request.await
+        };
+        if result.is_ok() && self.is_activity(pdu) {
+            self.feed();
+        }
+        result
+    }
+
+    /// Check whether a successful request counts as activity
+    fn is_activity(&self, pdu: &[u8]) -> bool {
+        let (table, quantity) = match pdu.first() {
+            Some(0x05) => (RegisterTable::Coil, Some(1)),
+            Some(0x06) => (RegisterTable::HoldingRegister, Some(1)),
+            Some(0x0F) => (RegisterTable::Coil, None),
+            Some(0x10) => (RegisterTable::HoldingRegister, None),
+            _ => return false,
+        };
+        let Some(heartbeat) = self.config.heartbeat else {
+            return true;
+        };
+        if pdu.len() < 5 || heartbeat.table != table {
+            return false;
+        }
+
+        let address = u16::from_be_bytes([pdu[1], pdu[2]]) as u32;
+        let quantity = quantity.unwrap_or(u16::from_be_bytes([pdu[3], pdu[4]]) as u32);
+        (address..address + quantity).contains(&(heartbeat.address as u32))
+    }
+
+    /// Watch for expiry until `shutdown` fires
+    pub(crate) async fn run<S: DataStore>(&self, store: &S, mut shutdown: broadcast::Receiver<()>) {
+        self.feed();
+        loop {
+            if self.is_expired() {
+                // Nothing to watch until the master is back
+                tokio::select! {
+                    _ = shutdown.recv() => break,
+                    _ = self.activity.notified() => continue,
+                }
+            }
+
+            let deadline = *self.lock_deadline();
+            tokio::select! {
+                _ = shutdown.recv() => break,
+                _ = self.activity.notified() => continue,
+                _ = tokio::time::sleep_until(deadline) => {}
+            }
+
+            let failsafe_applied = {
+                let _gate = self.gate.write().await;
+                {
+                    // Activity while waiting for the gate keeps the watchdog armed
+                    let deadline = self.lock_deadline();
+                    if Instant::now() < *deadline {
+                        continue;
+                    }
+                    self.expired.store(true, Ordering::Release);
+                }
+                warn!("Watchdog expired after {:?} without activity", self.config.timeout);
+                match store.write_batch(&self.config.failsafe) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        error!("Failed to apply watchdog failsafe values: {}", e);
+                        false
+                    }
+                }
+            };
+            let _ = self.events.send(WatchdogEvent::Expired { failsafe_applied });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::register_bank::ModbusRegisterBank;
+
+    #[tokio::test]
+    async fn test_expiry_and_heartbeat() {
+        let bank = ModbusRegisterBank::new();
+        bank.write_10(10, &[500, 600]).unwrap();
+        let config = WatchdogConfig::new(Duration::from_millis(50))
+            .with_heartbeat(DataAddress::new(RegisterTable::HoldingRegister, 99))
+            .with_failsafe(DataAddress::new(RegisterTable::HoldingRegister, 10), PointValue::Registers(vec![0, 0]))
+            .with_failsafe(DataAddress::new(RegisterTable::Coil, 0), PointValue::Bits(vec![false]));
+        let watchdog = std::sync::Arc::new(Watchdog::new(config));
+        let mut events = watchdog.subscribe();
+
+        let (shutdown, _) = broadcast::channel(1);
+        let task = {
+            let (watchdog, bank, shutdown) = (watchdog.clone(), bank.clone(), shutdown.subscribe());
+            tokio::spawn(async move { watchdog.run(&bank, shutdown).await })
+        };
+
+        // Writes elsewhere keep the master alive only when they cover the heartbeat
+        let setpoint = [0x06, 0x00, 0x0A, 0x01, 0xF4];
+        let heartbeat = [0x10, 0x00, 0x62, 0x00, 0x02, 0x04, 0x00, 0x01, 0x00, 0x02];
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            watchdog.serve(&heartbeat, async { Ok(Vec::new()) }).await.unwrap();
+            watchdog.serve(&setpoint, async { Ok(Vec::new()) }).await.unwrap();
+        }
+        assert!(!watchdog.is_expired());
+
+        assert_eq!(events.recv().await.unwrap(), WatchdogEvent::Expired { failsafe_applied: true });
+        assert!(watchdog.is_expired());
+        assert_eq!(bank.read_03(10, 2).unwrap(), vec![0, 0]);
+
+        watchdog.serve(&setpoint, async { Ok(Vec::new()) }).await.unwrap();
+        assert!(watchdog.is_expired());
+        watchdog.serve(&heartbeat, async { Ok(Vec::new()) }).await.unwrap();
+        assert_eq!(events.recv().await.unwrap(), WatchdogEvent::Rearmed);
+
+        shutdown.send(()).unwrap();
+        task.await.unwrap();
+    }
+}
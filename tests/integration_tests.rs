@@ -265,6 +265,37 @@ fn test_data_type_handling() {
     assert!((reconstructed_float - float_val).abs() < 0.001);
 }
 
+/// Test RTU frames against a register bank through an in-memory line
+#[tokio::test]
+async fn test_rtu_loopback_frames() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use voltage_modbus::mock::{Framing, MemoryServer};
+
+    let bank = Arc::new(ModbusRegisterBank::new());
+    bank.write_10(0, &[0x000A, 0x000B]).unwrap();
+    let (mut line, device) = tokio::io::duplex(256);
+    let _server = MemoryServer::spawn(device, bank.clone(), Framing::Rtu);
+
+    // Read holding registers (slave 1, addr 0, qty 2)
+    line.write_all(&[0x01, 0x03, 0x00, 0x00, 0x00, 0x02, 0xC4, 0x0B]).await.unwrap();
+    let mut response = [0u8; 9];
+    timeout(Duration::from_secs(1), line.read_exact(&mut response)).await.unwrap().unwrap();
+    assert_eq!(response[..7], [0x01, 0x03, 0x04, 0x00, 0x0A, 0x00, 0x0B]);
+    assert!(validate_crc(&response));
+
+    // A corrupted frame is ignored, the next one answered
+    line.write_all(&[0x01, 0x06, 0x00, 0x01, 0x00, 0x03, 0x00, 0x00]).await.unwrap();
+    let mut write = vec![0x01, 0x06, 0x00, 0x01, 0x00, 0x03];
+    let crc = calculate_crc16(&write);
+    write.extend_from_slice(&crc.to_le_bytes());
+    line.write_all(&write).await.unwrap();
+    let mut echo = [0u8; 8];
+    timeout(Duration::from_secs(1), line.read_exact(&mut echo)).await.unwrap().unwrap();
+    assert_eq!(echo.to_vec(), write);
+    assert_eq!(bank.read_03(1, 1).unwrap(), vec![0x0003]);
+}
+
 // Helper functions for tests
 
 /// Calculate CRC-16 for Modbus RTU
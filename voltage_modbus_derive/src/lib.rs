@@ -0,0 +1,403 @@
+//! # Voltage Modbus Derive
+//!
+//! Derive macros for the `voltage_modbus` library. Enable them through the
+//! `derive` feature of `voltage_modbus` instead of depending on this crate
+//! directly.
+//!
+//! ## `#[derive(ModbusRegisters)]`
+//!
+//! Maps a struct onto a contiguous block of registers and implements
+//! `voltage_modbus::codec::ModbusRegisters` for it.
+//!
+//! Field attributes, all optional:
+//!
+//! - **offset = N**: register offset within the block (defaults to right after the previous field)
+//! - **ty = "u32"**: raw register type when it differs from the field type; it
+//!   may not be narrower than the field, and integer values that do not fit
+//!   fail at runtime instead of wrapping
+//! - **order = "cdab"**: `abcd`, `cdab`, `badc` or `dcba`
+//! - **word_order / byte_order = "little"**: set the two halves of the order separately
+//! - **scale = 0.1**: engineering value = raw × scale, rounded for integer fields;
+//!   values that do not fit the raw type once scaled fail at runtime
+//! - **len = N**: register length of `String` fields
+//! - **skip**: not stored in registers, filled with `Default::default()`
+//!
+//! A struct-level `#[modbus(order = "...")]` sets the default order of all fields.
+//! Fields whose register ranges overlap are rejected at compile time.
+//!
+//! Author: Evan Liu <evan.liu@voltageenergy.com>
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitFloat, LitInt, LitStr, Type};
+
+/// Derive `voltage_modbus::codec::ModbusRegisters` for a struct with named fields
+#[proc_macro_derive(ModbusRegisters, attributes(modbus))]
+pub fn derive_modbus_registers(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Word and byte order of a field, `true` meaning little-endian
+#[derive(Clone, Copy, Default)]
+struct Order {
+    word_little: bool,
+    byte_little: bool,
+}
+
+impl Order {
+    fn parse(lit: &LitStr) -> syn::Result<Self> {
+        let (word_little, byte_little) = match lit.value().to_ascii_lowercase().as_str() {
+            "abcd" => (false, false),
+            "cdab" => (true, false),
+            "badc" => (false, true),
+            "dcba" => (true, true),
+            _ => return Err(syn::Error::new(lit.span(), "expected one of abcd, cdab, badc, dcba")),
+        };
+        Ok(Self { word_little, byte_little })
+    }
+
+    fn tokens(self) -> TokenStream2 {
+        let word = if self.word_little { quote!(Little) } else { quote!(Big) };
+        let byte = if self.byte_little { quote!(Little) } else { quote!(Big) };
+        quote! {
+            ::voltage_modbus::codec::RegisterOrder::new(
+                ::voltage_modbus::codec::WordOrder::#word,
+                ::voltage_modbus::codec::ByteOrder::#byte,
+            )
+        }
+    }
+}
+
+/// Parsed `#[modbus(...)]` attributes of one field
+#[derive(Default)]
+struct FieldAttrs {
+    offset: Option<u16>,
+    ty: Option<Type>,
+    order: Option<Order>,
+    word_little: Option<bool>,
+    byte_little: Option<bool>,
+    scale: Option<f64>,
+    len: Option<u16>,
+    skip: bool,
+}
+
+fn parse_endianness(lit: &LitStr) -> syn::Result<bool> {
+    match lit.value().to_ascii_lowercase().as_str() {
+        "big" => Ok(false),
+        "little" => Ok(true),
+        _ => Err(syn::Error::new(lit.span(), "expected \"big\" or \"little\"")),
+    }
+}
+
+fn parse_struct_order(attrs: &[syn::Attribute]) -> syn::Result<Order> {
+    let mut order = Order::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("modbus")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("order") {
+                order = Order::parse(&meta.value()?.parse()?)?;
+            } else if meta.path.is_ident("word_order") {
+                order.word_little = parse_endianness(&meta.value()?.parse()?)?;
+            } else if meta.path.is_ident("byte_order") {
+                order.byte_little = parse_endianness(&meta.value()?.parse()?)?;
+            } else {
+                return Err(meta.error("unsupported struct attribute, expected order, word_order or byte_order"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(order)
+}
+
+fn parse_field_attrs(attrs: &[syn::Attribute]) -> syn::Result<FieldAttrs> {
+    let mut parsed = FieldAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("modbus")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("offset") {
+                parsed.offset = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("ty") {
+                let value = meta.value()?;
+                parsed.ty = Some(if value.peek(LitStr) { value.parse::<LitStr>()?.parse()? } else { value.parse()? });
+            } else if meta.path.is_ident("order") {
+                parsed.order = Some(Order::parse(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("word_order") {
+                parsed.word_little = Some(parse_endianness(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("byte_order") {
+                parsed.byte_little = Some(parse_endianness(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("scale") {
+                let value = meta.value()?;
+                parsed.scale = Some(if value.peek(LitInt) {
+                    value.parse::<LitInt>()?.base10_parse()?
+                } else {
+                    value.parse::<LitFloat>()?.base10_parse()?
+                });
+            } else if meta.path.is_ident("len") {
+                parsed.len = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("skip") {
+                parsed.skip = true;
+            } else {
+                return Err(meta.error("unsupported field attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(parsed)
+}
+
+fn is_string(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "String"))
+}
+
+fn is_float(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.is_ident("f32") || path.path.is_ident("f64"))
+}
+
+/// Width in bits and register count of a primitive register type
+fn primitive(ty: &Type) -> Option<(u32, u16)> {
+    let Type::Path(path) = ty else { return None };
+    let ident = path.path.get_ident()?.to_string();
+    match ident.as_str() {
+        "u8" | "i8" => Some((8, 1)),
+        "u16" | "i16" => Some((16, 1)),
+        "u32" | "i32" | "f32" => Some((32, 2)),
+        "u64" | "i64" | "f64" => Some((64, 4)),
+        _ => None,
+    }
+}
+
+fn is_integer(ty: &Type) -> bool {
+    primitive(ty).is_some() && !is_float(ty)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "ModbusRegisters requires named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(name, "ModbusRegisters can only be derived for structs")),
+    };
+    let struct_order = parse_struct_order(&input.attrs)?;
+
+    let mut next_offset = quote!(0u16);
+    // Offsets and lengths known at expansion time, for the overlap check
+    let mut next_start = Some(0u16);
+    let mut spans: Vec<(u16, u16, &syn::Ident)> = Vec::new();
+    let mut ends = Vec::new();
+    let mut decoders = Vec::new();
+    let mut encoders = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let attrs = parse_field_attrs(&field.attrs)?;
+        if attrs.skip {
+            decoders.push(quote!(#ident: ::core::default::Default::default()));
+            continue;
+        }
+
+        let mut order = attrs.order.unwrap_or(struct_order);
+        if let Some(little) = attrs.word_little {
+            order.word_little = little;
+        }
+        if let Some(little) = attrs.byte_little {
+            order.byte_little = little;
+        }
+        let order = order.tokens();
+        let offset = match attrs.offset {
+            Some(offset) => quote!(#offset),
+            None => next_offset.clone(),
+        };
+        let start = attrs.offset.or(next_start);
+        let field_ty = &field.ty;
+
+        let (size, length) = if is_string(field_ty) {
+            let len = attrs.len.ok_or_else(|| syn::Error::new_spanned(field_ty, "String fields need #[modbus(len = N)]"))?;
+            decoders.push(quote! {
+                #ident: ::voltage_modbus::codec::decode_string(&registers[#offset as usize..(#offset + #len) as usize], #order)?
+            });
+            encoders.push(quote! {
+                let data = ::voltage_modbus::codec::encode_string(&self.#ident, #len, #order)?;
+                registers[#offset as usize..#offset as usize + data.len()].copy_from_slice(&data);
+            });
+            (quote!(#len), Some(len))
+        } else {
+            let raw_ty = attrs.ty.clone().unwrap_or_else(|| field_ty.clone());
+            let raw = quote!(<#raw_ty as ::voltage_modbus::codec::RegisterValue>);
+            let (decode, encode) = match (attrs.scale, attrs.ty.is_some()) {
+                (Some(scale), _) => {
+                    let encode_round = if is_float(&raw_ty) { quote!() } else { quote!(.round()) };
+                    let decode_round = if is_float(field_ty) { quote!() } else { quote!(.round()) };
+                    (
+                        quote!(((raw as f64) * #scale)#decode_round as #field_ty),
+                        quote! {{
+                            let scaled = ((self.#ident as f64) / #scale)#encode_round;
+                            if !scaled.is_finite() || scaled < <#raw_ty>::MIN as f64 || scaled > <#raw_ty>::MAX as f64 {
+                                return Err(::voltage_modbus::error::ModbusError::invalid_data(format!(
+                                    "{}.{} value {} out of range for {}",
+                                    stringify!(#name), stringify!(#ident), scaled, stringify!(#raw_ty)
+                                )));
+                            }
+                            scaled as #raw_ty
+                        }},
+                    )
+                }
+                (None, true) => {
+                    if let (Some((raw_bits, _)), Some((field_bits, _))) = (primitive(&raw_ty), primitive(field_ty)) {
+                        if raw_bits < field_bits {
+                            return Err(syn::Error::new_spanned(
+                                &raw_ty,
+                                format!(
+                                    "ty `{}` is narrower than field type `{}`; use a wider ty or a scale",
+                                    quote!(#raw_ty),
+                                    quote!(#field_ty)
+                                ),
+                            ));
+                        }
+                    }
+                    if is_integer(&raw_ty) && is_integer(field_ty) {
+                        let out_of_range = |target: &Type| quote! {
+                            |_| ::voltage_modbus::error::ModbusError::invalid_data(format!(
+                                "{}.{} value out of range for {}",
+                                stringify!(#name), stringify!(#ident), stringify!(#target)
+                            ))
+                        };
+                        let to_field = out_of_range(field_ty);
+                        let to_raw = out_of_range(&raw_ty);
+                        (
+                            quote!(<#field_ty as ::core::convert::TryFrom<#raw_ty>>::try_from(raw).map_err(#to_field)?),
+                            quote!(<#raw_ty as ::core::convert::TryFrom<#field_ty>>::try_from(self.#ident).map_err(#to_raw)?),
+                        )
+                    } else {
+                        (quote!(raw as #field_ty), quote!(self.#ident as #raw_ty))
+                    }
+                }
+                (None, false) => (quote!(raw), quote!(self.#ident)),
+            };
+            decoders.push(quote! {
+                #ident: {
+                    let raw = #raw::from_registers(&registers[#offset as usize..], #order)?;
+                    #decode
+                }
+            });
+            encoders.push(quote! {
+                let data = ::voltage_modbus::codec::RegisterValue::to_registers(&(#encode), #order);
+                registers[#offset as usize..#offset as usize + data.len()].copy_from_slice(&data);
+            });
+            (quote!(#raw::REGISTERS), primitive(&raw_ty).map(|(_, registers)| registers))
+        };
+
+        next_start = match (start, length) {
+            (Some(start), Some(length)) => {
+                let end = start.checked_add(length).ok_or_else(|| {
+                    syn::Error::new_spanned(ident, "field extends past the end of the register space")
+                })?;
+                if let Some((other_start, other_end, other)) =
+                    spans.iter().find(|(other_start, other_end, _)| start < *other_end && *other_start < end)
+                {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        format!(
+                            "field `{}` (registers {}..{}) overlaps field `{}` (registers {}..{})",
+                            ident, start, end, other, other_start, other_end
+                        ),
+                    ));
+                }
+                spans.push((start, end, ident));
+                Some(end)
+            }
+            _ => None,
+        };
+        ends.push(quote!(#offset + #size));
+        next_offset = quote!((#offset + #size));
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::voltage_modbus::codec::ModbusRegisters for #name #ty_generics #where_clause {
+            const REGISTER_COUNT: u16 = {
+                let mut count = 0u16;
+                #( if #ends > count { count = #ends; } )*
+                count
+            };
+
+            fn from_registers(registers: &[u16]) -> ::voltage_modbus::error::ModbusResult<Self> {
+                if registers.len() < <Self as ::voltage_modbus::codec::ModbusRegisters>::REGISTER_COUNT as usize {
+                    return Err(::voltage_modbus::error::ModbusError::invalid_data(format!(
+                        "{} needs {} registers, got {}",
+                        stringify!(#name),
+                        <Self as ::voltage_modbus::codec::ModbusRegisters>::REGISTER_COUNT,
+                        registers.len()
+                    )));
+                }
+                Ok(Self { #( #decoders, )* })
+            }
+
+            fn to_registers(&self) -> ::voltage_modbus::error::ModbusResult<Vec<u16>> {
+                let mut registers = vec![0u16; <Self as ::voltage_modbus::codec::ModbusRegisters>::REGISTER_COUNT as usize];
+                #( { #encoders } )*
+                Ok(registers)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_error(input: DeriveInput) -> String {
+        match expand(input) {
+            Ok(_) => panic!("expansion should fail"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rejects_overlapping_fields() {
+        let error = expand_error(syn::parse_quote! {
+            struct Block {
+                energy: u32,
+                #[modbus(offset = 1)]
+                power: u16,
+            }
+        });
+        assert_eq!(error, "field `power` (registers 1..2) overlaps field `energy` (registers 0..2)");
+
+        let error = expand_error(syn::parse_quote! {
+            struct Block {
+                #[modbus(offset = 4, len = 4)]
+                model: String,
+                #[modbus(offset = 2, ty = "u64")]
+                serial: u64,
+            }
+        });
+        assert!(error.contains("overlaps field `model`"));
+    }
+
+    #[test]
+    fn test_rejects_narrow_ty() {
+        let error = expand_error(syn::parse_quote! {
+            struct Block {
+                #[modbus(ty = "i16")]
+                temperature: i32,
+            }
+        });
+        assert!(error.contains("narrower than field type"));
+    }
+
+    #[test]
+    fn test_accepts_adjacent_fields_and_scaled_narrow_ty() {
+        let tokens = expand(syn::parse_quote! {
+            struct Block {
+                #[modbus(ty = "u16", scale = 0.1)]
+                voltage: f64,
+                energy: u32,
+                #[modbus(offset = 3)]
+                power: u16,
+            }
+        });
+        assert!(tokens.is_ok());
+    }
+}
@@ -0,0 +1,58 @@
+//! Round trips of derived register blocks
+
+use voltage_modbus::{ModbusRegisters, RegisterOrder, RegisterValue};
+
+#[derive(Debug, PartialEq, ModbusRegisters)]
+struct Meter {
+    #[modbus(ty = "u16", scale = 0.1)]
+    voltage: f32,
+    #[modbus(ty = "i16", scale = 0.1)]
+    current: i32,
+    #[modbus(ty = "u32", order = "cdab")]
+    energy: u16,
+    #[modbus(ty = "i32")]
+    offset: f32,
+}
+
+#[test]
+fn test_round_trip() {
+    let meter = Meter { voltage: 230.1, current: -125, energy: 42, offset: -3.0 };
+    let registers = meter.to_registers().unwrap();
+    assert_eq!(Meter::REGISTER_COUNT, 6);
+    assert_eq!(registers[..4], [2301, (-1250i16) as u16, 42, 0]);
+    assert_eq!(Meter::from_registers(&registers).unwrap(), meter);
+}
+
+#[test]
+fn test_scaled_integer_rounds() {
+    // 257 × 0.1 would truncate to 25
+    let registers = vec![0, 257, 0, 0, 0, 0];
+    assert_eq!(Meter::from_registers(&registers).unwrap().current, 26);
+    let registers = vec![0, (-257i16) as u16, 0, 0, 0, 0];
+    assert_eq!(Meter::from_registers(&registers).unwrap().current, -26);
+}
+
+#[test]
+fn test_wider_ty_out_of_range_is_an_error() {
+    let mut registers = vec![0u16; 6];
+    registers[2..4].copy_from_slice(&70_000u32.to_registers(RegisterOrder::CDAB));
+    assert!(Meter::from_registers(&registers).is_err());
+}
+
+#[test]
+fn test_scaled_out_of_range_is_an_error() {
+    let meter = Meter { voltage: 6600.0, current: 0, energy: 0, offset: 0.0 };
+    let error = meter.to_registers().unwrap_err();
+    assert!(error.to_string().contains("Meter.voltage value 66000 out of range for u16"), "{}", error);
+
+    let meter = Meter { voltage: f32::NAN, current: 0, energy: 0, offset: 0.0 };
+    assert!(meter.to_registers().is_err());
+    let meter = Meter { voltage: -0.1, current: 0, energy: 0, offset: 0.0 };
+    assert!(meter.to_registers().is_err());
+    let meter = Meter { voltage: 0.0, current: 3300, energy: 0, offset: 0.0 };
+    assert!(meter.to_registers().is_err());
+
+    // The limits themselves still encode
+    let meter = Meter { voltage: 6553.5, current: -3276, energy: 0, offset: 0.0 };
+    assert_eq!(meter.to_registers().unwrap()[..2], [u16::MAX, (-32760i16) as u16]);
+}